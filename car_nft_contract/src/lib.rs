@@ -1,11 +1,31 @@
 use cosmwasm_std::{
     entry_point, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
-    SubMsg, Uint128, WasmMsg, BankMsg, Reply,
+    SubMsg, Uint128, WasmMsg, BankMsg, Reply, Coin, Storage, Order, BlockInfo, Timestamp, OverflowError,
+    OverflowOperation,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cw_storage_plus::{Item, Map};
-use car_types::{PartType, PartStats, PartData};
+use cw_storage_plus::{Bound, Item, Map};
+use car_types::{randomness, normalize_gateway, normalize_uri, resolve_uri, Metadata, NftInfoResponse, PartOwnerResponse, PartType, PartStats, PartData, Rarity, StatBounds, Trait};
+use sha2::{Digest, Sha256};
+
+// Bloques que tiene un jugador para revelar su secreto después de CommitMint
+pub const DEFAULT_REVEAL_WINDOW: u64 = 100;
+
+// Cantidad máxima de carros por página en GetStaleCars
+pub const MAX_STALE_CARS_LIMIT: u32 = 30;
+
+// Largo máximo del nombre de un carro, en caracteres
+pub const MAX_CAR_NAME_LENGTH: usize = 32;
+
+// Calcomanías que se pueden aplicar a un mismo carro
+pub const MAX_DECALS_PER_CAR: usize = 5;
+
+// Regalía máxima sobre las reventas, en porcentaje
+pub const MAX_ROYALTY_PERCENT: u8 = 25;
+
+// Stats del carro en el orden en que se acumulan; son los nombres que devuelve `PartType::stat_names`
+pub const CAR_STAT_NAMES: [&str; 6] = ["speed", "acceleration", "handling", "drift_factor", "turn_factor", "max_speed"];
 
 // Estructuras principales
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -13,6 +33,137 @@ pub struct CarComposition {
     pub part_ids: Vec<u64>,
     pub car_image_uri: String,
     pub slot_occupied: Vec<bool>,
+    #[serde(default)]
+    pub model_id: u64, // 0 = chasis genérico
+    #[serde(default)]
+    pub stale_image: bool, // las partes cambiaron y la imagen espera un nuevo render
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub cosmetic_id: u64, // pintura o livery aplicada; 0 = pintura de fábrica
+    #[serde(default)]
+    pub decal_ids: Vec<u64>,
+}
+
+// Cosméticos registrados por el admin; solo cambian la apariencia, nunca los stats
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CosmeticKind {
+    Paint,
+    Livery,
+}
+
+impl std::fmt::Display for CosmeticKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CosmeticKind::Paint => write!(f, "paint"),
+            CosmeticKind::Livery => write!(f, "livery"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CosmeticItem {
+    pub name: String,
+    pub kind: CosmeticKind,
+    pub image_uri: String,
+}
+
+// Unidad en la que se cobra y se mide un alquiler
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RentalUnit {
+    Blocks,
+    Hours,
+}
+
+// Oferta de alquiler publicada por el dueño: precio por unidad y duración máxima
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RentalListing {
+    pub owner: Addr,
+    pub price: Uint128,
+    pub unit: RentalUnit,
+    pub max_units: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RentalExpiry {
+    AtHeight(u64),
+    AtTime(Timestamp),
+}
+
+impl RentalExpiry {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        match self {
+            RentalExpiry::AtHeight(height) => block.height >= *height,
+            RentalExpiry::AtTime(time) => block.time >= *time,
+        }
+    }
+}
+
+// Derecho de uso al estilo ERC-4907: `user` puede correr con el carro hasta `expires`,
+// pero no transferirlo, venderlo ni cambiar sus partes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CarUser {
+    pub user: Addr,
+    pub expires: RentalExpiry,
+}
+
+// Regalía CW2981 que cobra la colección en cada reventa de un carro
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyConfig {
+    pub payment_address: Addr,
+    pub percent: u8,
+}
+
+// Calcomanía: token propio del jugador que se aplica sobre uno de sus carros
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Decal {
+    pub owner: Addr,
+    pub name: String,
+    pub image_uri: String,
+    pub car_id: u64, // 0 indica que no está aplicada
+}
+
+// Stats de un carro, usados como base y como tope en los modelos
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CarStats {
+    pub speed: u8,
+    pub acceleration: u8,
+    pub handling: u8,
+    pub drift_factor: u8,
+    pub turn_factor: u8,
+    pub max_speed: u8,
+}
+
+// Modelo de carro registrado por el admin (Drift, Muscle, Rally...).
+// Las listas vacías de tipos o rarezas permiten cualquier parte.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CarModel {
+    pub name: String,
+    pub base_stats: CarStats,
+    pub stat_caps: CarStats,
+    pub allowed_part_types: Vec<PartType>,
+    pub allowed_rarities: Vec<Rarity>,
+}
+
+impl CarModel {
+    // Chasis genérico (model_id 0): sin stats base, sin topes y acepta cualquier parte
+    pub fn generic() -> Self {
+        Self {
+            name: "Generic".to_string(),
+            base_stats: CarStats { speed: 0, acceleration: 0, handling: 0, drift_factor: 0, turn_factor: 0, max_speed: 0 },
+            stat_caps: CarStats { speed: u8::MAX, acceleration: u8::MAX, handling: u8::MAX, drift_factor: u8::MAX, turn_factor: u8::MAX, max_speed: u8::MAX },
+            allowed_part_types: vec![],
+            allowed_rarities: vec![],
+        }
+    }
+
+    pub fn accepts(&self, part_type: &PartType, rarity: &Rarity) -> bool {
+        (self.allowed_part_types.is_empty() || self.allowed_part_types.contains(part_type))
+            && (self.allowed_rarities.is_empty() || self.allowed_rarities.contains(rarity))
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -23,29 +174,139 @@ pub struct PartMetadata {
     pub slot_index: u8,
 }
 
+// Slot de un carro: tipo de parte que acepta y si es obligatorio para mintear.
+// El índice del slot en el esquema es el índice en CarComposition.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SlotDef {
+    pub part_type: PartType,
+    pub required: bool,
+}
+
+// Esquema de slots original: motor, transmisión y ruedas
+pub fn default_slot_schema() -> Vec<SlotDef> {
+    [PartType::Engine, PartType::Transmission, PartType::Wheels]
+        .into_iter()
+        .map(|part_type| SlotDef { part_type, required: true })
+        .collect()
+}
+
+// Esquema de firma de la clave del backend del juego
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureScheme {
+    Secp256k1,
+    Ed25519,
+}
+
+// Forma en que se generan los stats de las partes al mintear un carro. El admin elige el modo activo.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsMode {
+    // El backend firma (minter, nonce, parts_data) con una clave de confianza
+    Signed {
+        scheme: SignatureScheme,
+        public_key: Binary,
+    },
+    // El jugador se compromete con un secreto y los stats salen de la semilla al revelarlo
+    CommitReveal {
+        reveal_window: u64,
+    },
+}
+
+// Prueba que acompaña a MintCar según el modo activo
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsProof {
+    Signature {
+        nonce: u64,
+        signature: Binary,
+    },
+    Reveal {
+        secret: String,
+    },
+}
+
+// Commit pendiente de un jugador: sha256(secret) en hex, el bloque en que se hizo y la semilla
+// que publica el servidor de juego después del commit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintCommit {
+    pub commitment: String,
+    pub height: u64,
+    #[serde(default)]
+    pub server_seed: Option<String>,
+}
+
+// Contenido que firma el backend en el modo Signed. Se firma sha256 del JSON de esta estructura.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedStats {
+    pub minter: String,
+    pub nonce: u64,
+    pub parts_data: Vec<PartData>,
+}
+
 // Estado del contrato
 pub struct CarNftContract<'a> {
     pub cars: Map<'a, u64, CarComposition>,
-    pub car_conditions: Map<'a, u64, u8>,
     pub workshop_contract: Item<'a, Addr>,
     pub leaderboard_contract: Item<'a, Addr>,
     pub car_part_contract: Item<'a, Addr>,
     pub mint_price: Item<'a, Uint128>,
     pub current_car_id: Item<'a, u64>,
     pub owner_cars: Map<'a, (Addr, u64), bool>,
+    pub admin: Item<'a, Addr>,
+    pub stats_mode: Item<'a, StatsMode>,
+    pub used_nonces: Map<'a, (&'a Addr, u64), bool>,
+    pub mint_commits: Map<'a, &'a Addr, MintCommit>,
+    pub salvage_value: Item<'a, Uint128>,
+    pub slot_schema: Item<'a, Vec<SlotDef>>,
+    pub car_models: Map<'a, u64, CarModel>,
+    pub image_gateway: Item<'a, String>,
+    pub renderer_contract: Item<'a, Addr>,
+    pub stale_cars: Map<'a, u64, bool>, // car_id => pendiente de un nuevo render
+    pub unique_car_names: Item<'a, bool>,
+    pub car_names: Map<'a, (&'a str, u64), bool>, // (nombre en minúsculas, car_id)
+    pub cosmetics: Map<'a, u64, CosmeticItem>,
+    pub decals: Map<'a, u64, Decal>,
+    pub owner_decals: Map<'a, (Addr, u64), bool>,
+    pub current_decal_id: Item<'a, u64>, // último ID de calcomanía minteado
+    pub rental_listings: Map<'a, u64, RentalListing>,
+    pub car_users: Map<'a, u64, CarUser>, // alquileres; los vencidos se ignoran
+    pub rental_fee: Item<'a, u8>, // porcentaje de cada alquiler que queda en el contrato
+    pub royalties: Item<'a, RoyaltyConfig>, // sin configurar no se cobran regalías
+    pub game_server: Item<'a, Addr>, // publica su semilla para cada commit de minteo
 }
 
 impl<'a> Clone for CarNftContract<'a> {
     fn clone(&self) -> Self {
         Self {
             cars: Map::new("cars"),
-            car_conditions: Map::new("car_conditions"),
             workshop_contract: Item::new("workshop_contract"),
             leaderboard_contract: Item::new("leaderboard_contract"),
             car_part_contract: Item::new("car_part_contract"),
             mint_price: Item::new("mint_price"),
             current_car_id: Item::new("current_car_id"),
             owner_cars: Map::new("owner_cars"),
+            admin: Item::new("admin"),
+            stats_mode: Item::new("stats_mode"),
+            used_nonces: Map::new("used_nonces"),
+            mint_commits: Map::new("mint_commits"),
+            salvage_value: Item::new("salvage_value"),
+            slot_schema: Item::new("slot_schema"),
+            car_models: Map::new("car_models"),
+            image_gateway: Item::new("image_gateway"),
+            renderer_contract: Item::new("renderer_contract"),
+            stale_cars: Map::new("stale_cars"),
+            unique_car_names: Item::new("unique_car_names"),
+            car_names: Map::new("car_names"),
+            cosmetics: Map::new("cosmetics"),
+            decals: Map::new("decals"),
+            owner_decals: Map::new("owner_decals"),
+            current_decal_id: Item::new("current_decal_id"),
+            rental_listings: Map::new("rental_listings"),
+            car_users: Map::new("car_users"),
+            rental_fee: Item::new("rental_fee"),
+            royalties: Item::new("royalties"),
+            game_server: Item::new("game_server"),
         }
     }
 }
@@ -54,13 +315,33 @@ impl<'a> Default for CarNftContract<'a> {
     fn default() -> Self {
         Self {
             cars: Map::new("cars"),
-            car_conditions: Map::new("car_conditions"),
             workshop_contract: Item::new("workshop_contract"),
             leaderboard_contract: Item::new("leaderboard_contract"),
             car_part_contract: Item::new("car_part_contract"),
             mint_price: Item::new("mint_price"),
             current_car_id: Item::new("current_car_id"),
             owner_cars: Map::new("owner_cars"),
+            admin: Item::new("admin"),
+            stats_mode: Item::new("stats_mode"),
+            used_nonces: Map::new("used_nonces"),
+            mint_commits: Map::new("mint_commits"),
+            salvage_value: Item::new("salvage_value"),
+            slot_schema: Item::new("slot_schema"),
+            car_models: Map::new("car_models"),
+            image_gateway: Item::new("image_gateway"),
+            renderer_contract: Item::new("renderer_contract"),
+            stale_cars: Map::new("stale_cars"),
+            unique_car_names: Item::new("unique_car_names"),
+            car_names: Map::new("car_names"),
+            cosmetics: Map::new("cosmetics"),
+            decals: Map::new("decals"),
+            owner_decals: Map::new("owner_decals"),
+            current_decal_id: Item::new("current_decal_id"),
+            rental_listings: Map::new("rental_listings"),
+            car_users: Map::new("car_users"),
+            rental_fee: Item::new("rental_fee"),
+            royalties: Item::new("royalties"),
+            game_server: Item::new("game_server"),
         }
     }
 }
@@ -70,6 +351,18 @@ impl<'a> Default for CarNftContract<'a> {
 pub struct InstantiateMsg {
     pub car_part_contract: String,
     pub mint_price: Uint128,
+    #[serde(default)]
+    pub stats_mode: Option<StatsMode>,
+}
+
+// Mensaje de migración. Permite ampliar el esquema de slots al subir un código con tipos de parte nuevos.
+// Los contratos instanciados antes de que existiera el admin deben indicarlo al migrar.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    #[serde(default)]
+    pub slot_schema: Option<Vec<SlotDef>>,
+    #[serde(default)]
+    pub admin: Option<String>,
 }
 
 // Mensajes de ejecución
@@ -79,6 +372,22 @@ pub enum ExecuteMsg {
     MintCar {
         car_image_uri: String,
         parts_data: Vec<PartData>,
+        proof: StatsProof,
+        #[serde(default)]
+        model_id: u64,
+    },
+    CommitMint {
+        commitment: String,
+    },
+    SetGameServer {
+        address: String,
+    },
+    SubmitMintSeed {
+        minter: String,
+        seed: String,
+    },
+    SetStatsMode {
+        mode: StatsMode,
     },
     UnequipPart {
         car_id: u64,
@@ -117,6 +426,148 @@ pub enum ExecuteMsg {
         part_id: u64,
         car_id: u64,
     },
+    BurnCar {
+        car_id: u64,
+        burn_parts: bool,
+    },
+    SetSalvageValue {
+        amount: Uint128,
+    },
+    DegradeCar {
+        car_id: u64,
+        wear: u8,
+    },
+    RepairCar {
+        car_id: u64,
+    },
+    RepairPart {
+        part_id: u64,
+    },
+    SetSlotSchema {
+        slots: Vec<SlotDef>,
+    },
+    SetCarModel {
+        model_id: u64,
+        model: CarModel,
+    },
+    SetImageGateway {
+        gateway: Option<String>,
+    },
+    SetRendererContract {
+        address: String,
+    },
+    SetCarImage {
+        car_id: u64,
+        car_image_uri: String,
+    },
+    SetCarName {
+        car_id: u64,
+        name: Option<String>,
+    },
+    SetUniqueCarNames {
+        unique: bool,
+    },
+    SetCosmetic {
+        cosmetic_id: u64,
+        cosmetic: CosmeticItem,
+    },
+    SetCarCosmetic {
+        car_id: u64,
+        cosmetic_id: u64,
+    },
+    MintDecal {
+        to: String,
+        name: String,
+        image_uri: String,
+    },
+    ApplyDecal {
+        car_id: u64,
+        decal_id: u64,
+    },
+    RemoveDecal {
+        decal_id: u64,
+    },
+    ListCarForRent {
+        car_id: u64,
+        price: Uint128,
+        unit: RentalUnit,
+        max_units: u64,
+    },
+    CancelCarRental {
+        car_id: u64,
+    },
+    RentCar {
+        car_id: u64,
+        units: u64,
+    },
+    SetRentalFee {
+        percent: u8,
+    },
+    SetRoyalties {
+        payment_address: String,
+        percent: u8,
+    },
+    TransferCar {
+        car_id: u64,
+        recipient: String,
+    },
+    SendCar {
+        car_id: u64,
+        contract: String,
+        msg: Binary,
+    },
+}
+
+// Mensajes al contrato de partes; este contrato debe estar configurado como su car_contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum PartExecuteMsg {
+    Mint {
+        to: String,
+        part_type: PartType,
+        stat1: u8,
+        stat2: u8,
+        stat3: u8,
+        image_uri: String,
+        car_id: u64,
+        rarity: Rarity,
+    },
+    SetEquippedState {
+        part_id: u64,
+        car_id: u64,
+    },
+    BurnCarParts {
+        owner: String,
+        car_id: u64,
+        part_ids: Vec<u64>,
+    },
+    DegradeParts {
+        part_ids: Vec<u64>,
+        wear: u8,
+    },
+    RepairParts {
+        part_ids: Vec<u64>,
+    },
+    TransferCarParts {
+        from: String,
+        to: String,
+        car_id: u64,
+        part_ids: Vec<u64>,
+    },
+}
+
+// Aviso CW721 al contrato que recibe un carro con SendCar
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
 }
 
 // Mensajes de consulta
@@ -143,9 +594,62 @@ pub enum QueryMsg {
     GetPartType {
         part_id: u64,
     },
+    GetPartOwner {
+        part_id: u64,
+    },
     GetAllCarMetadata {
         owner: String,
     },
+    GetStatsMode {},
+    GetGameServer {},
+    GetMintCommit {
+        owner: String,
+    },
+    GetRarityBounds {
+        rarity: Rarity,
+    },
+    GetSalvageValue {},
+    GetPartDurability {
+        part_id: u64,
+    },
+    GetSlotSchema {},
+    GetCarModel {
+        model_id: u64,
+    },
+    GetCarModels {},
+    NftInfo {
+        token_id: String,
+        #[serde(default)]
+        resolve_ipfs: bool,
+    },
+    GetImageGateway {},
+    GetStaleCars {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    GetCosmetic {
+        cosmetic_id: u64,
+    },
+    GetCosmetics {},
+    GetDecal {
+        decal_id: u64,
+    },
+    GetOwnerDecals {
+        owner: String,
+    },
+    GetRentalListing {
+        car_id: u64,
+    },
+    UserOf {
+        car_id: u64,
+    },
+    GetRentalFee {},
+    // CW2981
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+    CheckRoyalties {},
 }
 
 // Respuestas de consulta
@@ -154,6 +658,17 @@ pub struct OwnerCarsResponse {
     pub car_ids: Vec<u64>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CheckRoyaltiesResponse {
+    pub royalty_payments: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CompactCarStats {
     pub image_uri: String,
@@ -166,9 +681,27 @@ pub struct CompactCarStats {
     pub condition: u8,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CarModelInfo {
+    pub model_id: u64,
+    pub model: CarModel,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CarModelsResponse {
+    pub models: Vec<CarModelInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CosmeticInfo {
+    pub cosmetic_id: u64,
+    pub cosmetic: CosmeticItem,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct FullCarMetadata {
     pub car_id: u64,
+    pub model_id: u64,
     pub car_image_uri: String,
     pub parts: Vec<PartMetadata>,
     pub total_stats: CompactCarStats,
@@ -199,6 +732,14 @@ pub fn instantiate(
     let car_part_addr = deps.api.addr_validate(&msg.car_part_contract)?;
     contract.car_part_contract.save(deps.storage, &car_part_addr)?;
     
+    // Quien instancia el contrato queda como admin; por defecto los stats salen de commit-reveal
+    contract.admin.save(deps.storage, &info.sender)?;
+    let stats_mode = msg.stats_mode.unwrap_or(StatsMode::CommitReveal {
+        reveal_window: DEFAULT_REVEAL_WINDOW,
+    });
+    validate_stats_mode(&stats_mode)?;
+    contract.stats_mode.save(deps.storage, &stats_mode)?;
+    
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("owner", info.sender)
@@ -216,8 +757,20 @@ pub fn execute(
     let contract = CarNftContract::default();
     
     match msg {
-        ExecuteMsg::MintCar { car_image_uri, parts_data } => {
-            execute_mint_car(deps, env, info, contract, car_image_uri, parts_data)
+        ExecuteMsg::MintCar { car_image_uri, parts_data, proof, model_id } => {
+            execute_mint_car(deps, env, info, contract, car_image_uri, parts_data, proof, model_id)
+        },
+        ExecuteMsg::CommitMint { commitment } => {
+            execute_commit_mint(deps, env, info, contract, commitment)
+        },
+        ExecuteMsg::SetGameServer { address } => {
+            execute_set_game_server(deps, env, info, contract, address)
+        },
+        ExecuteMsg::SubmitMintSeed { minter, seed } => {
+            execute_submit_mint_seed(deps, env, info, contract, minter, seed)
+        },
+        ExecuteMsg::SetStatsMode { mode } => {
+            execute_set_stats_mode(deps, env, info, contract, mode)
         },
         ExecuteMsg::UnequipPart { car_id, part_id } => {
             execute_unequip_part(deps, env, info, contract, car_id, part_id)
@@ -246,13 +799,87 @@ pub fn execute(
         ExecuteMsg::SetEquippedState { part_id, car_id } => {
             execute_set_equipped_state(deps, env, info, contract, part_id, car_id)
         },
+        ExecuteMsg::BurnCar { car_id, burn_parts } => {
+            execute_burn_car(deps, env, info, contract, car_id, burn_parts)
+        },
+        ExecuteMsg::SetSalvageValue { amount } => {
+            execute_set_salvage_value(deps, env, info, contract, amount)
+        },
+        ExecuteMsg::DegradeCar { car_id, wear } => {
+            execute_degrade_car(deps, env, info, contract, car_id, wear)
+        },
+        ExecuteMsg::RepairCar { car_id } => {
+            execute_repair_car(deps, env, info, contract, car_id)
+        },
+        ExecuteMsg::RepairPart { part_id } => {
+            execute_repair_part(deps, env, info, contract, part_id)
+        },
+        ExecuteMsg::SetSlotSchema { slots } => {
+            execute_set_slot_schema(deps, env, info, contract, slots)
+        },
+        ExecuteMsg::SetCarModel { model_id, model } => {
+            execute_set_car_model(deps, env, info, contract, model_id, model)
+        },
+        ExecuteMsg::SetImageGateway { gateway } => {
+            execute_set_image_gateway(deps, env, info, contract, gateway)
+        },
+        ExecuteMsg::SetRendererContract { address } => {
+            execute_set_renderer_contract(deps, env, info, contract, address)
+        },
+        ExecuteMsg::SetCarImage { car_id, car_image_uri } => {
+            execute_set_car_image(deps, env, info, contract, car_id, car_image_uri)
+        },
+        ExecuteMsg::SetCarName { car_id, name } => {
+            execute_set_car_name(deps, env, info, contract, car_id, name)
+        },
+        ExecuteMsg::SetUniqueCarNames { unique } => {
+            execute_set_unique_car_names(deps, env, info, contract, unique)
+        },
+        ExecuteMsg::SetCosmetic { cosmetic_id, cosmetic } => {
+            execute_set_cosmetic(deps, env, info, contract, cosmetic_id, cosmetic)
+        },
+        ExecuteMsg::SetCarCosmetic { car_id, cosmetic_id } => {
+            execute_set_car_cosmetic(deps, env, info, contract, car_id, cosmetic_id)
+        },
+        ExecuteMsg::MintDecal { to, name, image_uri } => {
+            execute_mint_decal(deps, env, info, contract, to, name, image_uri)
+        },
+        ExecuteMsg::ApplyDecal { car_id, decal_id } => {
+            execute_apply_decal(deps, env, info, contract, car_id, decal_id)
+        },
+        ExecuteMsg::RemoveDecal { decal_id } => {
+            execute_remove_decal(deps, env, info, contract, decal_id)
+        },
+        ExecuteMsg::ListCarForRent { car_id, price, unit, max_units } => {
+            execute_list_car_for_rent(deps, env, info, contract, car_id, price, unit, max_units)
+        },
+        ExecuteMsg::CancelCarRental { car_id } => {
+            execute_cancel_car_rental(deps, env, info, contract, car_id)
+        },
+        ExecuteMsg::RentCar { car_id, units } => {
+            execute_rent_car(deps, env, info, contract, car_id, units)
+        },
+        ExecuteMsg::SetRentalFee { percent } => {
+            execute_set_rental_fee(deps, env, info, contract, percent)
+        },
+        ExecuteMsg::SetRoyalties { payment_address, percent } => {
+            execute_set_royalties(deps, info, contract, payment_address, percent)
+        },
+        ExecuteMsg::TransferCar { car_id, recipient } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            let response = transfer_car(deps, &env, &info, &contract, car_id, &recipient)?;
+            Ok(response.add_attribute("method", "transfer_car"))
+        },
+        ExecuteMsg::SendCar { car_id, contract: recipient, msg } => {
+            execute_send_car(deps, env, info, contract, car_id, recipient, msg)
+        },
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(
     deps: Deps,
-    _env: Env,
+    env: Env,
     msg: QueryMsg,
 ) -> StdResult<Binary> {
     let contract = CarNftContract::default();
@@ -282,45 +909,139 @@ pub fn query(
         QueryMsg::GetPartType { part_id } => {
             to_json_binary(&query_part_type(deps, contract, part_id)?)
         },
+        QueryMsg::GetPartOwner { part_id } => {
+            to_json_binary(&query_part_owner(deps, contract, part_id)?)
+        },
         QueryMsg::GetAllCarMetadata { owner } => {
             to_json_binary(&query_all_car_metadata(deps, contract, owner)?)
         },
-    }
-}
-
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
-    let contract = CarNftContract::default();
-    
-    // Obtener el ID de la parte del atributo del evento
-    let part_id = msg.result
-        .into_result()
-        .map_err(|_| StdError::generic_err("Error al procesar la respuesta del minteo"))?
-        .events
-        .iter()
-        .find(|e| e.ty == "wasm")
-        .ok_or_else(|| StdError::generic_err("No se encontró el evento wasm"))?
-        .attributes
-        .iter()
+        QueryMsg::GetStatsMode {} => {
+            to_json_binary(&contract.stats_mode.load(deps.storage)?)
+        },
+        QueryMsg::GetGameServer {} => {
+            to_json_binary(&contract.game_server.may_load(deps.storage)?)
+        },
+        QueryMsg::GetMintCommit { owner } => {
+            to_json_binary(&query_mint_commit(deps, contract, owner)?)
+        },
+        QueryMsg::GetRarityBounds { rarity } => {
+            to_json_binary(&query_rarity_bounds(deps, contract, rarity)?)
+        },
+        QueryMsg::GetSalvageValue {} => {
+            to_json_binary(&contract.salvage_value.may_load(deps.storage)?.unwrap_or_default())
+        },
+        QueryMsg::GetPartDurability { part_id } => {
+            let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+            to_json_binary(&deps.querier.query_wasm_smart::<u8>(car_part_contract, &QueryMsg::GetPartDurability { part_id })?)
+        },
+        QueryMsg::GetSlotSchema {} => {
+            to_json_binary(&load_slot_schema(deps.storage, &contract)?)
+        },
+        QueryMsg::GetCarModel { model_id } => {
+            to_json_binary(&load_car_model(deps.storage, &contract, model_id)?)
+        },
+        QueryMsg::GetCarModels {} => {
+            to_json_binary(&query_car_models(deps, contract)?)
+        },
+        QueryMsg::NftInfo { token_id, resolve_ipfs } => {
+            to_json_binary(&query_nft_info(deps, contract, token_id, resolve_ipfs)?)
+        },
+        QueryMsg::GetImageGateway {} => {
+            to_json_binary(&contract.image_gateway.may_load(deps.storage)?)
+        },
+        QueryMsg::GetStaleCars { start_after, limit } => {
+            to_json_binary(&query_stale_cars(deps, contract, start_after, limit)?)
+        },
+        QueryMsg::GetCosmetic { cosmetic_id } => {
+            to_json_binary(&contract.cosmetics.load(deps.storage, cosmetic_id)?)
+        },
+        QueryMsg::GetCosmetics {} => {
+            to_json_binary(&query_cosmetics(deps, contract)?)
+        },
+        QueryMsg::GetDecal { decal_id } => {
+            to_json_binary(&contract.decals.load(deps.storage, decal_id)?)
+        },
+        QueryMsg::GetOwnerDecals { owner } => {
+            to_json_binary(&query_owner_decals(deps, contract, owner)?)
+        },
+        QueryMsg::GetRentalListing { car_id } => {
+            to_json_binary(&contract.rental_listings.may_load(deps.storage, car_id)?)
+        },
+        QueryMsg::UserOf { car_id } => {
+            to_json_binary(&active_car_user(deps.storage, &contract, &env.block, car_id)?)
+        },
+        QueryMsg::GetRentalFee {} => {
+            to_json_binary(&contract.rental_fee.may_load(deps.storage)?.unwrap_or_default())
+        },
+        QueryMsg::RoyaltyInfo { token_id, sale_price } => {
+            to_json_binary(&query_royalty_info(deps, contract, token_id, sale_price)?)
+        },
+        QueryMsg::CheckRoyalties {} => {
+            let royalty_payments = contract.royalties.may_load(deps.storage)?
+                .is_some_and(|royalties| royalties.percent > 0);
+            to_json_binary(&CheckRoyaltiesResponse { royalty_payments })
+        },
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let contract = CarNftContract::default();
+
+    // Sin admin guardado todas las funciones de administración fallarían
+    match msg.admin {
+        Some(admin) => {
+            let admin = deps.api.addr_validate(&admin)?;
+            contract.admin.save(deps.storage, &admin)?;
+        },
+        None if !contract.admin.exists(deps.storage) => {
+            return Err(StdError::generic_err("El contrato no tiene admin; indícalo en el mensaje de migración"));
+        },
+        None => {},
+    }
+
+    // Ampliar el esquema de slots con los tipos de parte del código nuevo
+    if let Some(slots) = msg.slot_schema {
+        save_slot_schema(deps.storage, &contract, slots)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("slots", load_slot_schema(deps.storage, &contract)?.len().to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    let contract = CarNftContract::default();
+    
+    // Obtener el ID de la parte del atributo del evento
+    let part_id = msg.result
+        .into_result()
+        .map_err(|_| StdError::generic_err("Error al procesar la respuesta del minteo"))?
+        .events
+        .iter()
+        .find(|e| e.ty == "wasm")
+        .ok_or_else(|| StdError::generic_err("No se encontró el evento wasm"))?
+        .attributes
+        .iter()
         .find(|a| a.key == "part_id")
         .ok_or_else(|| StdError::generic_err("No se encontró el ID de la parte"))?
         .value
         .parse::<u64>()
         .map_err(|_| StdError::generic_err("Error al parsear el ID de la parte"))?;
     
-    // El ID de respuesta corresponde al índice + 1 de la parte en el array original
-    let part_index = (msg.id - 1) as usize;
+    // El ID de respuesta corresponde al índice + 1 del slot de la parte
+    let slot_index = (msg.id - 1) as usize;
     
     // Obtener el último carro minteado
     let car_id = contract.current_car_id.load(deps.storage)? - 1;
     let mut car = contract.cars.load(deps.storage, car_id)?;
     
     // Actualizar el ID de la parte en el slot correspondiente
-    let slot_index = if part_index == 0 { 0 } // Engine
-        else if part_index == 1 { 1 } // Transmission
-        else { 2 }; // Wheels
-    
-    car.part_ids[slot_index] = part_id;
+    let slot = car.part_ids
+        .get_mut(slot_index)
+        .ok_or_else(|| StdError::generic_err("Índice de slot inválido en la respuesta del minteo"))?;
+    *slot = part_id;
     contract.cars.save(deps.storage, car_id, &car)?;
     
     Ok(Response::new()
@@ -331,69 +1052,76 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
 }
 
 // Funciones de ejecución
+#[allow(clippy::too_many_arguments)]
 fn execute_mint_car(
-    deps: DepsMut,
-    _env: Env,
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     contract: CarNftContract,
     car_image_uri: String,
     parts_data: Vec<PartData>,
+    proof: StatsProof,
+    model_id: u64,
 ) -> StdResult<Response> {
-    // Verificar el pago
-    let mint_price = contract.mint_price.load(deps.storage)?;
-    let payment = info.funds.iter()
-        .find(|coin| coin.denom == "uxion")
-        .ok_or_else(|| StdError::generic_err("No se encontró el pago en uxion"))?;
-    
-    if payment.amount < mint_price {
-        return Err(StdError::generic_err("Pago insuficiente"));
-    }
-
     // Verificar la cantidad de partes
-    if parts_data.len() > 3 {
+    let schema = load_slot_schema(deps.storage, &contract)?;
+    let model = load_car_model(deps.storage, &contract, model_id)?;
+    if parts_data.len() > schema.len() {
         return Err(StdError::generic_err("Demasiadas partes"));
     }
 
+    // Los stats nunca los elige el jugador: o vienen firmados por el backend o salen de la semilla.
+    // En modo commit-reveal el pago se hizo en CommitMint.
+    let parts_data = match (contract.stats_mode.load(deps.storage)?, proof) {
+        (StatsMode::Signed { scheme, public_key }, StatsProof::Signature { nonce, signature }) => {
+            check_payment(deps.as_ref(), &contract, &info)?;
+            verify_signed_stats(deps.branch(), &contract, &info.sender, nonce, &parts_data, &scheme, &public_key, &signature)?;
+            parts_data
+        },
+        (StatsMode::CommitReveal { reveal_window }, StatsProof::Reveal { secret }) => {
+            let seed = reveal_mint_seed(deps.branch(), &env, &contract, &info.sender, &secret, reveal_window)?;
+            roll_parts_stats(deps.as_ref(), &contract, &seed, parts_data, &model)?
+        },
+        _ => return Err(StdError::generic_err("La prueba de stats no corresponde al modo activo")),
+    };
+
+    // Validar y normalizar las URIs de imagen; la firma cubre las URIs tal como se enviaron
+    let car_image_uri = normalize_uri(&car_image_uri).map_err(StdError::generic_err)?;
+    let parts_data = parts_data
+        .into_iter()
+        .map(|mut part| {
+            part.image_uri = normalize_uri(&part.image_uri).map_err(StdError::generic_err)?;
+            Ok(part)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
     // Obtener el ID actual del carro
     let car_id = contract.current_car_id.load(deps.storage)?;
     
-    // Verificar que tenga todas las partes necesarias
-    let mut has_engine = false;
-    let mut has_transmission = false;
-    let mut has_wheels = false;
-
-    // Preparar los arrays para la composición del carro
-    let part_ids = vec![0u64; 3];
-    let mut slot_occupied = vec![false; 3];
+    // Preparar los arrays para la composición del carro según el esquema de slots
+    let part_ids = vec![0u64; schema.len()];
+    let mut slot_occupied = vec![false; schema.len()];
     let mut messages = Vec::new();
 
     // Obtener la dirección del contrato de partes
     let car_part_contract = contract.car_part_contract.load(deps.storage)?;
 
     // Procesar cada parte
-    for (index, part) in parts_data.iter().enumerate() {
-        let slot_index = match part.part_type {
-            PartType::Engine => {
-                has_engine = true;
-                0
-            },
-            PartType::Transmission => {
-                has_transmission = true;
-                1
-            },
-            PartType::Wheels => {
-                has_wheels = true;
-                2
-            },
-        };
-
-        // Validar stats
-        if part.stat1 > 10 || part.stat2 > 10 || part.stat3 > 10 {
-            return Err(StdError::generic_err("Los stats deben ser <= 10"));
+    for part in parts_data.iter() {
+        let slot_index = schema
+            .iter()
+            .position(|slot| slot.part_type == part.part_type)
+            .ok_or_else(|| StdError::generic_err(format!("El carro no tiene slot para {}", part.part_type)))?;
+        if slot_occupied[slot_index] {
+            return Err(StdError::generic_err(format!("Parte repetida: {}", part.part_type)));
         }
+        check_model_accepts(&model, &part.part_type, &part.rarity)?;
+
+        // Los stats se validan en el contrato de partes según los límites de la rareza.
+        // Si no son válidos el submensaje falla y se revierte todo el minteo del carro.
 
         // Crear el mensaje para mintear la parte
-        let mint_msg = ExecuteMsg::Mint {
+        let mint_msg = PartExecuteMsg::Mint {
             to: info.sender.to_string(),
             part_type: part.part_type.clone(),
             stat1: part.stat1,
@@ -401,6 +1129,7 @@ fn execute_mint_car(
             stat3: part.stat3,
             image_uri: part.image_uri.clone(),
             car_id,
+            rarity: part.rarity.clone(),
         };
 
         // Crear el submensaje para mintear la parte
@@ -408,7 +1137,7 @@ fn execute_mint_car(
             contract_addr: car_part_contract.to_string(),
             msg: to_json_binary(&mint_msg)?,
             funds: vec![],
-        }, (index + 1) as u64);
+        }, (slot_index + 1) as u64);
 
         // Marcar el slot como ocupado
         slot_occupied[slot_index] = true;
@@ -417,9 +1146,15 @@ fn execute_mint_car(
         messages.push(mint_submsg);
     }
 
-    // Verificar que tenga todas las partes necesarias
-    if !has_engine || !has_transmission || !has_wheels {
-        return Err(StdError::generic_err("Faltan partes necesarias (motor, transmisión o ruedas)"));
+    // Verificar que tenga todas las partes obligatorias del esquema
+    let missing: Vec<String> = schema
+        .iter()
+        .zip(slot_occupied.iter())
+        .filter(|(slot, &occupied)| slot.required && !occupied)
+        .map(|(slot, _)| slot.part_type.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(StdError::generic_err(format!("Faltan partes necesarias: {}", missing.join(", "))));
     }
 
     // Guardar la composición del carro
@@ -427,12 +1162,14 @@ fn execute_mint_car(
         part_ids,
         car_image_uri: car_image_uri.clone(),
         slot_occupied,
+        model_id,
+        stale_image: false,
+        name: None,
+        cosmetic_id: 0,
+        decal_ids: vec![],
     };
     contract.cars.save(deps.storage, car_id, &car_composition)?;
 
-    // Inicializar la condición del carro al 100%
-    contract.car_conditions.save(deps.storage, car_id, &100u8)?;
-
     // Registrar el propietario del carro
     contract.owner_cars.save(deps.storage, (info.sender.clone(), car_id), &true)?;
 
@@ -443,13 +1180,142 @@ fn execute_mint_car(
         .add_submessages(messages)
         .add_attribute("method", "mint_car")
         .add_attribute("car_id", car_id.to_string())
+        .add_attribute("model_id", model_id.to_string())
         .add_attribute("owner", info.sender))
 }
 
-fn execute_unequip_part(
+fn execute_commit_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    commitment: String,
+) -> StdResult<Response> {
+    let reveal_window = match contract.stats_mode.load(deps.storage)? {
+        StatsMode::CommitReveal { reveal_window } => reveal_window,
+        _ => return Err(StdError::generic_err("El modo de stats activo no usa commit-reveal")),
+    };
+
+    // Sin servidor de juego nadie publicaría la segunda semilla y el commit no se podría revelar
+    if contract.game_server.may_load(deps.storage)?.is_none() {
+        return Err(StdError::generic_err("El servidor de juego no está configurado"));
+    }
+
+    // El minteo se paga al hacer el commit, así no revelar no sirve para repetir tiradas gratis
+    check_payment(deps.as_ref(), &contract, &info)?;
+
+    let commitment = randomness::parse_commitment(&commitment).map_err(StdError::generic_err)?;
+
+    // Un commit pendiente solo se puede reemplazar cuando ya expiró
+    if let Some(pending) = contract.mint_commits.may_load(deps.storage, &info.sender)? {
+        if env.block.height <= pending.height + reveal_window {
+            return Err(StdError::generic_err("Ya existe un commit pendiente de revelar"));
+        }
+    }
+
+    contract.mint_commits.save(deps.storage, &info.sender, &MintCommit {
+        commitment: commitment.clone(),
+        height: env.block.height,
+        server_seed: None,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "commit_mint")
+        .add_attribute("owner", info.sender)
+        .add_attribute("commitment", commitment)
+        .add_attribute("height", env.block.height.to_string()))
+}
+
+fn execute_set_game_server(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
+    contract: CarNftContract,
+    address: String,
+) -> StdResult<Response> {
+    // Solo el admin puede designar el servidor de juego
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar el servidor de juego"));
+    }
+    let server_addr = deps.api.addr_validate(&address)?;
+    contract.game_server.save(deps.storage, &server_addr)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_game_server")
+        .add_attribute("address", address))
+}
+
+// El servidor de juego publica su semilla para el commit pendiente de un jugador. Llega después
+// del commit, así el jugador ya no puede cambiar su secreto, y no se puede reemplazar.
+fn execute_submit_mint_seed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    minter: String,
+    seed: String,
+) -> StdResult<Response> {
+    let game_server = contract.game_server.may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("El servidor de juego no está configurado"))?;
+    if info.sender != game_server {
+        return Err(StdError::generic_err("Solo el servidor de juego puede publicar la semilla"));
+    }
+    let reveal_window = match contract.stats_mode.load(deps.storage)? {
+        StatsMode::CommitReveal { reveal_window } => reveal_window,
+        _ => return Err(StdError::generic_err("El modo de stats activo no usa commit-reveal")),
+    };
+    if seed.is_empty() {
+        return Err(StdError::generic_err("La semilla del servidor no puede estar vacía"));
+    }
+
+    let minter_addr = deps.api.addr_validate(&minter)?;
+    let mut commit = contract.mint_commits
+        .may_load(deps.storage, &minter_addr)?
+        .ok_or_else(|| StdError::generic_err("No hay un commit pendiente para este jugador"))?;
+    if env.block.height > commit.height + reveal_window {
+        return Err(StdError::generic_err("El plazo para revelar el secreto expiró"));
+    }
+    if commit.server_seed.is_some() {
+        return Err(StdError::generic_err("El servidor ya publicó su semilla para este commit"));
+    }
+    commit.server_seed = Some(seed);
+    contract.mint_commits.save(deps.storage, &minter_addr, &commit)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "submit_mint_seed")
+        .add_attribute("minter", minter_addr))
+}
+
+fn execute_set_stats_mode(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    mode: StatsMode,
+) -> StdResult<Response> {
+    // Solo el admin puede cambiar el modo de generación de stats
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede cambiar el modo de stats"));
+    }
+
+    validate_stats_mode(&mode)?;
+    contract.stats_mode.save(deps.storage, &mode)?;
+
+    let mode_name = match mode {
+        StatsMode::Signed { .. } => "signed",
+        StatsMode::CommitReveal { .. } => "commit_reveal",
+    };
+    Ok(Response::new()
+        .add_attribute("method", "set_stats_mode")
+        .add_attribute("mode", mode_name))
+}
+
+fn execute_unequip_part(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     contract: CarNftContract,
     car_id: u64,
     part_id: u64,
@@ -457,8 +1323,9 @@ fn execute_unequip_part(
     // Verificar que el carro existe
     let car = contract.cars.load(deps.storage, car_id)?;
     
-    // Verificar que el remitente es el dueño del carro
-    // TODO: Implementar verificación de propiedad cuando se implemente CW721
+    // Verificar que el remitente es el dueño del carro y que no está alquilado
+    ensure_car_owner(deps.storage, &contract, &info.sender, car_id)?;
+    ensure_not_rented(deps.storage, &contract, &env.block, car_id)?;
     
     // Verificar que la parte está equipada en el carro
     let mut found = false;
@@ -479,7 +1346,7 @@ fn execute_unequip_part(
     let car_part_contract = contract.car_part_contract.load(deps.storage)?;
     
     // Crear el mensaje para actualizar el estado de equipamiento en el contrato de partes
-    let set_equipped_msg = ExecuteMsg::SetEquippedState {
+    let set_equipped_msg = PartExecuteMsg::SetEquippedState {
         part_id,
         car_id: 0, // 0 indica que la parte no está equipada
     };
@@ -488,6 +1355,7 @@ fn execute_unequip_part(
     let mut updated_car = car;
     updated_car.part_ids[slot_index] = 0;
     updated_car.slot_occupied[slot_index] = false;
+    mark_image_stale(deps.storage, &contract, car_id, &mut updated_car)?;
     contract.cars.save(deps.storage, car_id, &updated_car)?;
     
     Ok(Response::new()
@@ -498,19 +1366,23 @@ fn execute_unequip_part(
         })
         .add_attribute("method", "unequip_part")
         .add_attribute("car_id", car_id.to_string())
-        .add_attribute("part_id", part_id.to_string()))
+        .add_attribute("part_id", part_id.to_string())
+        .add_attribute("stale_image", "true"))
 }
 
 fn execute_set_mint_price(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     contract: CarNftContract,
     price: Uint128,
 ) -> StdResult<Response> {
-    // Verificar que el remitente es el dueño del contrato
-    // TODO: Implementar verificación de propiedad cuando se implemente CW721
-    
+    // Solo el admin puede cambiar el precio de minteo
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar el precio de minteo"));
+    }
+
     // Actualizar el precio de minteo
     contract.mint_price.save(deps.storage, &price)?;
     
@@ -521,8 +1393,8 @@ fn execute_set_mint_price(
 
 fn execute_equip_part(
     deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
+    env: Env,
+    info: MessageInfo,
     contract: CarNftContract,
     car_id: u64,
     part_id: u64,
@@ -530,11 +1402,17 @@ fn execute_equip_part(
 ) -> StdResult<Response> {
     // Verificar que el carro existe
     let mut car = contract.cars.load(deps.storage, car_id)?;
+
+    // Verificar que el remitente es el dueño del carro y que no está alquilado
+    ensure_car_owner(deps.storage, &contract, &info.sender, car_id)?;
+    ensure_not_rented(deps.storage, &contract, &env.block, car_id)?;
     
     // Verificar que el slot es válido
-    if slot_index >= car.part_ids.len() as u64 {
-        return Err(StdError::generic_err("Índice de slot inválido"));
-    }
+    let schema = load_slot_schema(deps.storage, &contract)?;
+    let slot = schema
+        .get(slot_index as usize)
+        .ok_or_else(|| StdError::generic_err("Índice de slot inválido"))?;
+    fit_car_slots(&mut car, schema.len());
     
     // Verificar que el slot no está ocupado
     if car.slot_occupied[slot_index as usize] {
@@ -543,9 +1421,13 @@ fn execute_equip_part(
     
     // Obtener la dirección del contrato de partes
     let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+
+    // Verificar que la parte corresponde al tipo del slot y al modelo del carro
+    let model = load_car_model(deps.storage, &contract, car.model_id)?;
+    check_slot_part(deps.as_ref(), &car_part_contract, slot, &model, &info.sender, part_id)?;
     
     // Crear el mensaje para actualizar el estado de equipamiento
-    let set_equipped_msg = ExecuteMsg::SetEquippedState {
+    let set_equipped_msg = PartExecuteMsg::SetEquippedState {
         part_id,
         car_id,
     };
@@ -553,6 +1435,7 @@ fn execute_equip_part(
     // Actualizar el estado del carro
     car.part_ids[slot_index as usize] = part_id;
     car.slot_occupied[slot_index as usize] = true;
+    mark_image_stale(deps.storage, &contract, car_id, &mut car)?;
     contract.cars.save(deps.storage, car_id, &car)?;
     
     Ok(Response::new()
@@ -564,13 +1447,14 @@ fn execute_equip_part(
         .add_attribute("method", "equip_part")
         .add_attribute("car_id", car_id.to_string())
         .add_attribute("part_id", part_id.to_string())
-        .add_attribute("slot_index", slot_index.to_string()))
+        .add_attribute("slot_index", slot_index.to_string())
+        .add_attribute("stale_image", "true"))
 }
 
 fn execute_replace_part(
     deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
+    env: Env,
+    info: MessageInfo,
     contract: CarNftContract,
     car_id: u64,
     old_part_id: u64,
@@ -581,6 +1465,10 @@ fn execute_replace_part(
     
     // Verificar que el carro existe
     let mut car = contract.cars.load(deps.storage, car_id)?;
+
+    // Verificar que el remitente es el dueño del carro y que no está alquilado
+    ensure_car_owner(deps.storage, &contract, &info.sender, car_id)?;
+    ensure_not_rented(deps.storage, &contract, &env.block, car_id)?;
     
     // Verificar que la parte antigua está equipada
     let mut slot_index = None;
@@ -595,9 +1483,17 @@ fn execute_replace_part(
     
     // Obtener la dirección del contrato de partes
     let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+
+    // La parte nueva debe corresponder al tipo del slot y al modelo del carro
+    let schema = load_slot_schema(deps.storage, &contract)?;
+    let slot = schema
+        .get(slot_index)
+        .ok_or_else(|| StdError::generic_err("Índice de slot inválido"))?;
+    let model = load_car_model(deps.storage, &contract, car.model_id)?;
+    check_slot_part(deps.as_ref(), &car_part_contract, slot, &model, &info.sender, new_part_id)?;
     
     // Desequipar la parte antigua
-    let unequip_msg = ExecuteMsg::SetEquippedState {
+    let unequip_msg = PartExecuteMsg::SetEquippedState {
         part_id: old_part_id,
         car_id: 0,
     };
@@ -609,7 +1505,7 @@ fn execute_replace_part(
     });
     
     // Equipar la nueva parte
-    let equip_msg = ExecuteMsg::SetEquippedState {
+    let equip_msg = PartExecuteMsg::SetEquippedState {
         part_id: new_part_id,
         car_id,
     };
@@ -622,22 +1518,29 @@ fn execute_replace_part(
     
     // Actualizar el estado del carro
     car.part_ids[slot_index] = new_part_id;
+    mark_image_stale(deps.storage, &contract, car_id, &mut car)?;
     contract.cars.save(deps.storage, car_id, &car)?;
     
     Ok(response
         .add_attribute("method", "replace_part")
         .add_attribute("car_id", car_id.to_string())
         .add_attribute("old_part_id", old_part_id.to_string())
-        .add_attribute("new_part_id", new_part_id.to_string()))
+        .add_attribute("new_part_id", new_part_id.to_string())
+        .add_attribute("stale_image", "true"))
 }
 
 fn execute_set_workshop_contract(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     contract: CarNftContract,
     address: String,
 ) -> StdResult<Response> {
+    // Solo el admin puede designar el taller: esa dirección puede degradar o reparar carros
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar el taller"));
+    }
     let workshop_addr = deps.api.addr_validate(&address)?;
     contract.workshop_contract.save(deps.storage, &workshop_addr)?;
     
@@ -649,10 +1552,15 @@ fn execute_set_workshop_contract(
 fn execute_set_leaderboard_contract(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     contract: CarNftContract,
     address: String,
 ) -> StdResult<Response> {
+    // Solo el admin puede designar el leaderboard: esa dirección puede degradar o reparar carros
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar el leaderboard"));
+    }
     let leaderboard_addr = deps.api.addr_validate(&address)?;
     contract.leaderboard_contract.save(deps.storage, &leaderboard_addr)?;
     
@@ -661,15 +1569,70 @@ fn execute_set_leaderboard_contract(
         .add_attribute("address", address))
 }
 
+fn execute_set_renderer_contract(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    address: String,
+) -> StdResult<Response> {
+    // Solo el admin puede designar el renderizador
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar el renderizador"));
+    }
+    let renderer_addr = deps.api.addr_validate(&address)?;
+    contract.renderer_contract.save(deps.storage, &renderer_addr)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_renderer_contract")
+        .add_attribute("address", address))
+}
+
+// El renderizador sube la imagen compuesta con las partes actuales y el carro deja de estar pendiente
+fn execute_set_car_image(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+    car_image_uri: String,
+) -> StdResult<Response> {
+    let renderer = contract.renderer_contract.may_load(deps.storage)?;
+    if renderer.as_ref() != Some(&info.sender) {
+        return Err(StdError::generic_err("Solo el renderizador puede cambiar la imagen del carro"));
+    }
+
+    let mut car = contract.cars.load(deps.storage, car_id)?;
+    car.car_image_uri = normalize_uri(&car_image_uri).map_err(StdError::generic_err)?;
+    car.stale_image = false;
+    contract.cars.save(deps.storage, car_id, &car)?;
+    contract.stale_cars.remove(deps.storage, car_id);
+
+    Ok(Response::new()
+        .add_attribute("method", "set_car_image")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("car_image_uri", car.car_image_uri))
+}
+
+// Marcar que la imagen del carro ya no refleja sus partes; el renderizador la regenera con SetCarImage
+fn mark_image_stale(storage: &mut dyn Storage, contract: &CarNftContract, car_id: u64, car: &mut CarComposition) -> StdResult<()> {
+    car.stale_image = true;
+    contract.stale_cars.save(storage, car_id, &true)
+}
+
 fn execute_withdraw_funds(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    _contract: CarNftContract,
+    contract: CarNftContract,
 ) -> StdResult<Response> {
-    // Verificar que el remitente es el dueño del contrato
-    // TODO: Implementar verificación de propiedad cuando se implemente CW721
-    
+    // Solo el admin retira lo recaudado: minteos y comisiones de alquiler
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede retirar los fondos"));
+    }
+
     // Obtener el balance del contrato
     let balance = deps.querier.query_all_balances(&env.contract.address)?;
     
@@ -690,6 +1653,7 @@ fn execute_withdraw_funds(
         .add_attribute("amount", format!("{:?}", balance)))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_mint(
     deps: DepsMut,
     _env: Env,
@@ -721,7 +1685,7 @@ fn execute_mint(
     let car_part_contract = contract.car_part_contract.load(deps.storage)?;
     
     // Crear el mensaje para actualizar el estado de equipamiento
-    let set_equipped_msg = ExecuteMsg::SetEquippedState {
+    let set_equipped_msg = PartExecuteMsg::SetEquippedState {
         part_id: car.part_ids[2],
         car_id,
     };
@@ -786,7 +1750,7 @@ fn execute_set_equipped_state(
     let car_part_contract = contract.car_part_contract.load(deps.storage)?;
     
     // Crear el mensaje para actualizar el estado de equipamiento
-    let set_equipped_msg = ExecuteMsg::SetEquippedState {
+    let set_equipped_msg = PartExecuteMsg::SetEquippedState {
         part_id,
         car_id,
     };
@@ -807,455 +1771,2829 @@ fn execute_set_equipped_state(
         .add_attribute("part_id", part_id.to_string()))
 }
 
-// Funciones auxiliares
-fn query_car_composition(deps: Deps, contract: CarNftContract, car_id: u64) -> StdResult<CarComposition> {
-    contract.cars.load(deps.storage, car_id)
-}
-
-// Funciones auxiliares de consulta
-fn query_owner_cars(deps: Deps, contract: CarNftContract, owner: String) -> StdResult<OwnerCarsResponse> {
-    let owner_addr = deps.api.addr_validate(&owner)?;
-    let last_token_id = contract.current_car_id.load(deps.storage)?;
-    let mut car_ids = Vec::new();
+fn execute_burn_car(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+    burn_parts: bool,
+) -> StdResult<Response> {
+    // Solo el dueño puede quemar su carro, y no mientras esté alquilado
+    if !contract.owner_cars.has(deps.storage, (info.sender.clone(), car_id)) {
+        return Err(StdError::generic_err("El carro no pertenece al remitente"));
+    }
+    ensure_not_rented(deps.storage, &contract, &env.block, car_id)?;
+    let car = contract.cars.load(deps.storage, car_id)?;
 
-    // Iterar sobre todos los posibles IDs de carros y verificar si pertenecen al propietario
-    for car_id in 1..last_token_id {
-        if contract.owner_cars.may_load(deps.storage, (owner_addr.clone(), car_id))?.unwrap_or(false) {
-            car_ids.push(car_id);
+    // Pago de rescate del carro, tomado del balance del contrato. No hay una reserva aparte:
+    // si `withdraw_funds` vació el balance, la quema falla hasta que el admin vuelva a fondearlo.
+    let salvage_value = contract.salvage_value.may_load(deps.storage)?.unwrap_or_default();
+    let mut response = Response::new();
+    if !salvage_value.is_zero() {
+        let balance = deps.querier.query_balance(&env.contract.address, "uxion")?;
+        if balance.amount < salvage_value {
+            return Err(StdError::generic_err("El contrato no tiene fondos para pagar el rescate"));
         }
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(salvage_value.u128(), "uxion")],
+        });
     }
 
-    Ok(OwnerCarsResponse { car_ids })
-}
+    contract.cars.remove(deps.storage, car_id);
+    contract.stale_cars.remove(deps.storage, car_id);
+    contract.owner_cars.remove(deps.storage, (info.sender.clone(), car_id));
+    contract.rental_listings.remove(deps.storage, car_id);
+    contract.car_users.remove(deps.storage, car_id);
 
-fn query_compact_car_stats(deps: Deps, contract: CarNftContract, car_id: u64) -> StdResult<CompactCarStats> {
-    let car = contract.cars.load(deps.storage, car_id)?;
-    let condition = contract.car_conditions.load(deps.storage, car_id)?;
-    
-    // Aquí deberías calcular los stats basados en las partes del carro
-    // Por ahora retornamos valores por defecto
-    Ok(CompactCarStats {
-        image_uri: car.car_image_uri,
-        speed: 0,
-        acceleration: 0,
-        handling: 0,
-        drift_factor: 0,
-        turn_factor: 0,
-        max_speed: 0,
-        condition,
-    })
-}
+    // El nombre queda libre y las calcomanías vuelven sueltas al dueño
+    if let Some(name) = &car.name {
+        contract.car_names.remove(deps.storage, (&name.to_lowercase(), car_id));
+    }
+    for &decal_id in &car.decal_ids {
+        contract.decals.update(deps.storage, decal_id, |decal| -> StdResult<_> {
+            let mut decal = decal.ok_or_else(|| StdError::generic_err("La calcomanía no existe"))?;
+            decal.car_id = 0;
+            Ok(decal)
+        })?;
+    }
 
-fn query_full_car_metadata(deps: Deps, contract: CarNftContract, car_id: u64) -> StdResult<FullCarMetadata> {
-    let car = contract.cars.load(deps.storage, car_id)?;
-    // Obtener la condición del carro, si no existe usar 100 como valor por defecto
-    let condition = contract.car_conditions.may_load(deps.storage, car_id)?.unwrap_or(100u8);
+    // Las partes equipadas se queman con el carro o vuelven sueltas al dueño
     let car_part_contract = contract.car_part_contract.load(deps.storage)?;
-    let mut parts = Vec::new();
-    let mut total_speed = 0u8;
-    let mut total_acceleration = 0u8;
-    let mut total_handling = 0u8;
-
-    // Obtener los metadatos de cada parte
-    for (slot_index, &part_id) in car.part_ids.iter().enumerate() {
-        if part_id > 0 && car.slot_occupied[slot_index] {
-            // Consultar stats de la parte
-            let query_msg = QueryMsg::GetPartStats { part_id };
-            let part_stats: PartStats = deps.querier.query_wasm_smart(
-                car_part_contract.clone(),
-                &query_msg,
-            )?;
+    let part_ids = equipped_part_ids(&car);
+    let part_msgs = if burn_parts {
+        vec![PartExecuteMsg::BurnCarParts {
+            owner: info.sender.to_string(),
+            car_id,
+            part_ids: part_ids.clone(),
+        }]
+    } else {
+        part_ids
+            .iter()
+            .map(|&part_id| PartExecuteMsg::SetEquippedState { part_id, car_id: 0 })
+            .collect()
+    };
+    for msg in part_msgs {
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr: car_part_contract.to_string(),
+            msg: to_json_binary(&msg)?,
+            funds: vec![],
+        });
+    }
 
-            // Consultar tipo de parte
-            let query_msg = QueryMsg::GetPartType { part_id };
-            let part_type: PartType = deps.querier.query_wasm_smart(
-                car_part_contract.clone(),
-                &query_msg,
-            )?;
-
-            // Agregar los stats al total
-            match part_type {
-                PartType::Engine => {
-                    total_speed += part_stats.stat1;
-                    total_acceleration += part_stats.stat2;
-                    total_handling += part_stats.stat3;
-                },
-                PartType::Transmission => {
-                    total_speed += part_stats.stat1;
-                    total_acceleration += part_stats.stat2;
-                    total_handling += part_stats.stat3;
-                },
-                PartType::Wheels => {
-                    total_speed += part_stats.stat1;
-                    total_acceleration += part_stats.stat2;
-                    total_handling += part_stats.stat3;
-                },
-            }
+    let part_ids: Vec<String> = part_ids.iter().map(|id| id.to_string()).collect();
+    Ok(response
+        .add_attribute("method", "burn_car")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("owner", info.sender)
+        .add_attribute("burn_parts", burn_parts.to_string())
+        .add_attribute("part_ids", part_ids.join(",")))
+}
 
-            parts.push(PartMetadata {
-                part_id,
-                part_type,
-                stats: part_stats,
-                slot_index: slot_index as u8,
-            });
-        }
+fn execute_degrade_car(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+    wear: u8,
+) -> StdResult<Response> {
+    // Solo el leaderboard degrada los carros después de una carrera
+    let leaderboard = contract.leaderboard_contract.may_load(deps.storage)?;
+    if leaderboard.as_ref() != Some(&info.sender) {
+        return Err(StdError::generic_err("Solo el contrato de leaderboard puede degradar carros"));
     }
 
-    // Calcular stats totales
-    let total_stats = CompactCarStats {
-        image_uri: car.car_image_uri.clone(),
-        speed: total_speed / 3,
-        acceleration: total_acceleration / 3,
-        handling: total_handling / 3,
-        drift_factor: (total_handling + total_acceleration) / 4,
-        turn_factor: (total_handling + total_speed) / 4,
-        max_speed: total_speed,
-        condition,
+    // El desgaste se aplica a cada parte equipada según su función
+    let car = contract.cars.load(deps.storage, car_id)?;
+    let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+    let degrade_msg = PartExecuteMsg::DegradeParts {
+        part_ids: equipped_part_ids(&car),
+        wear,
     };
 
-    Ok(FullCarMetadata {
-        car_id,
-        car_image_uri: car.car_image_uri,
-        parts,
-        total_stats,
-    })
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: car_part_contract.to_string(),
+            msg: to_json_binary(&degrade_msg)?,
+            funds: vec![],
+        })
+        .add_attribute("method", "degrade_car")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("wear", wear.to_string()))
 }
 
-fn query_last_token_id(deps: Deps, contract: CarNftContract) -> StdResult<u64> {
-    contract.current_car_id.load(deps.storage)
+fn execute_repair_car(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+) -> StdResult<Response> {
+    let car = contract.cars.load(deps.storage, car_id)?;
+    let response = repair_parts(deps.as_ref(), &contract, &info, equipped_part_ids(&car))?;
+
+    Ok(response
+        .add_attribute("method", "repair_car")
+        .add_attribute("car_id", car_id.to_string()))
 }
 
-// Funciones de consulta
-fn query_part_stats(deps: Deps, contract: CarNftContract, part_id: u64) -> StdResult<PartStats> {
-    let car_part_contract = contract.car_part_contract.load(deps.storage)?;
-    deps.querier.query_wasm_smart(
-        car_part_contract,
-        &QueryMsg::GetPartStats { part_id },
-    )
+fn execute_repair_part(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    part_id: u64,
+) -> StdResult<Response> {
+    let response = repair_parts(deps.as_ref(), &contract, &info, vec![part_id])?;
+
+    Ok(response
+        .add_attribute("method", "repair_part")
+        .add_attribute("part_id", part_id.to_string()))
 }
 
-fn query_part_type(deps: Deps, contract: CarNftContract, part_id: u64) -> StdResult<PartType> {
+// Reenviar una reparación del taller al contrato de partes
+fn repair_parts(deps: Deps, contract: &CarNftContract, info: &MessageInfo, part_ids: Vec<u64>) -> StdResult<Response> {
+    let workshop = contract.workshop_contract.may_load(deps.storage)?;
+    if workshop.as_ref() != Some(&info.sender) {
+        return Err(StdError::generic_err("Solo el taller puede reparar carros y partes"));
+    }
+
     let car_part_contract = contract.car_part_contract.load(deps.storage)?;
-    deps.querier.query_wasm_smart(
-        car_part_contract,
-        &QueryMsg::GetPartType { part_id },
-    )
+    Ok(Response::new().add_message(WasmMsg::Execute {
+        contract_addr: car_part_contract.to_string(),
+        msg: to_json_binary(&PartExecuteMsg::RepairParts { part_ids })?,
+        funds: vec![],
+    }))
 }
 
-// Función para obtener todos los metadatos de los carros de un usuario
-pub fn query_all_car_metadata(
+fn execute_set_slot_schema(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    slots: Vec<SlotDef>,
+) -> StdResult<Response> {
+    // Solo el admin puede cambiar el esquema de slots
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede cambiar el esquema de slots"));
+    }
+
+    save_slot_schema(deps.storage, &contract, slots)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_slot_schema")
+        .add_attribute("slots", load_slot_schema(deps.storage, &contract)?.len().to_string()))
+}
+
+// El esquema guardado o, en contratos anteriores al esquema, el original de tres slots
+fn load_slot_schema(storage: &dyn Storage, contract: &CarNftContract) -> StdResult<Vec<SlotDef>> {
+    Ok(contract.slot_schema.may_load(storage)?.unwrap_or_else(default_slot_schema))
+}
+
+// Validar y guardar un esquema nuevo. Los slots existentes no se pueden quitar ni reordenar,
+// porque los carros ya minteados guardan sus partes por índice de slot; solo se agregan al final.
+fn save_slot_schema(storage: &mut dyn Storage, contract: &CarNftContract, slots: Vec<SlotDef>) -> StdResult<()> {
+    let current = load_slot_schema(storage, contract)?;
+    if slots.len() < current.len()
+        || current.iter().zip(slots.iter()).any(|(old, new)| old.part_type != new.part_type)
+    {
+        return Err(StdError::generic_err("Los slots existentes no se pueden quitar ni reordenar"));
+    }
+    for (index, slot) in slots.iter().enumerate() {
+        if slots[..index].iter().any(|other| other.part_type == slot.part_type) {
+            return Err(StdError::generic_err(format!("Slot repetido: {}", slot.part_type)));
+        }
+    }
+    if !slots.iter().any(|slot| slot.required) {
+        return Err(StdError::generic_err("El esquema necesita al menos un slot obligatorio"));
+    }
+    contract.slot_schema.save(storage, &slots)
+}
+
+// Los carros minteados antes de ampliar el esquema tienen menos slots; se completan vacíos
+fn fit_car_slots(car: &mut CarComposition, slots: usize) {
+    if car.part_ids.len() < slots {
+        car.part_ids.resize(slots, 0);
+        car.slot_occupied.resize(slots, false);
+    }
+}
+
+// Solo se equipan partes sueltas del dueño del carro: no una en custodia de otro contrato
+// (marketplace, subasta) ni una equipada en otro carro
+fn check_slot_part(
     deps: Deps,
+    car_part_contract: &Addr,
+    slot: &SlotDef,
+    model: &CarModel,
+    car_owner: &Addr,
+    part_id: u64,
+) -> StdResult<()> {
+    let part_owner: PartOwnerResponse = deps.querier.query_wasm_smart(
+        car_part_contract.to_string(),
+        &QueryMsg::GetPartOwner { part_id },
+    )?;
+    if part_owner.owner != car_owner.as_str() {
+        return Err(StdError::generic_err("La parte no pertenece al dueño del carro"));
+    }
+    if part_owner.equipped_in_car > 0 {
+        return Err(StdError::generic_err(format!("La parte ya está equipada en el carro {}", part_owner.equipped_in_car)));
+    }
+
+    let part_stats: PartStats = deps.querier.query_wasm_smart(
+        car_part_contract.to_string(),
+        &QueryMsg::GetPartStats { part_id },
+    )?;
+    if part_stats.part_type != slot.part_type {
+        return Err(StdError::generic_err(format!(
+            "Una parte {} no se puede equipar en el slot de {}",
+            part_stats.part_type, slot.part_type
+        )));
+    }
+    check_model_accepts(model, &part_stats.part_type, &part_stats.rarity)
+}
+
+fn check_model_accepts(model: &CarModel, part_type: &PartType, rarity: &Rarity) -> StdResult<()> {
+    if !model.accepts(part_type, rarity) {
+        return Err(StdError::generic_err(format!(
+            "El modelo {} no admite una parte {} {}",
+            model.name, part_type, rarity
+        )));
+    }
+    Ok(())
+}
+
+fn execute_set_car_model(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
     contract: CarNftContract,
-    owner: String,
-) -> StdResult<AllCarMetadataResponse> {
-    let _owner_addr = deps.api.addr_validate(&owner)?;
-    let owner_cars = query_owner_cars(deps, contract.clone(), owner)?;
-    
-    let mut cars = Vec::new();
-    for car_id in owner_cars.car_ids {
-        if let Ok(car_metadata) = query_full_car_metadata(deps, contract.clone(), car_id) {
-            cars.push(car_metadata);
+    model_id: u64,
+    model: CarModel,
+) -> StdResult<Response> {
+    // Solo el admin puede registrar modelos
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede registrar modelos de carro"));
+    }
+    if model_id == 0 {
+        return Err(StdError::generic_err("El modelo 0 es el chasis genérico y no se puede cambiar"));
+    }
+
+    // El modelo debe admitir todas las partes obligatorias, o ningún carro se podría mintear
+    let schema = load_slot_schema(deps.storage, &contract)?;
+    if let Some(slot) = schema.iter().find(|slot| {
+        slot.required && !model.allowed_part_types.is_empty() && !model.allowed_part_types.contains(&slot.part_type)
+    }) {
+        return Err(StdError::generic_err(format!(
+            "El modelo debe admitir la parte obligatoria {}",
+            slot.part_type
+        )));
+    }
+
+    contract.car_models.save(deps.storage, model_id, &model)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_car_model")
+        .add_attribute("model_id", model_id.to_string())
+        .add_attribute("name", model.name))
+}
+
+fn execute_set_image_gateway(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    gateway: Option<String>,
+) -> StdResult<Response> {
+    // Solo el admin puede configurar el gateway de imágenes
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar el gateway de imágenes"));
+    }
+
+    // `None` quita el gateway y las URIs ipfs:// se devuelven sin resolver
+    let gateway = match gateway {
+        Some(gateway) => {
+            let gateway = normalize_gateway(&gateway).map_err(StdError::generic_err)?;
+            contract.image_gateway.save(deps.storage, &gateway)?;
+            gateway
+        },
+        None => {
+            contract.image_gateway.remove(deps.storage);
+            String::new()
+        },
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", "set_image_gateway")
+        .add_attribute("gateway", gateway))
+}
+
+// Solo el dueño del carro puede cambiar su apariencia
+fn ensure_car_owner(storage: &dyn Storage, contract: &CarNftContract, sender: &Addr, car_id: u64) -> StdResult<()> {
+    if !contract.owner_cars.has(storage, (sender.clone(), car_id)) {
+        return Err(StdError::generic_err("El carro no pertenece al remitente"));
+    }
+    Ok(())
+}
+
+// Un nombre válido no está vacío, no supera MAX_CAR_NAME_LENGTH caracteres ni tiene caracteres de control
+fn validate_car_name(name: &str) -> StdResult<String> {
+    let name = name.trim();
+    let length = name.chars().count();
+    if length == 0 || length > MAX_CAR_NAME_LENGTH {
+        return Err(StdError::generic_err(format!(
+            "El nombre del carro debe tener entre 1 y {} caracteres",
+            MAX_CAR_NAME_LENGTH
+        )));
+    }
+    if name.chars().any(char::is_control) {
+        return Err(StdError::generic_err("El nombre del carro tiene caracteres inválidos"));
+    }
+    Ok(name.to_string())
+}
+
+fn execute_set_car_name(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+    name: Option<String>,
+) -> StdResult<Response> {
+    ensure_car_owner(deps.storage, &contract, &info.sender, car_id)?;
+    let mut car = contract.cars.load(deps.storage, car_id)?;
+    let name = name.as_deref().map(validate_car_name).transpose()?;
+
+    // Con nombres únicos, ningún otro carro puede usar el mismo nombre sin distinguir mayúsculas
+    if let Some(name) = &name {
+        let key = name.to_lowercase();
+        let unique = contract.unique_car_names.may_load(deps.storage)?.unwrap_or(false);
+        let taken = contract.car_names
+            .prefix(&key)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<u64>>>()?
+            .into_iter()
+            .any(|other| other != car_id);
+        if unique && taken {
+            return Err(StdError::generic_err("Ya existe un carro con ese nombre"));
         }
     }
-    
-    Ok(AllCarMetadataResponse { cars })
+
+    if let Some(old_name) = &car.name {
+        contract.car_names.remove(deps.storage, (&old_name.to_lowercase(), car_id));
+    }
+    if let Some(name) = &name {
+        contract.car_names.save(deps.storage, (&name.to_lowercase(), car_id), &true)?;
+    }
+    car.name = name;
+    contract.cars.save(deps.storage, car_id, &car)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_car_name")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("name", car.name.unwrap_or_default()))
+}
+
+fn execute_set_unique_car_names(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    unique: bool,
+) -> StdResult<Response> {
+    // Solo el admin decide si los nombres deben ser únicos; los repetidos previos se conservan
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar los nombres de carros"));
+    }
+    contract.unique_car_names.save(deps.storage, &unique)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_unique_car_names")
+        .add_attribute("unique", unique.to_string()))
+}
+
+fn execute_set_cosmetic(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    cosmetic_id: u64,
+    mut cosmetic: CosmeticItem,
+) -> StdResult<Response> {
+    // Solo el admin puede registrar pinturas y liveries
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede registrar cosméticos"));
+    }
+    if cosmetic_id == 0 {
+        return Err(StdError::generic_err("El cosmético 0 es la pintura de fábrica y no se puede cambiar"));
+    }
+    cosmetic.image_uri = normalize_uri(&cosmetic.image_uri).map_err(StdError::generic_err)?;
+    contract.cosmetics.save(deps.storage, cosmetic_id, &cosmetic)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_cosmetic")
+        .add_attribute("cosmetic_id", cosmetic_id.to_string())
+        .add_attribute("kind", cosmetic.kind.to_string())
+        .add_attribute("name", cosmetic.name))
+}
+
+fn execute_set_car_cosmetic(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+    cosmetic_id: u64,
+) -> StdResult<Response> {
+    ensure_car_owner(deps.storage, &contract, &info.sender, car_id)?;
+    let mut car = contract.cars.load(deps.storage, car_id)?;
+    // 0 vuelve a la pintura de fábrica
+    if cosmetic_id != 0 && !contract.cosmetics.has(deps.storage, cosmetic_id) {
+        return Err(StdError::generic_err(format!("El cosmético {} no existe", cosmetic_id)));
+    }
+
+    car.cosmetic_id = cosmetic_id;
+    mark_image_stale(deps.storage, &contract, car_id, &mut car)?;
+    contract.cars.save(deps.storage, car_id, &car)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_car_cosmetic")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("cosmetic_id", cosmetic_id.to_string())
+        .add_attribute("stale_image", "true"))
+}
+
+fn execute_mint_decal(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    to: String,
+    name: String,
+    image_uri: String,
+) -> StdResult<Response> {
+    // Solo el admin puede mintear calcomanías
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede mintear calcomanías"));
+    }
+    let owner = deps.api.addr_validate(&to)?;
+    let image_uri = normalize_uri(&image_uri).map_err(StdError::generic_err)?;
+
+    let decal_id = contract.current_decal_id.may_load(deps.storage)?.unwrap_or_default() + 1;
+    let decal = Decal {
+        owner: owner.clone(),
+        name,
+        image_uri,
+        car_id: 0,
+    };
+    contract.decals.save(deps.storage, decal_id, &decal)?;
+    contract.owner_decals.save(deps.storage, (owner, decal_id), &true)?;
+    contract.current_decal_id.save(deps.storage, &decal_id)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "mint_decal")
+        .add_attribute("decal_id", decal_id.to_string())
+        .add_attribute("owner", to))
+}
+
+fn execute_apply_decal(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+    decal_id: u64,
+) -> StdResult<Response> {
+    ensure_car_owner(deps.storage, &contract, &info.sender, car_id)?;
+    let mut decal = contract.decals.load(deps.storage, decal_id)?;
+    if decal.owner != info.sender {
+        return Err(StdError::generic_err("La calcomanía no pertenece al remitente"));
+    }
+    if decal.car_id != 0 {
+        return Err(StdError::generic_err("La calcomanía ya está aplicada en un carro"));
+    }
+    let mut car = contract.cars.load(deps.storage, car_id)?;
+    if car.decal_ids.len() >= MAX_DECALS_PER_CAR {
+        return Err(StdError::generic_err(format!(
+            "Un carro admite hasta {} calcomanías",
+            MAX_DECALS_PER_CAR
+        )));
+    }
+
+    decal.car_id = car_id;
+    contract.decals.save(deps.storage, decal_id, &decal)?;
+    car.decal_ids.push(decal_id);
+    mark_image_stale(deps.storage, &contract, car_id, &mut car)?;
+    contract.cars.save(deps.storage, car_id, &car)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "apply_decal")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("decal_id", decal_id.to_string())
+        .add_attribute("stale_image", "true"))
 }
 
-// Función para consultar el precio de minteo
-fn query_mint_price(deps: Deps, contract: CarNftContract) -> StdResult<Uint128> {
-    contract.mint_price.load(deps.storage)
-}
+fn execute_remove_decal(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    decal_id: u64,
+) -> StdResult<Response> {
+    let mut decal = contract.decals.load(deps.storage, decal_id)?;
+    if decal.owner != info.sender {
+        return Err(StdError::generic_err("La calcomanía no pertenece al remitente"));
+    }
+    if decal.car_id == 0 {
+        return Err(StdError::generic_err("La calcomanía no está aplicada"));
+    }
+
+    let car_id = decal.car_id;
+    let mut car = contract.cars.load(deps.storage, car_id)?;
+    car.decal_ids.retain(|&id| id != decal_id);
+    mark_image_stale(deps.storage, &contract, car_id, &mut car)?;
+    contract.cars.save(deps.storage, car_id, &car)?;
+    decal.car_id = 0;
+    contract.decals.save(deps.storage, decal_id, &decal)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "remove_decal")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("decal_id", decal_id.to_string())
+        .add_attribute("stale_image", "true"))
+}
+
+// Alquiler vigente del carro, si lo hay
+fn active_car_user(storage: &dyn Storage, contract: &CarNftContract, block: &BlockInfo, car_id: u64) -> StdResult<Option<CarUser>> {
+    Ok(contract.car_users
+        .may_load(storage, car_id)?
+        .filter(|car_user| !car_user.expires.is_expired(block)))
+}
+
+// Mientras dura un alquiler nadie puede cambiar las partes ni quemar el carro
+fn ensure_not_rented(storage: &dyn Storage, contract: &CarNftContract, block: &BlockInfo, car_id: u64) -> StdResult<()> {
+    if active_car_user(storage, contract, block, car_id)?.is_some() {
+        return Err(StdError::generic_err("El carro está alquilado"));
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_list_car_for_rent(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+    price: Uint128,
+    unit: RentalUnit,
+    max_units: u64,
+) -> StdResult<Response> {
+    ensure_car_owner(deps.storage, &contract, &info.sender, car_id)?;
+    if price.is_zero() || max_units == 0 {
+        return Err(StdError::generic_err("El alquiler necesita un precio y una duración máxima mayores a cero"));
+    }
+    // Cambiar la oferta no afecta un alquiler en curso
+    let listing = RentalListing {
+        owner: info.sender.clone(),
+        price,
+        unit,
+        max_units,
+    };
+    contract.rental_listings.save(deps.storage, car_id, &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "list_car_for_rent")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("price", listing.price.to_string())
+        .add_attribute("max_units", listing.max_units.to_string()))
+}
+
+fn execute_cancel_car_rental(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+) -> StdResult<Response> {
+    // Retirar la oferta; un alquiler en curso sigue hasta vencer
+    ensure_car_owner(deps.storage, &contract, &info.sender, car_id)?;
+    if !contract.rental_listings.has(deps.storage, car_id) {
+        return Err(StdError::generic_err("El carro no está en alquiler"));
+    }
+    contract.rental_listings.remove(deps.storage, car_id);
+
+    Ok(Response::new()
+        .add_attribute("method", "cancel_car_rental")
+        .add_attribute("car_id", car_id.to_string()))
+}
+
+// Alquilar `units` bloques u horas pagando el precio exacto. El dueño cobra al momento y
+// el porcentaje de `rental_fee` queda en el contrato.
+fn execute_rent_car(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+    units: u64,
+) -> StdResult<Response> {
+    let listing = contract.rental_listings
+        .may_load(deps.storage, car_id)?
+        .ok_or_else(|| StdError::generic_err("El carro no está en alquiler"))?;
+    if info.sender == listing.owner {
+        return Err(StdError::generic_err("No puedes alquilar tu propio carro"));
+    }
+    ensure_not_rented(deps.storage, &contract, &env.block, car_id)?;
+    if units == 0 || units > listing.max_units {
+        return Err(StdError::generic_err(format!(
+            "El alquiler debe durar entre 1 y {} unidades",
+            listing.max_units
+        )));
+    }
+
+    // El dueño fija precio y duración sin límites, así que el cálculo puede desbordar
+    let total = listing.price.checked_mul(Uint128::from(units))?;
+    let paid: Uint128 = info.funds.iter()
+        .filter(|coin| coin.denom == "uxion")
+        .map(|coin| coin.amount)
+        .sum();
+    if paid != total {
+        return Err(StdError::generic_err(format!("Debes pagar exactamente {}uxion", total)));
+    }
+    let fee_percent = contract.rental_fee.may_load(deps.storage)?.unwrap_or_default();
+    let fee = total.multiply_ratio(fee_percent as u128, 100u128);
+    let owner_share = total - fee;
+
+    let expires = rental_expiry(&env.block, &listing.unit, units)?;
+    let car_user = CarUser {
+        user: info.sender.clone(),
+        expires,
+    };
+    contract.car_users.save(deps.storage, car_id, &car_user)?;
+
+    let mut response = Response::new();
+    if !owner_share.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: listing.owner.to_string(),
+            amount: vec![Coin::new(owner_share.u128(), "uxion")],
+        });
+    }
+
+    Ok(response
+        .add_attribute("method", "rent_car")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("user", info.sender)
+        .add_attribute("expires", format!("{:?}", car_user.expires))
+        .add_attribute("owner_share", owner_share.to_string())
+        .add_attribute("fee", fee.to_string()))
+}
+
+// Vencimiento de un alquiler de `units` unidades que empieza en este bloque
+fn rental_expiry(block: &BlockInfo, unit: &RentalUnit, units: u64) -> StdResult<RentalExpiry> {
+    let overflow = |operation, operand1: u64, operand2: u64| {
+        StdError::overflow(OverflowError::new(operation, operand1, operand2))
+    };
+    match unit {
+        RentalUnit::Blocks => {
+            let height = block.height.checked_add(units)
+                .ok_or_else(|| overflow(OverflowOperation::Add, block.height, units))?;
+            Ok(RentalExpiry::AtHeight(height))
+        },
+        RentalUnit::Hours => {
+            let nanos = units.checked_mul(3600 * 1_000_000_000)
+                .ok_or_else(|| overflow(OverflowOperation::Mul, units, 3600 * 1_000_000_000))?;
+            let expires = block.time.nanos().checked_add(nanos)
+                .ok_or_else(|| overflow(OverflowOperation::Add, block.time.nanos(), nanos))?;
+            Ok(RentalExpiry::AtTime(Timestamp::from_nanos(expires)))
+        },
+    }
+}
+
+fn execute_set_rental_fee(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    percent: u8,
+) -> StdResult<Response> {
+    // Solo el admin puede fijar la comisión de los alquileres
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar la comisión de alquiler"));
+    }
+    if percent > 100 {
+        return Err(StdError::generic_err("La comisión no puede superar el 100%"));
+    }
+    contract.rental_fee.save(deps.storage, &percent)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_rental_fee")
+        .add_attribute("percent", percent.to_string()))
+}
+
+fn execute_set_royalties(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarNftContract,
+    payment_address: String,
+    percent: u8,
+) -> StdResult<Response> {
+    // Solo el admin puede fijar la regalía de la colección
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar las regalías"));
+    }
+    if percent > MAX_ROYALTY_PERCENT {
+        return Err(StdError::generic_err(format!("La regalía no puede superar el {}%", MAX_ROYALTY_PERCENT)));
+    }
+    let payment_address = deps.api.addr_validate(&payment_address)?;
+    contract.royalties.save(deps.storage, &RoyaltyConfig { payment_address: payment_address.clone(), percent })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_royalties")
+        .add_attribute("payment_address", payment_address)
+        .add_attribute("percent", percent.to_string()))
+}
+
+// Cambiar de dueño un carro junto con sus partes equipadas. Un carro alquilado no se puede
+// transferir; la oferta de alquiler se retira y las calcomanías vuelven sueltas al dueño anterior.
+fn transfer_car(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    contract: &CarNftContract,
+    car_id: u64,
+    recipient: &Addr,
+) -> StdResult<Response> {
+    ensure_car_owner(deps.storage, contract, &info.sender, car_id)?;
+    ensure_not_rented(deps.storage, contract, &env.block, car_id)?;
+    let mut car = contract.cars.load(deps.storage, car_id)?;
+
+    contract.owner_cars.remove(deps.storage, (info.sender.clone(), car_id));
+    contract.owner_cars.save(deps.storage, (recipient.clone(), car_id), &true)?;
+    contract.rental_listings.remove(deps.storage, car_id);
+    contract.car_users.remove(deps.storage, car_id);
+    if !car.decal_ids.is_empty() {
+        for decal_id in std::mem::take(&mut car.decal_ids) {
+            let mut decal = contract.decals.load(deps.storage, decal_id)?;
+            decal.car_id = 0;
+            contract.decals.save(deps.storage, decal_id, &decal)?;
+        }
+        mark_image_stale(deps.storage, contract, car_id, &mut car)?;
+        contract.cars.save(deps.storage, car_id, &car)?;
+    }
+
+    let mut response = Response::new();
+    let part_ids = equipped_part_ids(&car);
+    if !part_ids.is_empty() {
+        let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr: car_part_contract.to_string(),
+            msg: to_json_binary(&PartExecuteMsg::TransferCarParts {
+                from: info.sender.to_string(),
+                to: recipient.to_string(),
+                car_id,
+                part_ids,
+            })?,
+            funds: vec![],
+        });
+    }
+
+    Ok(response
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("from", info.sender.clone())
+        .add_attribute("to", recipient.clone()))
+}
+
+// Transferir el carro a un contrato y avisarle con ReceiveNft, como send_nft de CW721
+fn execute_send_car(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    car_id: u64,
+    recipient: String,
+    msg: Binary,
+) -> StdResult<Response> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let response = transfer_car(deps, &env, &info, &contract, car_id, &recipient)?;
+
+    let receive_msg = ReceiverExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+        sender: info.sender.to_string(),
+        token_id: car_id.to_string(),
+        msg,
+    });
+    Ok(response
+        .add_message(WasmMsg::Execute {
+            contract_addr: recipient.to_string(),
+            msg: to_json_binary(&receive_msg)?,
+            funds: vec![],
+        })
+        .add_attribute("method", "send_car"))
+}
+
+// Regalía CW2981 para una venta de `sale_price`; cero si no hay regalías configuradas
+fn query_royalty_info(deps: Deps, contract: CarNftContract, token_id: String, sale_price: Uint128) -> StdResult<RoyaltiesInfoResponse> {
+    let car_id: u64 = token_id.parse()
+        .map_err(|_| StdError::generic_err("ID de token inválido"))?;
+    if !contract.cars.has(deps.storage, car_id) {
+        return Err(StdError::generic_err("El carro no existe"));
+    }
+    Ok(match contract.royalties.may_load(deps.storage)? {
+        Some(royalties) => RoyaltiesInfoResponse {
+            address: royalties.payment_address.to_string(),
+            royalty_amount: sale_price.multiply_ratio(royalties.percent, 100u128),
+        },
+        None => RoyaltiesInfoResponse {
+            address: String::new(),
+            royalty_amount: Uint128::zero(),
+        },
+    })
+}
+
+fn load_car_model(storage: &dyn Storage, contract: &CarNftContract, model_id: u64) -> StdResult<CarModel> {
+    if model_id == 0 {
+        return Ok(CarModel::generic());
+    }
+    contract.car_models
+        .may_load(storage, model_id)?
+        .ok_or_else(|| StdError::generic_err(format!("El modelo {} no existe", model_id)))
+}
+
+// IDs de las partes equipadas en los slots ocupados del carro
+fn equipped_part_ids(car: &CarComposition) -> Vec<u64> {
+    car.part_ids
+        .iter()
+        .zip(car.slot_occupied.iter())
+        .filter(|(_, &occupied)| occupied)
+        .map(|(&part_id, _)| part_id)
+        .collect()
+}
+
+// La condición del carro es el promedio de la durabilidad de sus partes equipadas
+fn car_condition(deps: Deps, contract: &CarNftContract, car: &CarComposition) -> StdResult<u8> {
+    let part_ids = equipped_part_ids(car);
+    if part_ids.is_empty() {
+        return Ok(0);
+    }
+    let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+    let mut total = 0u32;
+    for &part_id in &part_ids {
+        let durability: u8 = deps.querier.query_wasm_smart(
+            car_part_contract.clone(),
+            &QueryMsg::GetPartDurability { part_id },
+        )?;
+        total += durability as u32;
+    }
+    Ok((total / part_ids.len() as u32) as u8)
+}
+
+fn execute_set_salvage_value(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: CarNftContract,
+    amount: Uint128,
+) -> StdResult<Response> {
+    // Solo el admin puede configurar el rescate
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar el rescate"));
+    }
+
+    contract.salvage_value.save(deps.storage, &amount)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_salvage_value")
+        .add_attribute("amount", amount.to_string()))
+}
+
+// Funciones auxiliares de minteo
+fn check_payment(deps: Deps, contract: &CarNftContract, info: &MessageInfo) -> StdResult<()> {
+    let mint_price = contract.mint_price.load(deps.storage)?;
+    let payment = info.funds.iter()
+        .find(|coin| coin.denom == "uxion")
+        .ok_or_else(|| StdError::generic_err("No se encontró el pago en uxion"))?;
+    
+    if payment.amount < mint_price {
+        return Err(StdError::generic_err("Pago insuficiente"));
+    }
+    Ok(())
+}
+
+fn validate_stats_mode(mode: &StatsMode) -> StdResult<()> {
+    match mode {
+        StatsMode::Signed { public_key, .. } if public_key.is_empty() => {
+            Err(StdError::generic_err("Falta la clave pública del backend"))
+        },
+        StatsMode::CommitReveal { reveal_window: 0 } => {
+            Err(StdError::generic_err("La ventana de revelación debe ser mayor que 0"))
+        },
+        _ => Ok(()),
+    }
+}
+
+// Hash que firma el backend: sha256 del JSON de SignedStats { minter, nonce, parts_data }
+pub fn signed_stats_hash(minter: &str, nonce: u64, parts_data: &[PartData]) -> StdResult<Vec<u8>> {
+    let payload = SignedStats {
+        minter: minter.to_string(),
+        nonce,
+        parts_data: parts_data.to_vec(),
+    };
+    Ok(Sha256::digest(to_json_binary(&payload)?.as_slice()).to_vec())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn verify_signed_stats(
+    deps: DepsMut,
+    contract: &CarNftContract,
+    minter: &Addr,
+    nonce: u64,
+    parts_data: &[PartData],
+    scheme: &SignatureScheme,
+    public_key: &Binary,
+    signature: &Binary,
+) -> StdResult<()> {
+    // Cada firma solo se puede usar una vez
+    if contract.used_nonces.has(deps.storage, (minter, nonce)) {
+        return Err(StdError::generic_err("El nonce ya fue usado"));
+    }
+
+    let hash = signed_stats_hash(minter.as_str(), nonce, parts_data)?;
+    let valid = match scheme {
+        SignatureScheme::Secp256k1 => deps.api.secp256k1_verify(&hash, signature, public_key)?,
+        SignatureScheme::Ed25519 => deps.api.ed25519_verify(&hash, signature, public_key)?,
+    };
+    if !valid {
+        return Err(StdError::generic_err("La firma de los stats no es válida"));
+    }
+
+    contract.used_nonces.save(deps.storage, (minter, nonce), &true)?;
+    Ok(())
+}
+
+// Verificar el secreto contra el commit pendiente y derivar la semilla con `randomness::derive_seed`:
+// el secreto del jugador, la semilla del servidor de juego y los bloques fijados en el commit.
+// El bloque de revelación no entra en la semilla, así revertir la transacción no cambia el resultado.
+fn reveal_mint_seed(
+    deps: DepsMut,
+    env: &Env,
+    contract: &CarNftContract,
+    minter: &Addr,
+    secret: &str,
+    reveal_window: u64,
+) -> StdResult<Vec<u8>> {
+    let commit = contract.mint_commits
+        .may_load(deps.storage, minter)?
+        .ok_or_else(|| StdError::generic_err("No hay un commit pendiente para este jugador"))?;
+
+    if env.block.height <= commit.height {
+        return Err(StdError::generic_err("El secreto se debe revelar en un bloque posterior al commit"));
+    }
+    if env.block.height > commit.height + reveal_window {
+        return Err(StdError::generic_err("El plazo para revelar el secreto expiró"));
+    }
+    let server_seed = commit.server_seed
+        .ok_or_else(|| StdError::generic_err("El servidor de juego todavía no publicó su semilla"))?;
+    randomness::check_reveal(&commit.commitment, secret).map_err(StdError::generic_err)?;
+    contract.mint_commits.remove(deps.storage, minter);
+
+    Ok(randomness::derive_seed(
+        &[(minter.as_str(), secret), ("game_server", &server_seed)],
+        commit.height,
+        commit.height + reveal_window,
+    ))
+}
+
+// Probabilidad (sobre 100) de cada rareza al tirar los stats
+fn roll_rarity(roll: u8) -> Rarity {
+    match (roll as u16 * 100) / 256 {
+        0..=59 => Rarity::Common,
+        60..=84 => Rarity::Rare,
+        85..=96 => Rarity::Epic,
+        _ => Rarity::Legendary,
+    }
+}
+
+// Ajustar una rareza tirada a las que admite el modelo: la mayor permitida que no la supere,
+// o la menor permitida si todas la superan
+fn fit_rarity(rarity: Rarity, allowed: &[Rarity]) -> Rarity {
+    if allowed.is_empty() || allowed.contains(&rarity) {
+        return rarity;
+    }
+    allowed
+        .iter()
+        .filter(|allowed| allowed.rank() < rarity.rank())
+        .max_by_key(|allowed| allowed.rank())
+        .or_else(|| allowed.iter().min_by_key(|allowed| allowed.rank()))
+        .cloned()
+        .unwrap_or(rarity)
+}
+
+// Tirar rareza y stats de cada parte. Solo se conservan el tipo y la imagen que envió el jugador.
+fn roll_parts_stats(
+    deps: Deps,
+    contract: &CarNftContract,
+    seed: &[u8],
+    parts_data: Vec<PartData>,
+    model: &CarModel,
+) -> StdResult<Vec<PartData>> {
+    let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+    parts_data
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| {
+            let roll = Sha256::new()
+                .chain_update(seed)
+                .chain_update((index as u64).to_be_bytes())
+                .finalize();
+            let rarity = fit_rarity(roll_rarity(roll[0]), &model.allowed_rarities);
+            let bounds: StatBounds = deps.querier.query_wasm_smart(
+                car_part_contract.clone(),
+                &QueryMsg::GetRarityBounds { rarity: rarity.clone() },
+            )?;
+            let span = bounds.max.saturating_sub(bounds.min) + 1;
+            Ok(PartData {
+                stat1: bounds.min + roll[1] % span,
+                stat2: bounds.min + roll[2] % span,
+                stat3: bounds.min + roll[3] % span,
+                rarity,
+                ..part
+            })
+        })
+        .collect()
+}
+
+// Funciones auxiliares
+fn query_car_composition(deps: Deps, contract: CarNftContract, car_id: u64) -> StdResult<CarComposition> {
+    contract.cars.load(deps.storage, car_id)
+}
+
+// Funciones auxiliares de consulta
+fn query_owner_cars(deps: Deps, contract: CarNftContract, owner: String) -> StdResult<OwnerCarsResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let last_token_id = contract.current_car_id.load(deps.storage)?;
+    let mut car_ids = Vec::new();
+
+    // Iterar sobre todos los posibles IDs de carros y verificar si pertenecen al propietario
+    for car_id in 1..last_token_id {
+        if contract.owner_cars.may_load(deps.storage, (owner_addr.clone(), car_id))?.unwrap_or(false) {
+            car_ids.push(car_id);
+        }
+    }
+
+    Ok(OwnerCarsResponse { car_ids })
+}
+
+fn query_compact_car_stats(deps: Deps, contract: CarNftContract, car_id: u64) -> StdResult<CompactCarStats> {
+    Ok(query_full_car_metadata(deps, contract, car_id)?.total_stats)
+}
+
+// Metadata CW721 del carro, calculada en vivo a partir de sus partes y su modelo
+fn query_nft_info(deps: Deps, contract: CarNftContract, token_id: String, resolve_ipfs: bool) -> StdResult<NftInfoResponse> {
+    let car_id: u64 = token_id
+        .parse()
+        .map_err(|_| StdError::generic_err(format!("token_id inválido: {}", token_id)))?;
+    let car = contract.cars.load(deps.storage, car_id)?;
+    let model_name = load_car_model(deps.storage, &contract, car.model_id)?.name;
+    // Atributos cosméticos: no afectan los stats
+    let mut cosmetic_traits = vec![];
+    if let Some(cosmetic) = contract.cosmetics.may_load(deps.storage, car.cosmetic_id)? {
+        cosmetic_traits.push(Trait::text(&cosmetic.kind.to_string(), &cosmetic.name));
+    }
+    for &decal_id in &car.decal_ids {
+        cosmetic_traits.push(Trait::text("decal", &contract.decals.load(deps.storage, decal_id)?.name));
+    }
+    let gateway = contract.image_gateway.may_load(deps.storage)?;
+    let metadata = query_full_car_metadata(deps, contract, car_id)?;
+    let stats = &metadata.total_stats;
+
+    let mut attributes = vec![
+        Trait::number("speed", stats.speed),
+        Trait::number("acceleration", stats.acceleration),
+        Trait::number("handling", stats.handling),
+        Trait::number("drift_factor", stats.drift_factor),
+        Trait::number("turn_factor", stats.turn_factor),
+        Trait::number("max_speed", stats.max_speed),
+        Trait::number("condition", stats.condition),
+        Trait::text("model", &model_name),
+    ];
+    // Un atributo por slot ocupado con el ID de la parte equipada
+    for part in &metadata.parts {
+        attributes.push(Trait::number(&format!("{} slot", part.part_type), part.part_id));
+    }
+    attributes.extend(cosmetic_traits);
+
+    Ok(NftInfoResponse {
+        token_uri: None,
+        extension: Metadata {
+            name: car.name.unwrap_or_else(|| format!("Car #{}", car_id)),
+            description: format!("Carro {} de Speed Rush 2D", model_name),
+            image: if resolve_ipfs {
+                resolve_uri(&metadata.car_image_uri, gateway.as_deref())
+            } else {
+                metadata.car_image_uri
+            },
+            attributes,
+        },
+    })
+}
+
+fn query_full_car_metadata(deps: Deps, contract: CarNftContract, car_id: u64) -> StdResult<FullCarMetadata> {
+    let car = contract.cars.load(deps.storage, car_id)?;
+    // La condición se deriva de la durabilidad de las partes equipadas
+    let condition = car_condition(deps, &contract, &car)?;
+    let model = load_car_model(deps.storage, &contract, car.model_id)?;
+    let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+    let mut parts = Vec::new();
+    let mut totals = [0u16; CAR_STAT_NAMES.len()];
+
+    // Obtener los metadatos de cada parte
+    for (slot_index, &part_id) in car.part_ids.iter().enumerate() {
+        if car.slot_occupied[slot_index] {
+            // Consultar stats de la parte
+            let query_msg = QueryMsg::GetPartStats { part_id };
+            let part_stats: PartStats = deps.querier.query_wasm_smart(
+                car_part_contract.clone(),
+                &query_msg,
+            )?;
+
+            let part_type = part_stats.part_type.clone();
+
+            // Cada stat de la parte suma al stat del carro que nombra su tipo
+            let stats = [part_stats.stat1, part_stats.stat2, part_stats.stat3];
+            for (name, value) in part_type.stat_names().iter().zip(stats) {
+                let index = CAR_STAT_NAMES.iter().position(|stat| stat == name)
+                    .ok_or_else(|| StdError::generic_err(format!("Stat desconocido: {}", name)))?;
+                totals[index] += value as u16;
+            }
+
+            parts.push(PartMetadata {
+                part_id,
+                part_type,
+                stats: part_stats,
+                slot_index: slot_index as u8,
+            });
+        }
+    }
+
+    // Todos los stats se dividen por la cantidad de slots del carro, no por las partes equipadas,
+    // así quitar una parte nunca sube un stat. Después se suman los stats base del modelo y se
+    // limita cada uno a su tope.
+    let slot_count = (car.part_ids.len() as u16).max(1);
+    let [speed, acceleration, handling, drift_factor, turn_factor, max_speed] = totals.map(|total| total / slot_count);
+    let apply_model = |value: u16, base: u8, cap: u8| (value + base as u16).min(cap as u16) as u8;
+    let base = &model.base_stats;
+    let caps = &model.stat_caps;
+    let total_stats = CompactCarStats {
+        image_uri: car.car_image_uri.clone(),
+        speed: apply_model(speed, base.speed, caps.speed),
+        acceleration: apply_model(acceleration, base.acceleration, caps.acceleration),
+        handling: apply_model(handling, base.handling, caps.handling),
+        drift_factor: apply_model(drift_factor, base.drift_factor, caps.drift_factor),
+        turn_factor: apply_model(turn_factor, base.turn_factor, caps.turn_factor),
+        max_speed: apply_model(max_speed, base.max_speed, caps.max_speed),
+        condition,
+    };
+
+    Ok(FullCarMetadata {
+        car_id,
+        model_id: car.model_id,
+        car_image_uri: car.car_image_uri,
+        parts,
+        total_stats,
+    })
+}
+
+fn query_car_models(deps: Deps, contract: CarNftContract) -> StdResult<CarModelsResponse> {
+    let models = contract.car_models
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(model_id, model)| CarModelInfo { model_id, model }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(CarModelsResponse { models })
+}
+
+// Carros pendientes de un nuevo render, en orden de ID
+fn query_stale_cars(deps: Deps, contract: CarNftContract, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<u64>> {
+    let limit = limit.unwrap_or(MAX_STALE_CARS_LIMIT).min(MAX_STALE_CARS_LIMIT) as usize;
+    contract.stale_cars
+        .keys(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+fn query_cosmetics(deps: Deps, contract: CarNftContract) -> StdResult<Vec<CosmeticInfo>> {
+    contract.cosmetics
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(cosmetic_id, cosmetic)| CosmeticInfo { cosmetic_id, cosmetic }))
+        .collect()
+}
+
+fn query_owner_decals(deps: Deps, contract: CarNftContract, owner: String) -> StdResult<Vec<u64>> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    contract.owner_decals
+        .prefix(owner_addr)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+fn query_last_token_id(deps: Deps, contract: CarNftContract) -> StdResult<u64> {
+    contract.current_car_id.load(deps.storage)
+}
+
+// Funciones de consulta
+fn query_part_stats(deps: Deps, contract: CarNftContract, part_id: u64) -> StdResult<PartStats> {
+    let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+    deps.querier.query_wasm_smart(
+        car_part_contract,
+        &QueryMsg::GetPartStats { part_id },
+    )
+}
+
+fn query_part_type(deps: Deps, contract: CarNftContract, part_id: u64) -> StdResult<PartType> {
+    let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+    deps.querier.query_wasm_smart(
+        car_part_contract,
+        &QueryMsg::GetPartType { part_id },
+    )
+}
+
+fn query_part_owner(deps: Deps, contract: CarNftContract, part_id: u64) -> StdResult<PartOwnerResponse> {
+    let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+    deps.querier.query_wasm_smart(
+        car_part_contract,
+        &QueryMsg::GetPartOwner { part_id },
+    )
+}
+
+// Función para obtener todos los metadatos de los carros de un usuario
+pub fn query_all_car_metadata(
+    deps: Deps,
+    contract: CarNftContract,
+    owner: String,
+) -> StdResult<AllCarMetadataResponse> {
+    let _owner_addr = deps.api.addr_validate(&owner)?;
+    let owner_cars = query_owner_cars(deps, contract.clone(), owner)?;
+    
+    let mut cars = Vec::new();
+    for car_id in owner_cars.car_ids {
+        if let Ok(car_metadata) = query_full_car_metadata(deps, contract.clone(), car_id) {
+            cars.push(car_metadata);
+        }
+    }
+    
+    Ok(AllCarMetadataResponse { cars })
+}
+
+fn query_mint_commit(deps: Deps, contract: CarNftContract, owner: String) -> StdResult<Option<MintCommit>> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    contract.mint_commits.may_load(deps.storage, &owner_addr)
+}
+
+fn query_rarity_bounds(deps: Deps, contract: CarNftContract, rarity: Rarity) -> StdResult<StatBounds> {
+    let car_part_contract = contract.car_part_contract.load(deps.storage)?;
+    deps.querier.query_wasm_smart(
+        car_part_contract,
+        &QueryMsg::GetRarityBounds { rarity },
+    )
+}
+
+// Función para consultar el precio de minteo
+fn query_mint_price(deps: Deps, contract: CarNftContract) -> StdResult<Uint128> {
+    contract.mint_price.load(deps.storage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+    use cosmwasm_std::{coins, from_json, CosmosMsg, MemoryStorage, OwnedDeps};
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+    use k256::ecdsa::{Signature, SigningKey};
+
+    // Clave del backend usada en los tests del modo Signed
+    fn backend_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32].into()).unwrap()
+    }
+
+    fn signed_mode() -> StatsMode {
+        StatsMode::Signed {
+            scheme: SignatureScheme::Secp256k1,
+            public_key: Binary::from(backend_key().verifying_key().to_encoded_point(true).as_bytes()),
+        }
+    }
+
+    // MintCar con los stats firmados por el backend
+    fn signed_mint_car(minter: &str, nonce: u64, parts_data: Vec<PartData>) -> ExecuteMsg {
+        let hash = signed_stats_hash(minter, nonce, &parts_data).unwrap();
+        let signature: Signature = backend_key().sign_prehash(&hash).unwrap();
+        ExecuteMsg::MintCar {
+            car_image_uri: "ipfs://car_uri".to_string(),
+            parts_data,
+            proof: StatsProof::Signature {
+                nonce,
+                signature: Binary::from(signature.to_bytes().as_slice()),
+            },
+            model_id: 0,
+        }
+    }
+
+    // Simula GetPartStats del contrato de partes: cada part_id es una parte común del tipo part_types[part_id % len]
+    fn mock_part_types(deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier>, part_types: Vec<PartType>) {
+        deps.querier.update_wasm(move |query| match query {
+            cosmwasm_std::WasmQuery::Smart { msg, .. } => match from_json::<QueryMsg>(msg).unwrap() {
+                QueryMsg::GetPartStats { part_id } => {
+                    let part_stats = PartStats {
+                        part_type: part_types[part_id as usize % part_types.len()].clone(),
+                        stat1: 5,
+                        stat2: 5,
+                        stat3: 5,
+                        image_uri: "ipfs://part_uri".to_string(),
+                        rarity: Rarity::Common,
+                    };
+                    cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(to_json_binary(&part_stats).unwrap()))
+                },
+                QueryMsg::GetPartDurability { .. } => {
+                    cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(to_json_binary(&100u8).unwrap()))
+                },
+                // Las partes son sueltas de "creator", salvo la 20, en custodia del marketplace,
+                // y la 21, equipada en el carro 5
+                QueryMsg::GetPartOwner { part_id } => {
+                    let part_owner = PartOwnerResponse {
+                        owner: if part_id == 20 { "marketplace" } else { "creator" }.to_string(),
+                        equipped_in_car: if part_id == 21 { 5 } else { 0 },
+                    };
+                    cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(to_json_binary(&part_owner).unwrap()))
+                },
+                _ => panic!("Consulta inesperada"),
+            },
+            _ => panic!("Consulta inesperada"),
+        });
+    }
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: None,
+        };
+
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(4, res.attributes.len());
+    }
+
+    #[test]
+    fn test_mint_car() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = "creator";
+        let info = mock_info(creator, &coins(1000000, "uxion"));
+
+        // Inicializar el contrato
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Crear datos de prueba para las partes
+        let parts_data = vec![
+            PartData {
+                part_type: PartType::Engine,
+                stat1: 8,
+                stat2: 7,
+                stat3: 6,
+                image_uri: "ipfs://engine_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+            PartData {
+                part_type: PartType::Transmission,
+                stat1: 5,
+                stat2: 6,
+                stat3: 7,
+                image_uri: "ipfs://transmission_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+            PartData {
+                part_type: PartType::Wheels,
+                stat1: 4,
+                stat2: 5,
+                stat3: 6,
+                image_uri: "ipfs://wheels_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+        ];
+
+        // Intentar mintear un carro
+        let msg = signed_mint_car("creator", 0, parts_data.clone());
+
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        
+        // Verificar que se crearon los mensajes correctos
+        assert_eq!(3, res.messages.len()); // Un mensaje por cada parte
+        
+        // Verificar que se guardó la composición del carro
+        let query_msg = QueryMsg::GetCarComposition { car_id: 1 };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let car_comp: CarComposition = from_json(&res).unwrap();
+        
+        assert_eq!(3, car_comp.part_ids.len());
+        assert_eq!(3, car_comp.slot_occupied.len());
+        assert_eq!("ipfs://car_uri", car_comp.car_image_uri);
+
+        // Verificar que el carro se registró para el propietario
+        let query_msg = QueryMsg::GetOwnerCars { owner: creator.to_string() };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let owner_cars: OwnerCarsResponse = from_json(&res).unwrap();
+        assert_eq!(vec![1], owner_cars.car_ids);
+    }
+
+    #[test]
+    fn test_mint_car_insufficient_payment() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = "creator";
+        let info = mock_info(creator, &coins(500000, "uxion")); // Pago insuficiente
+
+        // Inicializar el contrato
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Crear datos de prueba
+        let parts_data = vec![
+            PartData {
+                part_type: PartType::Engine,
+                stat1: 8,
+                stat2: 7,
+                stat3: 6,
+                image_uri: "ipfs://engine_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+        ];
+
+        // Intentar mintear un carro
+        let msg = signed_mint_car("creator", 0, parts_data);
+
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(err.to_string().contains("Pago insuficiente"));
+    }
+
+    #[test]
+    fn test_mint_car_missing_parts() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = "creator";
+        let info = mock_info(creator, &coins(1000000, "uxion"));
+
+        // Inicializar el contrato
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Crear datos de prueba sin todas las partes necesarias
+        let parts_data = vec![
+            PartData {
+                part_type: PartType::Engine,
+                stat1: 8,
+                stat2: 7,
+                stat3: 6,
+                image_uri: "ipfs://engine_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+            // Falta transmisión y ruedas
+        ];
+
+        // Intentar mintear un carro
+        let msg = signed_mint_car("creator", 0, parts_data);
+
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(err.to_string().contains("Faltan partes necesarias"));
+    }
+
+    #[test]
+    fn test_equip_unequip_part() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = "creator";
+        let info = mock_info(creator, &coins(1000000, "uxion"));
+
+        // Inicializar el contrato
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Crear un carro con todas sus partes
+        let parts_data = vec![
+            PartData {
+                part_type: PartType::Engine,
+                stat1: 8,
+                stat2: 7,
+                stat3: 6,
+                image_uri: "ipfs://engine_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+            PartData {
+                part_type: PartType::Transmission,
+                stat1: 5,
+                stat2: 6,
+                stat3: 7,
+                image_uri: "ipfs://transmission_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+            PartData {
+                part_type: PartType::Wheels,
+                stat1: 4,
+                stat2: 5,
+                stat3: 6,
+                image_uri: "ipfs://wheels_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+        ];
+
+        let msg = signed_mint_car("creator", 0, parts_data);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        mock_part_types(&mut deps, vec![PartType::Engine, PartType::Transmission, PartType::Wheels]);
+
+        // Desequipar una parte
+        let msg = ExecuteMsg::UnequipPart {
+            car_id: 1,
+            part_id: 0, // El motor
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Verificar que la parte se desequipó
+        let query_msg = QueryMsg::GetCarComposition { car_id: 1 };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let car_comp: CarComposition = from_json(&res).unwrap();
+        assert_eq!(0, car_comp.part_ids[0]); // El slot del motor debe estar vacío
+        assert!(!car_comp.slot_occupied[0]); // El slot no debe estar ocupado
+
+        // Equipar una parte en un slot vacío
+        let msg = ExecuteMsg::EquipPart {
+            car_id: 1,
+            part_id: 0,
+            slot_index: 0,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Verificar que la parte se equipó correctamente
+        let query_msg = QueryMsg::GetCarComposition { car_id: 1 };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let car_comp: CarComposition = from_json(&res).unwrap();
+        assert_eq!(0, car_comp.part_ids[0]); // El slot del motor debe tener la parte
+        assert!(car_comp.slot_occupied[0]); // El slot debe estar ocupado
+    }
+
+    #[test]
+    fn test_replace_part() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = "creator";
+        let info = mock_info(creator, &coins(1000000, "uxion"));
+
+        // Inicializar el contrato
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Crear un carro con todas sus partes
+        let parts_data = vec![
+            PartData {
+                part_type: PartType::Engine,
+                stat1: 8,
+                stat2: 7,
+                stat3: 6,
+                image_uri: "ipfs://engine_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+            PartData {
+                part_type: PartType::Transmission,
+                stat1: 5,
+                stat2: 6,
+                stat3: 7,
+                image_uri: "ipfs://transmission_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+            PartData {
+                part_type: PartType::Wheels,
+                stat1: 4,
+                stat2: 5,
+                stat3: 6,
+                image_uri: "ipfs://wheels_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+        ];
+
+        let msg = signed_mint_car("creator", 0, parts_data);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        mock_part_types(&mut deps, vec![PartType::Engine, PartType::Transmission, PartType::Wheels]);
+
+        // Reemplazar una parte
+        let msg = ExecuteMsg::ReplacePart {
+            car_id: 1,
+            old_part_id: 0, // El motor original
+            new_part_id: 3, // Un nuevo motor
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Verificar que la parte se reemplazó correctamente
+        let query_msg = QueryMsg::GetCarComposition { car_id: 1 };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let car_comp: CarComposition = from_json(&res).unwrap();
+        assert_eq!(3, car_comp.part_ids[0]); // El slot del motor debe tener la nueva parte
+        assert!(car_comp.slot_occupied[0]); // El slot debe seguir ocupado
+    }
+
+    #[test]
+    fn test_equip_foreign_part() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &coins(1000000, "uxion"));
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), signed_mint_car("creator", 0, test_parts_data())).unwrap();
+        mock_part_types(&mut deps, vec![PartType::Engine, PartType::Transmission, PartType::Wheels]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::UnequipPart { car_id: 1, part_id: 0 }).unwrap();
+
+        // Una parte en custodia del marketplace no se puede equipar aunque su tipo encaje
+        let equip = |part_id| ExecuteMsg::EquipPart { car_id: 1, part_id, slot_index: 0 };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), equip(20)).unwrap_err();
+        assert!(err.to_string().contains("La parte no pertenece al dueño del carro"));
+
+        // Tampoco una parte equipada en otro carro, ni al equipar ni al reemplazar
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), equip(21)).unwrap_err();
+        assert!(err.to_string().contains("La parte ya está equipada en el carro 5"));
+        let replace = |new_part_id| ExecuteMsg::ReplacePart { car_id: 1, old_part_id: 0, new_part_id };
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), replace(20)).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), replace(21)).is_err());
+        let car = CarNftContract::default().cars.load(&deps.storage, 1).unwrap();
+        assert_eq!(vec![0, 0, 0], car.part_ids);
+    }
+
+    #[test]
+    fn test_mint_car_forwards_rarity() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &coins(1000000, "uxion"));
+
+        // Inicializar el contrato
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Un motor épico junto a partes comunes
+        let parts_data = vec![
+            PartData {
+                part_type: PartType::Engine,
+                stat1: 8,
+                stat2: 7,
+                stat3: 6,
+                image_uri: "ipfs://engine_uri".to_string(),
+                rarity: Rarity::Epic,
+            },
+            PartData {
+                part_type: PartType::Transmission,
+                stat1: 5,
+                stat2: 6,
+                stat3: 7,
+                image_uri: "ipfs://transmission_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+            PartData {
+                part_type: PartType::Wheels,
+                stat1: 4,
+                stat2: 5,
+                stat3: 6,
+                image_uri: "ipfs://wheels_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+        ];
+        let msg = signed_mint_car("creator", 0, parts_data);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // La rareza viaja en el mensaje de minteo hacia el contrato de partes
+        match &res.messages[0].msg {
+            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                let mint_msg: PartExecuteMsg = from_json(msg).unwrap();
+                match mint_msg {
+                    PartExecuteMsg::Mint { rarity, .. } => assert_eq!(Rarity::Epic, rarity),
+                    _ => panic!("Se esperaba un mensaje de minteo"),
+                }
+            },
+            _ => panic!("Se esperaba un mensaje wasm"),
+        }
+    }
+
+    fn test_parts_data() -> Vec<PartData> {
+        vec![
+            PartData {
+                part_type: PartType::Engine,
+                stat1: 10,
+                stat2: 10,
+                stat3: 10,
+                image_uri: "ipfs://engine_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+            PartData {
+                part_type: PartType::Transmission,
+                stat1: 10,
+                stat2: 10,
+                stat3: 10,
+                image_uri: "ipfs://transmission_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+            PartData {
+                part_type: PartType::Wheels,
+                stat1: 10,
+                stat2: 10,
+                stat3: 10,
+                image_uri: "ipfs://wheels_uri".to_string(),
+                rarity: Rarity::Common,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_signed_stats_cannot_be_changed_or_replayed() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &coins(1000000, "uxion"));
+
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // El jugador cambia los stats después de que el backend los firmó
+        let mut msg = signed_mint_car("creator", 1, test_parts_data());
+        if let ExecuteMsg::MintCar { parts_data, .. } = &mut msg {
+            parts_data[0].stat1 = 9;
+        }
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(err.to_string().contains("La firma de los stats no es válida"));
+
+        // Una firma hecha para otro jugador tampoco sirve
+        let msg = signed_mint_car("otro", 1, test_parts_data());
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), msg).is_err());
+
+        // La firma correcta se acepta una sola vez
+        let msg = signed_mint_car("creator", 1, test_parts_data());
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(err.to_string().contains("El nonce ya fue usado"));
+
+        // Una prueba de commit-reveal no sirve en modo Signed
+        let msg = ExecuteMsg::MintCar {
+            car_image_uri: "ipfs://car_uri".to_string(),
+            parts_data: test_parts_data(),
+            proof: StatsProof::Reveal { secret: "secreto".to_string() },
+            model_id: 0,
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(err.to_string().contains("no corresponde al modo activo"));
+    }
+
+    #[test]
+    fn test_commit_reveal_rolls_stats() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &coins(1000000, "uxion"));
+
+        // Sin modo explícito se usa commit-reveal
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // El contrato de partes responde con los límites de cada rareza
+        deps.querier.update_wasm(|query| match query {
+            cosmwasm_std::WasmQuery::Smart { msg, .. } => {
+                let bounds = match from_json::<QueryMsg>(msg).unwrap() {
+                    QueryMsg::GetRarityBounds { rarity: Rarity::Common } => StatBounds { min: 1, max: 4 },
+                    QueryMsg::GetRarityBounds { .. } => StatBounds { min: 5, max: 10 },
+                    _ => panic!("Consulta inesperada"),
+                };
+                cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(to_json_binary(&bounds).unwrap()))
+            },
+            _ => panic!("Consulta inesperada"),
+        });
+
+        // Revelar sin commit previo falla
+        let reveal_msg = ExecuteMsg::MintCar {
+            car_image_uri: "ipfs://car_uri".to_string(),
+            parts_data: test_parts_data(),
+            proof: StatsProof::Reveal { secret: "mi secreto".to_string() },
+            model_id: 0,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), reveal_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("No hay un commit pendiente"));
+
+        // Sin servidor de juego no se aceptan commits
+        let commitment = randomness::commitment_of("mi secreto");
+        let commit_msg = ExecuteMsg::CommitMint { commitment: commitment.clone() };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), commit_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("servidor de juego no está configurado"));
+
+        // Solo el admin designa el servidor de juego
+        let set_server = ExecuteMsg::SetGameServer { address: "game_server".to_string() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("player", &[]), set_server.clone()).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), set_server).unwrap();
+
+        // El commit requiere el pago del minteo
+        let err = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), commit_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("No se encontró el pago"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), commit_msg).unwrap();
+
+        // No se puede revelar en el mismo bloque del commit
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), reveal_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("bloque posterior"));
+
+        // Ni antes de que el servidor publique su semilla
+        env.block.height += 1;
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), reveal_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("todavía no publicó su semilla"));
+
+        // Solo el servidor de juego publica la semilla, y una sola vez
+        let seed_msg = ExecuteMsg::SubmitMintSeed { minter: "creator".to_string(), seed: "semilla".to_string() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), seed_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("Solo el servidor de juego"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("game_server", &[]), seed_msg.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("game_server", &[]), seed_msg).unwrap_err();
+        assert!(err.to_string().contains("ya publicó su semilla"));
+
+        // Un secreto distinto no corresponde al commit
+        let wrong_msg = ExecuteMsg::MintCar {
+            car_image_uri: "ipfs://car_uri".to_string(),
+            parts_data: test_parts_data(),
+            proof: StatsProof::Reveal { secret: "otro secreto".to_string() },
+            model_id: 0,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), wrong_msg).unwrap_err();
+        assert!(err.to_string().contains("no corresponde al commit"));
+
+        // Al revelar, los stats enviados por el jugador se ignoran y se tiran dentro de los límites
+        let info_without_funds = mock_info("creator", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info_without_funds, reveal_msg).unwrap();
+        assert_eq!(3, res.messages.len());
+        for submsg in &res.messages {
+            if let cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = &submsg.msg {
+                if let PartExecuteMsg::Mint { stat1, stat2, stat3, rarity, .. } = from_json(msg).unwrap() {
+                    let bounds = if rarity == Rarity::Common { (1, 4) } else { (5, 10) };
+                    for stat in [stat1, stat2, stat3] {
+                        assert!(stat >= bounds.0 && stat <= bounds.1);
+                    }
+                }
+            }
+        }
+
+        // El commit se consume al revelarlo
+        let query_msg = QueryMsg::GetMintCommit { owner: "creator".to_string() };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let commit: Option<MintCommit> = from_json(&res).unwrap();
+        assert_eq!(None, commit);
+
+        // Un commit que no se revela a tiempo expira
+        let commit_msg = ExecuteMsg::CommitMint { commitment };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), commit_msg).unwrap();
+        env.block.height += DEFAULT_REVEAL_WINDOW + 1;
+        let reveal_msg = ExecuteMsg::MintCar {
+            car_image_uri: "ipfs://car_uri".to_string(),
+            parts_data: test_parts_data(),
+            proof: StatsProof::Reveal { secret: "mi secreto".to_string() },
+            model_id: 0,
+        };
+        let err = execute(deps.as_mut(), env, info, reveal_msg).unwrap_err();
+        assert!(err.to_string().contains("expiró"));
+    }
+
+    #[test]
+    fn test_reveal_height_does_not_change_stats() {
+        // Mintear con el mismo secreto y la misma semilla del servidor, revelando en un bloque dado
+        let roll_at = |reveal_height: u64| -> Vec<PartExecuteMsg> {
+            let mut deps = mock_dependencies();
+            let mut env = mock_env();
+            let info = mock_info("creator", &coins(1000000, "uxion"));
+            let msg = InstantiateMsg {
+                car_part_contract: "car_part_contract".to_string(),
+                mint_price: Uint128::new(1000000),
+                stats_mode: None,
+            };
+            let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            deps.querier.update_wasm(|query| match query {
+                cosmwasm_std::WasmQuery::Smart { .. } => {
+                    let bounds = StatBounds { min: 1, max: 10 };
+                    cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(to_json_binary(&bounds).unwrap()))
+                },
+                _ => panic!("Consulta inesperada"),
+            });
+            let set_server = ExecuteMsg::SetGameServer { address: "game_server".to_string() };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), set_server).unwrap();
+
+            let commit_msg = ExecuteMsg::CommitMint { commitment: randomness::commitment_of("mi secreto") };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), commit_msg).unwrap();
+            let seed_msg = ExecuteMsg::SubmitMintSeed { minter: "creator".to_string(), seed: "semilla".to_string() };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("game_server", &[]), seed_msg).unwrap();
+
+            env.block.height += reveal_height;
+            env.block.time = env.block.time.plus_seconds(reveal_height * 5);
+            let reveal_msg = ExecuteMsg::MintCar {
+                car_image_uri: "ipfs://car_uri".to_string(),
+                parts_data: test_parts_data(),
+                proof: StatsProof::Reveal { secret: "mi secreto".to_string() },
+                model_id: 0,
+            };
+            let res = execute(deps.as_mut(), env, mock_info("creator", &[]), reveal_msg).unwrap();
+            res.messages
+                .iter()
+                .filter_map(|submsg| match &submsg.msg {
+                    cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => from_json(msg).ok(),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // Revelar en otro bloque del plazo da exactamente las mismas partes
+        let early = roll_at(1);
+        assert_eq!(3, early.len());
+        assert_eq!(early, roll_at(DEFAULT_REVEAL_WINDOW));
+    }
+
+    #[test]
+    fn test_set_stats_mode_requires_admin() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let set_mode_msg = ExecuteMsg::SetStatsMode { mode: signed_mode() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("player", &[]), set_mode_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), set_mode_msg).unwrap();
+        let res = query(deps.as_ref(), env, QueryMsg::GetStatsMode {}).unwrap();
+        let mode: StatsMode = from_json(&res).unwrap();
+        assert_eq!(signed_mode(), mode);
+    }
+
+    #[test]
+    fn test_burn_car() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &coins(1000000, "uxion"));
+
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Dos carros; en los tests unitarios no hay reply, así que se asignan los IDs de las partes a mano
+        let contract = CarNftContract::default();
+        for (nonce, car_id) in [(0, 1u64), (1, 2u64)] {
+            let msg = signed_mint_car("creator", nonce, test_parts_data());
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            let mut car = contract.cars.load(&deps.storage, car_id).unwrap();
+            car.part_ids = vec![car_id * 10, car_id * 10 + 1, car_id * 10 + 2];
+            contract.cars.save(&mut deps.storage, car_id, &car).unwrap();
+        }
+
+        // Solo el dueño puede quemar el carro
+        let burn_msg = ExecuteMsg::BurnCar { car_id: 1, burn_parts: false };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), burn_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("no pertenece"));
+
+        // Con rescate configurado, el carro paga desde el balance del contrato
+        let err = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), ExecuteMsg::SetSalvageValue { amount: Uint128::new(500) }).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::SetSalvageValue { amount: Uint128::new(500) }).unwrap();
+        deps.querier.update_balance(env.contract.address.clone(), coins(2000000, "uxion"));
+
+        // Quemar sin las partes: se desequipan y vuelven sueltas al dueño
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), burn_msg).unwrap();
+        assert_eq!(4, res.messages.len());
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send { to_address: "creator".to_string(), amount: coins(500, "uxion") }),
+            res.messages[0].msg
+        );
+        let unequip_msg = to_json_binary(&PartExecuteMsg::SetEquippedState { part_id: 10, car_id: 0 }).unwrap();
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "car_part_contract".to_string(),
+                msg: unequip_msg,
+                funds: vec![],
+            }),
+            res.messages[1].msg
+        );
+        assert!(query(deps.as_ref(), env.clone(), QueryMsg::GetCarComposition { car_id: 1 }).is_err());
+
+        // Quemar con las partes: el contrato de partes las quema junto con el carro
+        let burn_msg = ExecuteMsg::BurnCar { car_id: 2, burn_parts: true };
+        let res = execute(deps.as_mut(), env.clone(), info, burn_msg).unwrap();
+        let burn_parts_msg = to_json_binary(&PartExecuteMsg::BurnCarParts {
+            owner: "creator".to_string(),
+            car_id: 2,
+            part_ids: vec![20, 21, 22],
+        }).unwrap();
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "car_part_contract".to_string(),
+                msg: burn_parts_msg.clone(),
+                funds: vec![],
+            }),
+            res.messages[1].msg
+        );
+
+        // Los mensajes del contrato de partes no son parte de la API de este contrato
+        assert!(from_json::<ExecuteMsg>(&burn_parts_msg).is_err());
+
+        let res = query(deps.as_ref(), env, QueryMsg::GetOwnerCars { owner: "creator".to_string() }).unwrap();
+        let owner_cars: OwnerCarsResponse = from_json(&res).unwrap();
+        assert!(owner_cars.car_ids.is_empty());
+    }
+
+    #[test]
+    fn test_degrade_and_repair_car() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &coins(1000000, "uxion"));
+
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = signed_mint_car("creator", 0, test_parts_data());
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let contract = CarNftContract::default();
+        let mut car = contract.cars.load(&deps.storage, 1).unwrap();
+        car.part_ids = vec![10, 11, 12];
+        contract.cars.save(&mut deps.storage, 1, &car).unwrap();
+
+        let set_leaderboard = ExecuteMsg::SetLeaderboardContract { address: "leaderboard".to_string() };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("leaderboard", &[]), set_leaderboard.clone()).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("workshop", &[]), ExecuteMsg::SetWorkshopContract { address: "workshop".to_string() }).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), set_leaderboard).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::SetWorkshopContract { address: "workshop".to_string() }).unwrap();
+
+        // Solo el leaderboard degrada; el desgaste llega a cada parte equipada
+        let degrade_msg = ExecuteMsg::DegradeCar { car_id: 1, wear: 5 };
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), degrade_msg.clone()).is_err());
+        let res = execute(deps.as_mut(), env.clone(), mock_info("leaderboard", &[]), degrade_msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "car_part_contract".to_string(),
+                msg: to_json_binary(&PartExecuteMsg::DegradeParts { part_ids: vec![10, 11, 12], wear: 5 }).unwrap(),
+                funds: vec![],
+            }),
+            res.messages[0].msg
+        );
+
+        // Solo el taller repara, un carro completo o una sola parte
+        assert!(execute(deps.as_mut(), env.clone(), info, ExecuteMsg::RepairCar { car_id: 1 }).is_err());
+        let res = execute(deps.as_mut(), env.clone(), mock_info("workshop", &[]), ExecuteMsg::RepairCar { car_id: 1 }).unwrap();
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "car_part_contract".to_string(),
+                msg: to_json_binary(&PartExecuteMsg::RepairParts { part_ids: vec![10, 11, 12] }).unwrap(),
+                funds: vec![],
+            }),
+            res.messages[0].msg
+        );
+        let res = execute(deps.as_mut(), env.clone(), mock_info("workshop", &[]), ExecuteMsg::RepairPart { part_id: 11 }).unwrap();
+        assert_eq!("repair_part", res.attributes[0].value);
+
+        // La condición del carro es el promedio de la durabilidad de sus partes
+        deps.querier.update_wasm(|query| match query {
+            cosmwasm_std::WasmQuery::Smart { msg, .. } => match from_json::<QueryMsg>(msg).unwrap() {
+                QueryMsg::GetPartDurability { part_id } => {
+                    let durability: u8 = if part_id == 10 { 70 } else { 95 };
+                    cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(to_json_binary(&durability).unwrap()))
+                },
+                QueryMsg::GetPartStats { .. } => {
+                    let part_stats = PartStats {
+                        part_type: PartType::Engine,
+                        stat1: 5,
+                        stat2: 5,
+                        stat3: 5,
+                        image_uri: "ipfs://part_uri".to_string(),
+                        rarity: Rarity::Common,
+                    };
+                    cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(to_json_binary(&part_stats).unwrap()))
+                },
+                _ => panic!("Consulta inesperada"),
+            },
+            _ => panic!("Consulta inesperada"),
+        });
+        let res = query(deps.as_ref(), env, QueryMsg::GetCompactCarStats { car_id: 1 }).unwrap();
+        let stats: CompactCarStats = from_json(&res).unwrap();
+        assert_eq!(86, stats.condition);
+    }
+
+    #[test]
+    fn test_slot_schema() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &coins(1000000, "uxion"));
+
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Carro 1 minteado con el esquema original de tres slots
+        let msg = signed_mint_car("creator", 0, test_parts_data());
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Los slots existentes no se pueden quitar ni reordenar
+        let mut slots = default_slot_schema();
+        slots.swap(0, 1);
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::SetSlotSchema { slots }).is_err());
+        let mut slots = default_slot_schema();
+        slots.push(SlotDef { part_type: PartType::Engine, required: false });
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::SetSlotSchema { slots }).is_err());
+
+        // Agregar un nitro opcional con una migración
+        let mut slots = default_slot_schema();
+        slots.push(SlotDef { part_type: PartType::Nitro, required: false });
+        let err = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), ExecuteMsg::SetSlotSchema { slots: slots.clone() }).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+        let _res = migrate(deps.as_mut(), env.clone(), MigrateMsg { slot_schema: Some(slots.clone()), admin: None }).unwrap();
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetSlotSchema {}).unwrap();
+        assert_eq!(slots, from_json::<Vec<SlotDef>>(&res).unwrap());
+
+        // El nitro va al cuarto slot y su reply usa ese índice
+        let mut parts_data = test_parts_data();
+        parts_data.insert(0, PartData {
+            part_type: PartType::Nitro,
+            stat1: 5,
+            stat2: 5,
+            stat3: 5,
+            image_uri: "ipfs://nitro_uri".to_string(),
+            rarity: Rarity::Common,
+        });
+        let msg = signed_mint_car("creator", 1, parts_data);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(vec![4, 1, 2, 3], res.messages.iter().map(|m| m.id).collect::<Vec<_>>());
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetCarComposition { car_id: 2 }).unwrap();
+        let car_comp: CarComposition = from_json(&res).unwrap();
+        assert_eq!(vec![true, true, true, true], car_comp.slot_occupied);
+
+        // Sin el nitro opcional también se puede mintear, pero no sin una parte obligatoria
+        let msg = signed_mint_car("creator", 2, test_parts_data());
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = signed_mint_car("creator", 3, test_parts_data()[..2].to_vec());
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(err.to_string().contains("Faltan partes necesarias: Wheels"));
+
+        // Un carro anterior al nitro gana el slot nuevo, que solo acepta nitros
+        mock_part_types(&mut deps, vec![PartType::Nitro, PartType::Engine]);
+        let equip = |part_id| ExecuteMsg::EquipPart { car_id: 1, part_id, slot_index: 3 };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), equip(11)).unwrap_err();
+        assert!(err.to_string().contains("Una parte Engine no se puede equipar en el slot de Nitro"));
+        let _res = execute(deps.as_mut(), env.clone(), info, equip(10)).unwrap();
+        let res = query(deps.as_ref(), env, QueryMsg::GetCarComposition { car_id: 1 }).unwrap();
+        let car_comp: CarComposition = from_json(&res).unwrap();
+        assert_eq!(vec![0, 0, 0, 10], car_comp.part_ids);
+    }
+
+    #[test]
+    fn test_car_models() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &coins(1000000, "uxion"));
+
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let drift = CarModel {
+            name: "Drift".to_string(),
+            base_stats: CarStats { speed: 0, acceleration: 0, handling: 3, drift_factor: 2, turn_factor: 0, max_speed: 0 },
+            stat_caps: CarStats { speed: 4, acceleration: 10, handling: 10, drift_factor: 10, turn_factor: 10, max_speed: 12 },
+            allowed_part_types: vec![],
+            allowed_rarities: vec![Rarity::Common, Rarity::Rare],
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_json};
+        // Solo el admin registra modelos; el 0 es el genérico y se deben admitir las partes obligatorias
+        let set_model = |model_id, model: CarModel| ExecuteMsg::SetCarModel { model_id, model };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("other", &[]), set_model(1, drift.clone())).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), set_model(0, drift.clone())).is_err());
+        let engine_only = CarModel { allowed_part_types: vec![PartType::Engine], ..drift.clone() };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), set_model(2, engine_only)).unwrap_err();
+        assert!(err.to_string().contains("parte obligatoria Transmission"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), set_model(1, drift.clone())).unwrap();
+
+        let mint_model = |nonce, parts_data, model_id| {
+            let mut msg = signed_mint_car("creator", nonce, parts_data);
+            if let ExecuteMsg::MintCar { model_id: id, .. } = &mut msg {
+                *id = model_id;
+            }
+            msg
+        };
+
+        // Un modelo inexistente o una rareza que el modelo no admite
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), mint_model(0, test_parts_data(), 9)).unwrap_err();
+        assert!(err.to_string().contains("El modelo 9 no existe"));
+        let mut legendary = test_parts_data();
+        legendary[0].rarity = Rarity::Legendary;
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), mint_model(1, legendary, 1)).unwrap_err();
+        assert!(err.to_string().contains("El modelo Drift no admite una parte Engine Legendary"));
+
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), mint_model(2, test_parts_data(), 1)).unwrap();
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetCarComposition { car_id: 1 }).unwrap();
+        let car_comp: CarComposition = from_json(&res).unwrap();
+        assert_eq!(1, car_comp.model_id);
+
+        // Stats de las partes (tres motores con 5 en cada stat) más los stats base, limitados por los topes del modelo
+        mock_part_types(&mut deps, vec![PartType::Engine, PartType::Transmission, PartType::Wheels]);
+        let stats = query_full_car_metadata(deps.as_ref(), CarNftContract::default(), 1).unwrap().total_stats;
+        assert_eq!(
+            (4, 5, 3, 2, 0, 5),
+            (stats.speed, stats.acceleration, stats.handling, stats.drift_factor, stats.turn_factor, stats.max_speed)
+        );
+
+        // Las tiradas de commit-reveal se ajustan a las rarezas del modelo
+        assert_eq!(Rarity::Rare, fit_rarity(Rarity::Legendary, &drift.allowed_rarities));
+        assert_eq!(Rarity::Epic, fit_rarity(Rarity::Common, &[Rarity::Epic, Rarity::Legendary]));
+        assert_eq!(Rarity::Epic, fit_rarity(Rarity::Epic, &[]));
+    }
 
     #[test]
-    fn proper_initialization() {
+    fn test_car_stats_follow_stat_names() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info("creator", &[]);
+        let info = mock_info("creator", &coins(1000000, "uxion"));
+
         let msg = InstantiateMsg {
             car_part_contract: "car_part_contract".to_string(),
             mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
         };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = signed_mint_car("creator", 1, test_parts_data());
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(4, res.attributes.len());
+        // El reply no corre en las pruebas; se asignan las partes 0 (motor), 1 (transmisión) y 2 (ruedas)
+        let contract = CarNftContract::default();
+        let mut car = contract.cars.load(&deps.storage, 1).unwrap();
+        car.part_ids = vec![0, 1, 2];
+        contract.cars.save(&mut deps.storage, 1, &car).unwrap();
+        mock_part_types(&mut deps, vec![PartType::Engine, PartType::Transmission, PartType::Wheels]);
+
+        // speed: motor + transmisión; acceleration: motor + transmisión; handling: transmisión + ruedas;
+        // drift_factor y turn_factor: ruedas; max_speed: motor. Todo dividido por los 3 slots.
+        let stats = query_full_car_metadata(deps.as_ref(), CarNftContract::default(), 1).unwrap().total_stats;
+        assert_eq!(
+            (3, 3, 3, 1, 1, 1),
+            (stats.speed, stats.acceleration, stats.handling, stats.drift_factor, stats.turn_factor, stats.max_speed)
+        );
+
+        // Sin las ruedas solo bajan los stats que aportaban; el divisor sigue siendo 3
+        car.slot_occupied[2] = false;
+        contract.cars.save(&mut deps.storage, 1, &car).unwrap();
+        let stats = query_full_car_metadata(deps.as_ref(), CarNftContract::default(), 1).unwrap().total_stats;
+        assert_eq!(
+            (3, 3, 1, 0, 0, 1),
+            (stats.speed, stats.acceleration, stats.handling, stats.drift_factor, stats.turn_factor, stats.max_speed)
+        );
     }
 
     #[test]
-    fn test_mint_car() {
+    fn test_nft_info() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let creator = "creator";
-        let info = mock_info(creator, &coins(1000000, "uxion"));
+        let info = mock_info("creator", &coins(1000000, "uxion"));
 
-        // Inicializar el contrato
         let msg = InstantiateMsg {
             car_part_contract: "car_part_contract".to_string(),
             mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
         };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, signed_mint_car("creator", 0, test_parts_data())).unwrap();
 
-        // Crear datos de prueba para las partes
-        let parts_data = vec![
-            PartData {
-                part_type: PartType::Engine,
-                stat1: 8,
-                stat2: 7,
-                stat3: 6,
-                image_uri: "engine_uri".to_string(),
-            },
-            PartData {
-                part_type: PartType::Transmission,
-                stat1: 5,
-                stat2: 6,
-                stat3: 7,
-                image_uri: "transmission_uri".to_string(),
-            },
-            PartData {
-                part_type: PartType::Wheels,
-                stat1: 4,
-                stat2: 5,
-                stat3: 6,
-                image_uri: "wheels_uri".to_string(),
-            },
-        ];
+        // En los tests no corre el reply, así que asignamos los IDs de las partes a mano
+        let contract = CarNftContract::default();
+        let mut car = contract.cars.load(&deps.storage, 1).unwrap();
+        car.part_ids = vec![3, 4, 5];
+        contract.cars.save(&mut deps.storage, 1, &car).unwrap();
+        mock_part_types(&mut deps, vec![PartType::Engine, PartType::Transmission, PartType::Wheels]);
 
-        // Intentar mintear un carro
-        let msg = ExecuteMsg::MintCar {
-            car_image_uri: "car_uri".to_string(),
-            parts_data: parts_data.clone(),
+        assert!(query(deps.as_ref(), env.clone(), QueryMsg::NftInfo { token_id: "uno".to_string(), resolve_ipfs: false }).is_err());
+
+        let res = query(deps.as_ref(), env, QueryMsg::NftInfo { token_id: "1".to_string(), resolve_ipfs: true }).unwrap();
+        let nft_info: NftInfoResponse = from_json(&res).unwrap();
+        assert_eq!("Car #1", nft_info.extension.name);
+        assert_eq!(car.car_image_uri, nft_info.extension.image);
+        let attributes: Vec<(&str, &str)> = nft_info.extension.attributes
+            .iter()
+            .map(|a| (a.trait_type.as_str(), a.value.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("speed", "3"),
+                ("acceleration", "3"),
+                ("handling", "3"),
+                ("drift_factor", "1"),
+                ("turn_factor", "1"),
+                ("max_speed", "1"),
+                ("condition", "100"),
+                ("model", "Generic"),
+                ("Engine slot", "3"),
+                ("Transmission slot", "4"),
+                ("Wheels slot", "5"),
+            ],
+            attributes
+        );
+        assert!(nft_info.extension.attributes.iter().filter(|a| a.trait_type != "model").all(|a| a.display_type.as_deref() == Some("number")));
+    }
+
+    #[test]
+    fn test_car_cosmetics() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let admin = mock_info("creator", &coins(1000000, "uxion"));
+        let player = mock_info("player", &coins(1000000, "uxion"));
+
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
         };
+        let _res = instantiate(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), signed_mint_car("creator", 0, test_parts_data())).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), signed_mint_car("creator", 1, test_parts_data())).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), player.clone(), signed_mint_car("player", 0, test_parts_data())).unwrap();
 
-        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        
-        // Verificar que se crearon los mensajes correctos
-        assert_eq!(3, res.messages.len()); // Un mensaje por cada parte
-        
-        // Verificar que se guardó la composición del carro
-        let query_msg = QueryMsg::GetCarComposition { car_id: 1 };
-        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-        let car_comp: CarComposition = from_json(&res).unwrap();
-        
-        assert_eq!(3, car_comp.part_ids.len());
-        assert_eq!(3, car_comp.slot_occupied.len());
-        assert_eq!("car_uri", car_comp.car_image_uri);
+        let contract = CarNftContract::default();
+        let mut car = contract.cars.load(&deps.storage, 1).unwrap();
+        car.part_ids = vec![3, 4, 5];
+        contract.cars.save(&mut deps.storage, 1, &car).unwrap();
+        mock_part_types(&mut deps, vec![PartType::Engine, PartType::Transmission, PartType::Wheels]);
+        let stats_before = query(deps.as_ref(), env.clone(), QueryMsg::GetCompactCarStats { car_id: 1 }).unwrap();
 
-        // Verificar que el carro se registró para el propietario
-        let query_msg = QueryMsg::GetOwnerCars { owner: creator.to_string() };
-        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-        let owner_cars: OwnerCarsResponse = from_json(&res).unwrap();
-        assert_eq!(vec![1], owner_cars.car_ids);
+        // Nombre: solo el dueño, sin espacios sobrantes y con largo máximo
+        let set_name = |car_id, name: &str| ExecuteMsg::SetCarName { car_id, name: Some(name.to_string()) };
+        assert!(execute(deps.as_mut(), env.clone(), player.clone(), set_name(1, "Rayo")).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), admin.clone(), set_name(1, "   ")).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), admin.clone(), set_name(1, &"a".repeat(MAX_CAR_NAME_LENGTH + 1))).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), set_name(1, "  Relámpago ")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), set_name(2, "relámpago")).unwrap();
+
+        // Con nombres únicos se compara sin distinguir mayúsculas
+        let unique = ExecuteMsg::SetUniqueCarNames { unique: true };
+        assert!(execute(deps.as_mut(), env.clone(), player.clone(), unique.clone()).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), unique).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), player.clone(), set_name(3, "RELÁMPAGO")).unwrap_err();
+        assert!(err.to_string().contains("Ya existe un carro con ese nombre"));
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), set_name(1, "Rayo")).unwrap();
+        assert!(execute(deps.as_mut(), env.clone(), player.clone(), set_name(3, "rayo")).is_err());
+
+        // Pinturas y liveries registradas por el admin
+        let flames = CosmeticItem { name: "Flames".to_string(), kind: CosmeticKind::Livery, image_uri: "ipfs://flames".to_string() };
+        let set_cosmetic = |cosmetic_id| ExecuteMsg::SetCosmetic { cosmetic_id, cosmetic: flames.clone() };
+        assert!(execute(deps.as_mut(), env.clone(), player.clone(), set_cosmetic(1)).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), admin.clone(), set_cosmetic(0)).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), set_cosmetic(1)).unwrap();
+        assert!(execute(deps.as_mut(), env.clone(), admin.clone(), ExecuteMsg::SetCarCosmetic { car_id: 1, cosmetic_id: 2 }).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), ExecuteMsg::SetCarCosmetic { car_id: 1, cosmetic_id: 1 }).unwrap();
+        assert!(contract.cars.load(&deps.storage, 1).unwrap().stale_image);
+
+        // Calcomanías: tokens propios que se aplican sobre un carro del mismo dueño
+        let mint_decal = |to: &str, name: &str| ExecuteMsg::MintDecal { to: to.to_string(), name: name.to_string(), image_uri: "ipfs://decal".to_string() };
+        assert!(execute(deps.as_mut(), env.clone(), player.clone(), mint_decal("player", "Calavera")).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), mint_decal("creator", "Estrella")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), mint_decal("player", "Calavera")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), mint_decal("creator", "Número 7")).unwrap();
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetOwnerDecals { owner: "creator".to_string() }).unwrap();
+        assert_eq!(vec![1, 3], from_json::<Vec<u64>>(&res).unwrap());
+
+        let apply = |car_id, decal_id| ExecuteMsg::ApplyDecal { car_id, decal_id };
+        assert!(execute(deps.as_mut(), env.clone(), admin.clone(), apply(1, 2)).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), player.clone(), apply(1, 2)).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), apply(1, 1)).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), apply(1, 3)).unwrap();
+        assert!(execute(deps.as_mut(), env.clone(), admin.clone(), apply(2, 1)).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), player.clone(), apply(3, 2)).unwrap();
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::NftInfo { token_id: "1".to_string(), resolve_ipfs: false }).unwrap();
+        let nft_info: NftInfoResponse = from_json(&res).unwrap();
+        assert_eq!("Rayo", nft_info.extension.name);
+        let cosmetics: Vec<(&str, &str)> = nft_info.extension.attributes[11..]
+            .iter()
+            .map(|a| (a.trait_type.as_str(), a.value.as_str()))
+            .collect();
+        assert_eq!(vec![("livery", "Flames"), ("decal", "Estrella"), ("decal", "Número 7")], cosmetics);
+
+        // Los cosméticos no cambian los stats
+        assert_eq!(stats_before, query(deps.as_ref(), env.clone(), QueryMsg::GetCompactCarStats { car_id: 1 }).unwrap());
+
+        assert!(execute(deps.as_mut(), env.clone(), player.clone(), ExecuteMsg::RemoveDecal { decal_id: 1 }).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), ExecuteMsg::RemoveDecal { decal_id: 1 }).unwrap();
+        assert_eq!(vec![3], contract.cars.load(&deps.storage, 1).unwrap().decal_ids);
+        assert_eq!(0, contract.decals.load(&deps.storage, 1).unwrap().car_id);
+
+        // Al quemar el carro, su nombre queda libre y las calcomanías vuelven sueltas
+        let _res = execute(deps.as_mut(), env.clone(), admin, ExecuteMsg::BurnCar { car_id: 1, burn_parts: false }).unwrap();
+        assert_eq!(0, contract.decals.load(&deps.storage, 3).unwrap().car_id);
+        let _res = execute(deps.as_mut(), env, player, set_name(3, "Rayo")).unwrap();
     }
 
     #[test]
-    fn test_mint_car_insufficient_payment() {
+    fn test_car_rental() {
         let mut deps = mock_dependencies();
-        let env = mock_env();
-        let creator = "creator";
-        let info = mock_info(creator, &coins(500000, "uxion")); // Pago insuficiente
+        let mut env = mock_env();
+        let owner = mock_info("creator", &coins(1000000, "uxion"));
 
-        // Inicializar el contrato
         let msg = InstantiateMsg {
             car_part_contract: "car_part_contract".to_string(),
             mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
         };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), owner.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), signed_mint_car("creator", 0, test_parts_data())).unwrap();
+        let owner = mock_info("creator", &[]);
 
-        // Crear datos de prueba
-        let parts_data = vec![
-            PartData {
-                part_type: PartType::Engine,
-                stat1: 8,
-                stat2: 7,
-                stat3: 6,
-                image_uri: "engine_uri".to_string(),
-            },
-        ];
+        let list = |unit| ExecuteMsg::ListCarForRent { car_id: 1, price: Uint128::new(100), unit, max_units: 50 };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("renter", &[]), list(RentalUnit::Blocks)).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), list(RentalUnit::Blocks)).unwrap();
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("renter", &[]), ExecuteMsg::SetRentalFee { percent: 10 }).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::SetRentalFee { percent: 101 }).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::SetRentalFee { percent: 10 }).unwrap();
 
-        // Intentar mintear un carro
-        let msg = ExecuteMsg::MintCar {
-            car_image_uri: "car_uri".to_string(),
-            parts_data,
+        // Duración dentro del máximo y pago exacto
+        let rent = |units| ExecuteMsg::RentCar { car_id: 1, units };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("creator", &coins(2000, "uxion")), rent(20)).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("renter", &[]), rent(0)).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("renter", &coins(5100, "uxion")), rent(51)).is_err());
+        let err = execute(deps.as_mut(), env.clone(), mock_info("renter", &coins(1999, "uxion")), rent(20)).unwrap_err();
+        assert!(err.to_string().contains("exactamente 2000uxion"));
+
+        // 2000 pagados: 1800 para el dueño y el 10% queda en el contrato
+        let res = execute(deps.as_mut(), env.clone(), mock_info("renter", &coins(2000, "uxion")), rent(20)).unwrap();
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send { to_address: "creator".to_string(), amount: coins(1800, "uxion") })],
+            res.messages
+        );
+        let user_of = |deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>, env: &Env| {
+            let res = query(deps.as_ref(), env.clone(), QueryMsg::UserOf { car_id: 1 }).unwrap();
+            from_json::<Option<CarUser>>(&res).unwrap()
         };
+        assert_eq!(
+            Some(CarUser { user: Addr::unchecked("renter"), expires: RentalExpiry::AtHeight(env.block.height + 20) }),
+            user_of(&deps, &env)
+        );
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("other", &coins(100, "uxion")), rent(1)).is_err());
 
-        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
-        assert!(err.to_string().contains("Pago insuficiente"));
+        // El inquilino no puede tocar el carro y el dueño no puede cambiar sus partes ni quemarlo
+        let unequip = ExecuteMsg::UnequipPart { car_id: 1, part_id: 0 };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("renter", &[]), unequip.clone()).unwrap_err();
+        assert!(err.to_string().contains("El carro no pertenece al remitente"));
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("renter", &[]), ExecuteMsg::EquipPart { car_id: 1, part_id: 7, slot_index: 0 }).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("renter", &[]), ExecuteMsg::SetCarName { car_id: 1, name: Some("Mío".to_string()) }).is_err());
+        let err = execute(deps.as_mut(), env.clone(), owner.clone(), unequip.clone()).unwrap_err();
+        assert!(err.to_string().contains("El carro está alquilado"));
+        assert!(execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::BurnCar { car_id: 1, burn_parts: false }).is_err());
+
+        // El derecho de uso vence solo
+        env.block.height += 20;
+        assert_eq!(None, user_of(&deps, &env));
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), unequip).unwrap();
+
+        // Por horas
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), list(RentalUnit::Hours)).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("renter2", &coins(200, "uxion")), rent(2)).unwrap();
+        assert_eq!(RentalExpiry::AtTime(env.block.time.plus_seconds(7200)), user_of(&deps, &env).unwrap().expires);
+        env.block.time = env.block.time.plus_seconds(7199);
+        assert!(user_of(&deps, &env).is_some());
+        env.block.time = env.block.time.plus_seconds(1);
+        assert!(user_of(&deps, &env).is_none());
+
+        // Precios o duraciones que desbordan devuelven un error en vez de entrar en pánico
+        let list_huge = |price: u128, unit| ExecuteMsg::ListCarForRent { car_id: 1, price: Uint128::new(price), unit, max_units: u64::MAX };
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), list_huge(u128::MAX, RentalUnit::Blocks)).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("renter", &[]), rent(2)).unwrap_err();
+        assert!(matches!(err, StdError::Overflow { .. }));
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), list_huge(1, RentalUnit::Blocks)).unwrap();
+        let renter_info = mock_info("renter", &coins(u64::MAX as u128, "uxion"));
+        assert!(matches!(execute(deps.as_mut(), env.clone(), renter_info, rent(u64::MAX)).unwrap_err(), StdError::Overflow { .. }));
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), list_huge(1, RentalUnit::Hours)).unwrap();
+        let renter_info = mock_info("renter", &coins((u64::MAX / 3600) as u128, "uxion"));
+        assert!(matches!(execute(deps.as_mut(), env.clone(), renter_info, rent(u64::MAX / 3600)).unwrap_err(), StdError::Overflow { .. }));
+
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::CancelCarRental { car_id: 1 }).unwrap();
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("renter", &coins(100, "uxion")), rent(1)).is_err());
+
+        // Solo el admin retira las comisiones acumuladas o cambia el precio de minteo
+        deps.querier.update_balance(env.contract.address.clone(), coins(220, "uxion"));
+        let err = execute(deps.as_mut(), env.clone(), mock_info("renter", &[]), ExecuteMsg::WithdrawFunds {}).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("renter", &[]), ExecuteMsg::SetMintPrice { price: Uint128::one() }).is_err());
+        let res = execute(deps.as_mut(), env, owner, ExecuteMsg::WithdrawFunds {}).unwrap();
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send { to_address: "creator".to_string(), amount: coins(220, "uxion") })],
+            res.messages
+        );
     }
 
     #[test]
-    fn test_mint_car_missing_parts() {
+    fn test_transfer_car() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let creator = "creator";
-        let info = mock_info(creator, &coins(1000000, "uxion"));
+        let owner = mock_info("creator", &coins(1000000, "uxion"));
 
-        // Inicializar el contrato
         let msg = InstantiateMsg {
             car_part_contract: "car_part_contract".to_string(),
             mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
         };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), owner.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), signed_mint_car("creator", 0, test_parts_data())).unwrap();
+        let owner = mock_info("creator", &[]);
 
-        // Crear datos de prueba sin todas las partes necesarias
-        let parts_data = vec![
-            PartData {
-                part_type: PartType::Engine,
-                stat1: 8,
-                stat2: 7,
-                stat3: 6,
-                image_uri: "engine_uri".to_string(),
-            },
-            // Falta transmisión y ruedas
-        ];
+        // En los tests no corre el reply, así que asignamos los IDs de las partes a mano
+        let contract = CarNftContract::default();
+        let mut car = contract.cars.load(&deps.storage, 1).unwrap();
+        car.part_ids = vec![0, 1, 2];
+        contract.cars.save(&mut deps.storage, 1, &car).unwrap();
 
-        // Intentar mintear un carro
-        let msg = ExecuteMsg::MintCar {
-            car_image_uri: "car_uri".to_string(),
-            parts_data,
+        let owner_cars = |deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>, owner: &str| {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwnerCars { owner: owner.to_string() }).unwrap();
+            from_json::<OwnerCarsResponse>(&res).unwrap().car_ids
         };
 
-        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
-        assert!(err.to_string().contains("Faltan partes necesarias"));
+        // Solo el dueño transfiere y nunca mientras el carro está alquilado
+        let transfer = |recipient: &str| ExecuteMsg::TransferCar { car_id: 1, recipient: recipient.to_string() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), transfer("other")).unwrap_err();
+        assert!(err.to_string().contains("El carro no pertenece al remitente"));
+        let list = ExecuteMsg::ListCarForRent { car_id: 1, price: Uint128::new(100), unit: RentalUnit::Blocks, max_units: 10 };
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), list).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("renter", &coins(100, "uxion")), ExecuteMsg::RentCar { car_id: 1, units: 1 }).unwrap();
+        assert!(execute(deps.as_mut(), env.clone(), owner.clone(), transfer("buyer")).is_err());
+
+        // Vencido el alquiler, las partes equipadas viajan con el carro y la oferta se retira
+        let mut env = env;
+        env.block.height += 1;
+        let res = execute(deps.as_mut(), env.clone(), owner.clone(), transfer("buyer")).unwrap();
+        assert_eq!(
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "car_part_contract".to_string(),
+                msg: to_json_binary(&PartExecuteMsg::TransferCarParts {
+                    from: "creator".to_string(),
+                    to: "buyer".to_string(),
+                    car_id: 1,
+                    part_ids: vec![0, 1, 2],
+                })
+                .unwrap(),
+                funds: vec![],
+            })],
+            res.messages
+        );
+        assert!(owner_cars(&deps, "creator").is_empty());
+        assert_eq!(vec![1], owner_cars(&deps, "buyer"));
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetRentalListing { car_id: 1 }).unwrap();
+        assert_eq!(None, from_json::<Option<RentalListing>>(&res).unwrap());
+
+        // SendCar avisa al contrato receptor con ReceiveNft
+        let send = ExecuteMsg::SendCar { car_id: 1, contract: "marketplace".to_string(), msg: Binary::from(b"{}".to_vec()) };
+        let res = execute(deps.as_mut(), env, mock_info("buyer", &[]), send).unwrap();
+        assert_eq!(2, res.messages.len());
+        assert_eq!(
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "marketplace".to_string(),
+                msg: to_json_binary(&ReceiverExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                    sender: "buyer".to_string(),
+                    token_id: "1".to_string(),
+                    msg: Binary::from(b"{}".to_vec()),
+                }))
+                .unwrap(),
+                funds: vec![],
+            }),
+            res.messages[1]
+        );
+        assert_eq!(vec![1], owner_cars(&deps, "marketplace"));
     }
 
     #[test]
-    fn test_equip_unequip_part() {
+    fn test_royalties() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let creator = "creator";
-        let info = mock_info(creator, &coins(1000000, "uxion"));
+        let owner = mock_info("creator", &coins(1000000, "uxion"));
 
-        // Inicializar el contrato
         let msg = InstantiateMsg {
             car_part_contract: "car_part_contract".to_string(),
             mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
         };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), owner.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), signed_mint_car("creator", 0, test_parts_data())).unwrap();
 
-        // Crear un carro con todas sus partes
-        let parts_data = vec![
-            PartData {
-                part_type: PartType::Engine,
-                stat1: 8,
-                stat2: 7,
-                stat3: 6,
-                image_uri: "engine_uri".to_string(),
-            },
-            PartData {
-                part_type: PartType::Transmission,
-                stat1: 5,
-                stat2: 6,
-                stat3: 7,
-                image_uri: "transmission_uri".to_string(),
-            },
-            PartData {
-                part_type: PartType::Wheels,
-                stat1: 4,
-                stat2: 5,
-                stat3: 6,
-                image_uri: "wheels_uri".to_string(),
-            },
-        ];
+        let royalty_info = |deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>, token_id: &str| {
+            let msg = QueryMsg::RoyaltyInfo { token_id: token_id.to_string(), sale_price: Uint128::new(10000) };
+            query(deps.as_ref(), mock_env(), msg).and_then(|res| from_json::<RoyaltiesInfoResponse>(&res))
+        };
+        let check_royalties = |deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>| {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::CheckRoyalties {}).unwrap();
+            from_json::<CheckRoyaltiesResponse>(&res).unwrap().royalty_payments
+        };
 
-        let msg = ExecuteMsg::MintCar {
-            car_image_uri: "car_uri".to_string(),
-            parts_data,
+        // Sin configurar no hay regalías
+        assert!(!check_royalties(&deps));
+        assert_eq!(Uint128::zero(), royalty_info(&deps, "1").unwrap().royalty_amount);
+
+        // Solo el admin las configura, con un tope
+        let set_royalties = |percent| ExecuteMsg::SetRoyalties { payment_address: "studio".to_string(), percent };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("other", &[]), set_royalties(5)).is_err());
+        let err = execute(deps.as_mut(), env.clone(), owner.clone(), set_royalties(MAX_ROYALTY_PERCENT + 1)).unwrap_err();
+        assert!(err.to_string().contains("La regalía no puede superar el 25%"));
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), set_royalties(5)).unwrap();
+
+        assert!(check_royalties(&deps));
+        assert_eq!(
+            RoyaltiesInfoResponse { address: "studio".to_string(), royalty_amount: Uint128::new(500) },
+            royalty_info(&deps, "1").unwrap()
+        );
+        assert!(royalty_info(&deps, "2").is_err());
+        assert!(royalty_info(&deps, "uno").is_err());
+
+        let _res = execute(deps.as_mut(), env, owner, set_royalties(0)).unwrap();
+        assert!(!check_royalties(&deps));
+    }
+
+    #[test]
+    fn test_image_uris() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &coins(1000000, "uxion"));
+
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        // Desequipar una parte
-        let msg = ExecuteMsg::UnequipPart {
-            car_id: 1,
-            part_id: 0, // El motor
+        let mint_with_uri = |nonce, uri: &str| {
+            let mut msg = signed_mint_car("creator", nonce, test_parts_data());
+            if let ExecuteMsg::MintCar { car_image_uri, .. } = &mut msg {
+                *car_image_uri = uri.to_string();
+            }
+            msg
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        // Verificar que la parte se desequipó
-        let query_msg = QueryMsg::GetCarComposition { car_id: 1 };
-        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-        let car_comp: CarComposition = from_json(&res).unwrap();
-        assert_eq!(0, car_comp.part_ids[0]); // El slot del motor debe estar vacío
-        assert!(!car_comp.slot_occupied[0]); // El slot no debe estar ocupado
+        // Solo se admiten ipfs:// y https:// con una longitud máxima
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), mint_with_uri(0, "car_uri")).unwrap_err();
+        assert!(err.to_string().contains("Esquema de URI no permitido"));
+        let long_uri = format!("https://{}", "a".repeat(car_types::MAX_URI_LENGTH));
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), mint_with_uri(1, &long_uri)).is_err());
+        let mut bad_part = test_parts_data();
+        bad_part[1].image_uri = "ftp://transmission".to_string();
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), signed_mint_car("creator", 2, bad_part)).is_err());
 
-        // Equipar una parte en un slot vacío
-        let msg = ExecuteMsg::EquipPart {
-            car_id: 1,
-            part_id: 0,
-            slot_index: 0,
-        };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        // La URI se guarda normalizada
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), mint_with_uri(3, " ipfs://ipfs/QmCar ")).unwrap();
+        let car = CarNftContract::default().cars.load(&deps.storage, 1).unwrap();
+        assert_eq!("ipfs://QmCar", car.car_image_uri);
 
-        // Verificar que la parte se equipó correctamente
-        let query_msg = QueryMsg::GetCarComposition { car_id: 1 };
-        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-        let car_comp: CarComposition = from_json(&res).unwrap();
-        assert_eq!(0, car_comp.part_ids[0]); // El slot del motor debe tener la parte
-        assert!(car_comp.slot_occupied[0]); // El slot debe estar ocupado
+        // Con gateway la consulta puede devolver el CID resuelto
+        mock_part_types(&mut deps, vec![PartType::Engine, PartType::Transmission, PartType::Wheels]);
+        let image = |deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>, resolve_ipfs| {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::NftInfo { token_id: "1".to_string(), resolve_ipfs }).unwrap();
+            from_json::<NftInfoResponse>(&res).unwrap().extension.image
+        };
+        let set_gateway = |gateway: &str| ExecuteMsg::SetImageGateway { gateway: Some(gateway.to_string()) };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("other", &[]), set_gateway("https://gateway.lighthouse.storage/ipfs")).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), set_gateway("ipfs://gateway")).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), info, set_gateway("https://gateway.lighthouse.storage/ipfs/")).unwrap();
+        let res = query(deps.as_ref(), env, QueryMsg::GetImageGateway {}).unwrap();
+        assert_eq!(Some("https://gateway.lighthouse.storage/ipfs/".to_string()), from_json::<Option<String>>(&res).unwrap());
+        assert_eq!("ipfs://QmCar", image(&deps, false));
+        assert_eq!("https://gateway.lighthouse.storage/ipfs/QmCar", image(&deps, true));
     }
 
     #[test]
-    fn test_replace_part() {
+    fn test_stale_car_image() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let creator = "creator";
-        let info = mock_info(creator, &coins(1000000, "uxion"));
+        let info = mock_info("creator", &coins(1000000, "uxion"));
 
-        // Inicializar el contrato
         let msg = InstantiateMsg {
             car_part_contract: "car_part_contract".to_string(),
             mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
         };
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), signed_mint_car("creator", 0, test_parts_data())).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), signed_mint_car("creator", 1, test_parts_data())).unwrap();
+        mock_part_types(&mut deps, vec![PartType::Engine, PartType::Transmission, PartType::Wheels]);
 
-        // Crear un carro con todas sus partes
-        let parts_data = vec![
-            PartData {
-                part_type: PartType::Engine,
-                stat1: 8,
-                stat2: 7,
-                stat3: 6,
-                image_uri: "engine_uri".to_string(),
-            },
-            PartData {
-                part_type: PartType::Transmission,
-                stat1: 5,
-                stat2: 6,
-                stat3: 7,
-                image_uri: "transmission_uri".to_string(),
-            },
-            PartData {
-                part_type: PartType::Wheels,
-                stat1: 4,
-                stat2: 5,
-                stat3: 6,
-                image_uri: "wheels_uri".to_string(),
-            },
-        ];
+        // En los tests no corre el reply, así que asignamos los IDs de las partes a mano
+        let contract = CarNftContract::default();
+        let mut car = contract.cars.load(&deps.storage, 2).unwrap();
+        car.part_ids = vec![3, 4, 5];
+        contract.cars.save(&mut deps.storage, 2, &car).unwrap();
 
-        let msg = ExecuteMsg::MintCar {
-            car_image_uri: "car_uri".to_string(),
-            parts_data,
+        let stale_cars = |deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>, start_after| {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStaleCars { start_after, limit: None }).unwrap();
+            from_json::<Vec<u64>>(&res).unwrap()
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert!(stale_cars(&deps, None).is_empty());
 
-        // Reemplazar una parte
-        let msg = ExecuteMsg::ReplacePart {
-            car_id: 1,
-            old_part_id: 0, // El motor original
-            new_part_id: 3, // Un nuevo motor
+        // Cambiar partes deja la imagen pendiente de un nuevo render
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::UnequipPart { car_id: 1, part_id: 0 }).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ReplacePart { car_id: 2, old_part_id: 3, new_part_id: 6 }).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "stale_image" && a.value == "true"));
+        assert!(contract.cars.load(&deps.storage, 2).unwrap().stale_image);
+        assert_eq!(vec![1, 2], stale_cars(&deps, None));
+        assert_eq!(vec![2], stale_cars(&deps, Some(1)));
+
+        // Solo el renderizador configurado por el admin sube la imagen nueva
+        let set_image = |uri: &str| ExecuteMsg::SetCarImage { car_id: 2, car_image_uri: uri.to_string() };
+        assert!(execute(deps.as_mut(), env.clone(), info.clone(), set_image("ipfs://QmRender")).is_err());
+        let set_renderer = ExecuteMsg::SetRendererContract { address: "renderer".to_string() };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("other", &[]), set_renderer.clone()).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), info, set_renderer).unwrap();
+        let renderer_info = mock_info("renderer", &[]);
+        assert!(execute(deps.as_mut(), env.clone(), renderer_info.clone(), set_image("render.png")).is_err());
+        let _res = execute(deps.as_mut(), env, renderer_info, set_image("ipfs://QmRender")).unwrap();
+
+        let car = contract.cars.load(&deps.storage, 2).unwrap();
+        assert_eq!("ipfs://QmRender", car.car_image_uri);
+        assert!(!car.stale_image);
+        assert_eq!(vec![1], stale_cars(&deps, None));
+    }
+
+    #[test]
+    fn test_migrate_admin() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            car_part_contract: "car_part_contract".to_string(),
+            mint_price: Uint128::new(1000000),
+            stats_mode: Some(signed_mode()),
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
 
-        // Verificar que la parte se reemplazó correctamente
-        let query_msg = QueryMsg::GetCarComposition { car_id: 1 };
-        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-        let car_comp: CarComposition = from_json(&res).unwrap();
-        assert_eq!(3, car_comp.part_ids[0]); // El slot del motor debe tener la nueva parte
-        assert!(car_comp.slot_occupied[0]); // El slot debe seguir ocupado
+        // Un contrato desplegado antes de que existiera el admin
+        let contract = CarNftContract::default();
+        contract.admin.remove(&mut deps.storage);
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg { slot_schema: None, admin: None }).unwrap_err();
+        assert!(err.to_string().contains("El contrato no tiene admin"));
+
+        let msg = MigrateMsg { slot_schema: None, admin: Some("creator".to_string()) };
+        let _res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(Addr::unchecked("creator"), contract.admin.load(&deps.storage).unwrap());
+        let set_fee = ExecuteMsg::SetRentalFee { percent: 5 };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_fee).unwrap();
     }
-} 
\ No newline at end of file
+}
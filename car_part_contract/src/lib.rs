@@ -1,11 +1,111 @@
 use cosmwasm_std::{
     entry_point, Binary, Deps, DepsMut, Env, MessageInfo,
-    Response, StdResult, Addr, to_json_binary, StdError,
+    Response, StdResult, Addr, to_json_binary, StdError, Uint128, BankMsg, Order, Coin, Storage, Event, WasmMsg,
 };
-use car_types::{PartType, PartStats};
+use car_types::{randomness, normalize_gateway, normalize_uri, resolve_uri, Metadata, NftInfoResponse, PartOwnerResponse, PartType, PartStats, Rarity, StatBounds, Trait, MAX_DURABILITY, MAX_STAT};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use cw_storage_plus::{Item, Map};
+use sha2::{Digest, Sha256};
+
+// Cantidad máxima de partes en un pack
+pub const MAX_PACK_SIZE: u8 = 10;
+// Bloques que tiene el comprador para abrir un pack después de comprarlo
+pub const PACK_REVEAL_WINDOW: u64 = 100;
+// Regalía máxima sobre las reventas, en porcentaje
+pub const MAX_ROYALTY_PERCENT: u8 = 25;
+
+// Entrada de una tabla de drops: qué parte puede salir y con qué peso relativo
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DropEntry {
+    pub part_type: PartType,
+    pub rarity: Rarity,
+    pub weight: u32,
+    pub image_uri: String,
+}
+
+// Pack a la venta. Una parte suelta se vende como un pack de tamaño 1.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PackConfig {
+    pub price: Uint128,
+    pub size: u8,
+    pub drop_table: Vec<DropEntry>,
+}
+
+// Pack comprado y pendiente de abrir. Guarda una copia del pack para que
+// un cambio posterior de la tabla de drops no afecte a la compra, y la semilla
+// que publica el servidor de juego después de la compra.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingPack {
+    pub pack_id: String,
+    pub pack: PackConfig,
+    pub commitment: String,
+    pub height: u64,
+    #[serde(default)]
+    pub server_seed: Option<String>,
+}
+
+// Reglas de fusión: cuántas partes del mismo tipo se combinan y cómo se calculan los stats.
+// Cada stat de la parte resultante es el promedio de las entradas más el bonus, limitado al máximo de su rareza.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FusionRules {
+    pub inputs_required: u8,
+    pub bonus: u8,
+    pub rarity_upgrade: bool, // la parte resultante sube un nivel de rareza
+}
+
+impl Default for FusionRules {
+    fn default() -> Self {
+        Self {
+            inputs_required: 3,
+            bonus: 1,
+            rarity_upgrade: false,
+        }
+    }
+}
+
+// Tarifas y límites del tuning. Cada mejora cuesta base_fee + fee_step * upgrade_level.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TuningConfig {
+    pub base_fee: Uint128,
+    pub fee_step: Uint128,
+    pub max_upgrades_per_stat: u8,
+}
+
+impl Default for TuningConfig {
+    fn default() -> Self {
+        Self {
+            base_fee: Uint128::new(100),
+            fee_step: Uint128::new(50),
+            max_upgrades_per_stat: 3,
+        }
+    }
+}
+
+// Mejora aplicada a un stat de una parte
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatUpgrade {
+    pub stat: String,
+    pub from: u8,
+    pub to: u8,
+    pub fee: Uint128,
+    pub height: u64,
+}
+
+// Historial de tuning de una parte
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PartUpgrades {
+    pub upgrade_level: u32,
+    pub stat_upgrades: [u8; 3], // mejoras aplicadas a stat1, stat2 y stat3
+    pub history: Vec<StatUpgrade>,
+}
+
+// Regalía CW2981 que cobra la colección en cada reventa de una parte
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyConfig {
+    pub payment_address: Addr,
+    pub percent: u8,
+}
 
 // Estado del contrato
 pub struct CarPartContract<'a> {
@@ -14,9 +114,22 @@ pub struct CarPartContract<'a> {
     pub current_part_id: Item<'a, u64>,
     pub car_contract: Item<'a, Addr>,
     pub owner_parts: Map<'a, String, Vec<u64>>, // owner => part_ids
+    pub part_owners: Map<'a, u64, String>, // part_id => owner
     pub owner_parts_by_type: Map<'a, (String, String), Vec<u64>>, // (owner, part_type) => part_ids
     pub owner_equipped_parts: Map<'a, String, Vec<u64>>, // owner => equipped_part_ids
     pub owner_unequipped_parts: Map<'a, String, Vec<u64>>, // owner => unequipped_part_ids
+    pub admin: Item<'a, Addr>,
+    pub rarity_bounds: Map<'a, String, StatBounds>, // rarity => límites de stats
+    pub packs: Map<'a, String, PackConfig>, // pack_id => pack
+    pub pending_packs: Map<'a, &'a Addr, PendingPack>, // comprador => pack sin abrir
+    pub salvage_value: Item<'a, Uint128>, // uxion que se paga por cada parte quemada
+    pub fusion_rules: Item<'a, FusionRules>,
+    pub tuning_config: Item<'a, TuningConfig>,
+    pub part_upgrades: Map<'a, u64, PartUpgrades>, // part_id => historial de tuning
+    pub part_durability: Map<'a, u64, u8>, // part_id => durabilidad; sin entrada la parte está intacta
+    pub image_gateway: Item<'a, String>, // gateway https:// para resolver las URIs ipfs://
+    pub royalties: Item<'a, RoyaltyConfig>, // sin configurar no se cobran regalías
+    pub game_server: Item<'a, Addr>, // publica su semilla para cada pack comprado
 }
 
 impl<'a> Default for CarPartContract<'a> {
@@ -27,9 +140,22 @@ impl<'a> Default for CarPartContract<'a> {
             current_part_id: Item::new("current_part_id"),
             car_contract: Item::new("car_contract"),
             owner_parts: Map::new("owner_parts"),
+            part_owners: Map::new("part_owners"),
             owner_parts_by_type: Map::new("owner_parts_by_type"),
             owner_equipped_parts: Map::new("owner_equipped_parts"),
             owner_unequipped_parts: Map::new("owner_unequipped_parts"),
+            admin: Item::new("admin"),
+            rarity_bounds: Map::new("rarity_bounds"),
+            packs: Map::new("packs"),
+            pending_packs: Map::new("pending_packs"),
+            salvage_value: Item::new("salvage_value"),
+            fusion_rules: Item::new("fusion_rules"),
+            tuning_config: Item::new("tuning_config"),
+            part_upgrades: Map::new("part_upgrades"),
+            part_durability: Map::new("part_durability"),
+            image_gateway: Item::new("image_gateway"),
+            royalties: Item::new("royalties"),
+            game_server: Item::new("game_server"),
         }
     }
 }
@@ -40,6 +166,15 @@ pub struct InstantiateMsg {
     pub car_contract: String,
 }
 
+// Mensaje de migración. Los tipos de parte nuevos solo requieren subir el código;
+// las partes existentes no cambian. Los contratos instanciados antes de que existiera
+// el admin deben indicarlo al migrar.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    #[serde(default)]
+    pub admin: Option<String>,
+}
+
 // Mensajes de ejecución
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -55,6 +190,8 @@ pub enum ExecuteMsg {
         stat3: u8,
         image_uri: String,
         car_id: u64,
+        #[serde(default)]
+        rarity: Rarity,
     },
     SetEquippedState {
         part_id: u64,
@@ -65,6 +202,102 @@ pub enum ExecuteMsg {
         to: String,
         part_id: u64,
     },
+    SendPart {
+        part_id: u64,
+        contract: String,
+        msg: Binary,
+    },
+    TransferCarParts {
+        from: String,
+        to: String,
+        car_id: u64,
+        part_ids: Vec<u64>,
+    },
+    SetRarityBounds {
+        rarity: Rarity,
+        min: u8,
+        max: u8,
+    },
+    SetPack {
+        pack_id: String,
+        price: Uint128,
+        size: u8,
+        drop_table: Vec<DropEntry>,
+    },
+    RemovePack {
+        pack_id: String,
+    },
+    BuyPack {
+        pack_id: String,
+        commitment: String,
+    },
+    OpenPack {
+        secret: String,
+    },
+    SetGameServer {
+        address: String,
+    },
+    SubmitPackSeed {
+        owner: String,
+        seed: String,
+    },
+    WithdrawFunds {},
+    BurnPart {
+        part_id: u64,
+    },
+    BurnCarParts {
+        owner: String,
+        car_id: u64,
+        part_ids: Vec<u64>,
+    },
+    SetSalvageValue {
+        amount: Uint128,
+    },
+    SetRoyalties {
+        payment_address: String,
+        percent: u8,
+    },
+    FusePart {
+        part_ids: Vec<u64>,
+    },
+    SetFusionRules {
+        inputs_required: u8,
+        bonus: u8,
+        rarity_upgrade: bool,
+    },
+    TunePart {
+        part_id: u64,
+        stat: String,
+    },
+    SetTuningConfig {
+        base_fee: Uint128,
+        fee_step: Uint128,
+        max_upgrades_per_stat: u8,
+    },
+    DegradeParts {
+        part_ids: Vec<u64>,
+        wear: u8,
+    },
+    RepairParts {
+        part_ids: Vec<u64>,
+    },
+    SetImageGateway {
+        gateway: Option<String>,
+    },
+}
+
+// Aviso CW721 al contrato que recibe una parte con SendPart
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
 }
 
 // Mensajes de consulta
@@ -83,6 +316,9 @@ pub enum QueryMsg {
     GetEquippedCar {
         part_id: u64,
     },
+    GetPartOwner {
+        part_id: u64,
+    },
     GetOwnerParts {
         owner: String,
     },
@@ -96,6 +332,68 @@ pub enum QueryMsg {
     GetOwnerUnequippedParts {
         owner: String,
     },
+    GetOwnerPartsByRarity {
+        owner: String,
+        rarity: Rarity,
+    },
+    GetRarityBounds {
+        rarity: Rarity,
+    },
+    GetPack {
+        pack_id: String,
+    },
+    GetPacks {},
+    GetPendingPack {
+        owner: String,
+    },
+    GetGameServer {},
+    GetSalvageValue {},
+    GetFusionRules {},
+    GetTuningConfig {},
+    GetPartUpgrades {
+        part_id: u64,
+    },
+    GetTuningFee {
+        part_id: u64,
+    },
+    GetPartDurability {
+        part_id: u64,
+    },
+    NftInfo {
+        token_id: String,
+        #[serde(default)]
+        resolve_ipfs: bool,
+    },
+    GetImageGateway {},
+    // CW2981
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+    CheckRoyalties {},
+}
+
+// Respuestas de consulta
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CheckRoyaltiesResponse {
+    pub royalty_payments: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PackInfo {
+    pub pack_id: String,
+    pub pack: PackConfig,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PacksResponse {
+    pub packs: Vec<PackInfo>,
 }
 
 // Entry points
@@ -103,11 +401,14 @@ pub enum QueryMsg {
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
     let contract = CarPartContract::default();
     
+    // Quien instancia el contrato queda como admin
+    contract.admin.save(deps.storage, &info.sender)?;
+    
     // Guardar la dirección del contrato de carros
     let car_addr = deps.api.addr_validate(&msg.car_contract)?;
     contract.car_contract.save(deps.storage, &car_addr)?;
@@ -123,7 +424,7 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> StdResult<Response> {
@@ -133,8 +434,8 @@ pub fn execute(
         ExecuteMsg::SetCarContract { address } => {
             execute_set_car_contract(deps, info, contract, address)
         },
-        ExecuteMsg::Mint { to, part_type, stat1, stat2, stat3, image_uri, car_id } => {
-            execute_mint(deps, _env, info, contract, to, part_type, stat1, stat2, stat3, image_uri, car_id)
+        ExecuteMsg::Mint { to, part_type, stat1, stat2, stat3, image_uri, car_id, rarity } => {
+            execute_mint(deps, env, info, contract, to, part_type, stat1, stat2, stat3, image_uri, car_id, rarity)
         },
         ExecuteMsg::SetEquippedState { part_id, car_id } => {
             execute_set_equipped_state(deps, info, contract, part_id, car_id)
@@ -142,6 +443,69 @@ pub fn execute(
         ExecuteMsg::TransferPart { from, to, part_id } => {
             execute_transfer_part(deps, info, contract, from, to, part_id)
         },
+        ExecuteMsg::SendPart { part_id, contract: recipient, msg } => {
+            execute_send_part(deps, info, contract, part_id, recipient, msg)
+        },
+        ExecuteMsg::TransferCarParts { from, to, car_id, part_ids } => {
+            execute_transfer_car_parts(deps, info, contract, from, to, car_id, part_ids)
+        },
+        ExecuteMsg::SetRarityBounds { rarity, min, max } => {
+            execute_set_rarity_bounds(deps, info, contract, rarity, min, max)
+        },
+        ExecuteMsg::SetPack { pack_id, price, size, drop_table } => {
+            execute_set_pack(deps, info, contract, pack_id, PackConfig { price, size, drop_table })
+        },
+        ExecuteMsg::RemovePack { pack_id } => {
+            execute_remove_pack(deps, info, contract, pack_id)
+        },
+        ExecuteMsg::BuyPack { pack_id, commitment } => {
+            execute_buy_pack(deps, env, info, contract, pack_id, commitment)
+        },
+        ExecuteMsg::OpenPack { secret } => {
+            execute_open_pack(deps, env, info, contract, secret)
+        },
+        ExecuteMsg::SetGameServer { address } => {
+            execute_set_game_server(deps, info, contract, address)
+        },
+        ExecuteMsg::SubmitPackSeed { owner, seed } => {
+            execute_submit_pack_seed(deps, env, info, contract, owner, seed)
+        },
+        ExecuteMsg::WithdrawFunds {} => {
+            execute_withdraw_funds(deps, env, info, contract)
+        },
+        ExecuteMsg::BurnPart { part_id } => {
+            execute_burn_part(deps, env, info, contract, part_id)
+        },
+        ExecuteMsg::BurnCarParts { owner, car_id, part_ids } => {
+            execute_burn_car_parts(deps, env, info, contract, owner, car_id, part_ids)
+        },
+        ExecuteMsg::SetSalvageValue { amount } => {
+            execute_set_salvage_value(deps, info, contract, amount)
+        },
+        ExecuteMsg::SetRoyalties { payment_address, percent } => {
+            execute_set_royalties(deps, info, contract, payment_address, percent)
+        },
+        ExecuteMsg::FusePart { part_ids } => {
+            execute_fuse_part(deps, info, contract, part_ids)
+        },
+        ExecuteMsg::SetFusionRules { inputs_required, bonus, rarity_upgrade } => {
+            execute_set_fusion_rules(deps, info, contract, FusionRules { inputs_required, bonus, rarity_upgrade })
+        },
+        ExecuteMsg::TunePart { part_id, stat } => {
+            execute_tune_part(deps, env, info, contract, part_id, stat)
+        },
+        ExecuteMsg::SetTuningConfig { base_fee, fee_step, max_upgrades_per_stat } => {
+            execute_set_tuning_config(deps, info, contract, TuningConfig { base_fee, fee_step, max_upgrades_per_stat })
+        },
+        ExecuteMsg::DegradeParts { part_ids, wear } => {
+            execute_degrade_parts(deps, info, contract, part_ids, wear)
+        },
+        ExecuteMsg::RepairParts { part_ids } => {
+            execute_repair_parts(deps, info, contract, part_ids)
+        },
+        ExecuteMsg::SetImageGateway { gateway } => {
+            execute_set_image_gateway(deps, info, contract, gateway)
+        },
     }
 }
 
@@ -166,6 +530,9 @@ pub fn query(
         QueryMsg::GetEquippedCar { part_id } => {
             to_json_binary(&query_equipped_car(deps, contract, part_id)?)
         },
+        QueryMsg::GetPartOwner { part_id } => {
+            to_json_binary(&query_part_owner(deps, contract, part_id)?)
+        },
         QueryMsg::GetOwnerParts { owner } => {
             to_json_binary(&query_owner_parts(deps, contract, owner)?)
         },
@@ -178,17 +545,98 @@ pub fn query(
         QueryMsg::GetOwnerUnequippedParts { owner } => {
             to_json_binary(&query_owner_unequipped_parts(deps, contract, owner)?)
         },
+        QueryMsg::GetOwnerPartsByRarity { owner, rarity } => {
+            to_json_binary(&query_owner_parts_by_rarity(deps, contract, owner, rarity)?)
+        },
+        QueryMsg::GetRarityBounds { rarity } => {
+            to_json_binary(&query_rarity_bounds(deps, contract, rarity)?)
+        },
+        QueryMsg::GetPack { pack_id } => {
+            to_json_binary(&contract.packs.load(deps.storage, pack_id)?)
+        },
+        QueryMsg::GetPacks {} => {
+            to_json_binary(&query_packs(deps, contract)?)
+        },
+        QueryMsg::GetPendingPack { owner } => {
+            to_json_binary(&query_pending_pack(deps, contract, owner)?)
+        },
+        QueryMsg::GetGameServer {} => {
+            to_json_binary(&contract.game_server.may_load(deps.storage)?)
+        },
+        QueryMsg::GetSalvageValue {} => {
+            to_json_binary(&contract.salvage_value.may_load(deps.storage)?.unwrap_or_default())
+        },
+        QueryMsg::GetFusionRules {} => {
+            to_json_binary(&contract.fusion_rules.may_load(deps.storage)?.unwrap_or_default())
+        },
+        QueryMsg::GetTuningConfig {} => {
+            to_json_binary(&contract.tuning_config.may_load(deps.storage)?.unwrap_or_default())
+        },
+        QueryMsg::GetPartUpgrades { part_id } => {
+            to_json_binary(&contract.part_upgrades.may_load(deps.storage, part_id)?.unwrap_or_default())
+        },
+        QueryMsg::GetTuningFee { part_id } => {
+            to_json_binary(&query_tuning_fee(deps, contract, part_id)?)
+        },
+        QueryMsg::GetPartDurability { part_id } => {
+            to_json_binary(&query_part_durability(deps, contract, part_id)?)
+        },
+        QueryMsg::NftInfo { token_id, resolve_ipfs } => {
+            to_json_binary(&query_nft_info(deps, contract, token_id, resolve_ipfs)?)
+        },
+        QueryMsg::GetImageGateway {} => {
+            to_json_binary(&contract.image_gateway.may_load(deps.storage)?)
+        },
+        QueryMsg::RoyaltyInfo { token_id, sale_price } => {
+            to_json_binary(&query_royalty_info(deps, contract, token_id, sale_price)?)
+        },
+        QueryMsg::CheckRoyalties {} => {
+            let royalty_payments = contract.royalties.may_load(deps.storage)?
+                .is_some_and(|royalties| royalties.percent > 0);
+            to_json_binary(&CheckRoyaltiesResponse { royalty_payments })
+        },
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let contract = CarPartContract::default();
+
+    // Sin admin guardado todas las funciones de administración fallarían
+    let admin = match msg.admin {
+        Some(admin) => {
+            let admin = deps.api.addr_validate(&admin)?;
+            contract.admin.save(deps.storage, &admin)?;
+            admin
+        },
+        None => contract.admin.may_load(deps.storage)?
+            .ok_or_else(|| StdError::generic_err("El contrato no tiene admin; indícalo en el mensaje de migración"))?,
+    };
+
+    // Índice part_id => dueño para las partes minteadas antes de que existiera
+    let owners = contract.owner_parts
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (owner, part_ids) in owners {
+        for part_id in part_ids {
+            contract.part_owners.save(deps.storage, part_id, &owner)?;
+        }
     }
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("admin", admin))
 }
 
 // Funciones de ejecución
 fn execute_set_car_contract(
     deps: DepsMut,
-    _info: MessageInfo,
+    info: MessageInfo,
     contract: CarPartContract,
     address: String,
 ) -> StdResult<Response> {
-    // TODO: Implementar verificación de propietario
+    // Solo el admin puede cambiar el contrato de carros: esa dirección mintea y mueve partes
+    ensure_admin(deps.as_ref(), &contract, &info)?;
     let car_addr = deps.api.addr_validate(&address)?;
     contract.car_contract.save(deps.storage, &car_addr)?;
     
@@ -203,6 +651,7 @@ pub struct MintResponse {
     pub part_id: u64,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_mint(
     deps: DepsMut,
     _env: Env,
@@ -215,30 +664,56 @@ fn execute_mint(
     stat3: u8,
     image_uri: String,
     car_id: u64,
+    rarity: Rarity,
 ) -> StdResult<Response> {
     // Verificar que el remitente es el contrato de carros
     let car_contract = contract.car_contract.load(deps.storage)?;
     if info.sender != car_contract {
         return Err(StdError::generic_err("Solo el contrato de carros puede mintear partes"));
     }
+    let image_uri = normalize_uri(&image_uri).map_err(StdError::generic_err)?;
 
-    // Validar stats
-    if stat1 > 10 || stat2 > 10 || stat3 > 10 {
-        return Err(StdError::generic_err("Los stats deben ser <= 10"));
-    }
-
-    // Obtener el ID actual de parte
-    let part_id = contract.current_part_id.load(deps.storage)?;
-    
-    // Guardar los stats de la parte
     let part_stats = PartStats {
         part_type: part_type.clone(),
         stat1,
         stat2,
         stat3,
-        image_uri: image_uri.clone(),
+        image_uri,
+        rarity: rarity.clone(),
     };
-    contract.part_stats.save(deps.storage, part_id, &part_stats)?;
+    let part_id = mint_part(deps, &contract, to.clone(), &part_stats, car_id)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "mint")
+        .add_attribute("part_id", part_id.to_string())
+        .add_attribute("owner", to.clone())
+        .add_attribute("part_type", format!("{:?}", part_type))
+        .add_attribute("rarity", rarity.to_string()))
+}
+
+// Guardar una parte nueva y actualizar los índices del propietario. Devuelve el ID de la parte.
+// Si car_id es 0 la parte queda sin equipar.
+fn mint_part(
+    deps: DepsMut,
+    contract: &CarPartContract,
+    to: String,
+    part_stats: &PartStats,
+    car_id: u64,
+) -> StdResult<u64> {
+    // Validar stats según los límites de la rareza
+    let bounds = load_rarity_bounds(deps.as_ref(), contract, &part_stats.rarity)?;
+    if !bounds.contains(part_stats.stat1) || !bounds.contains(part_stats.stat2) || !bounds.contains(part_stats.stat3) {
+        return Err(StdError::generic_err(format!(
+            "Los stats de una parte {} deben estar entre {} y {}",
+            part_stats.rarity, bounds.min, bounds.max
+        )));
+    }
+
+    // Obtener el ID actual de parte
+    let part_id = contract.current_part_id.load(deps.storage)?;
+    
+    // Guardar los stats de la parte
+    contract.part_stats.save(deps.storage, part_id, part_stats)?;
 
     // Actualizar los mapeos de seguimiento
     deps.api.addr_validate(&to)?;
@@ -249,9 +724,10 @@ fn execute_mint(
         .unwrap_or_default();
     owner_parts.push(part_id);
     contract.owner_parts.save(deps.storage, to.clone(), &owner_parts)?;
+    contract.part_owners.save(deps.storage, part_id, &to)?;
     
     // Actualizar owner_parts_by_type
-    let part_type_str = format!("{:?}", part_type);
+    let part_type_str = format!("{:?}", part_stats.part_type);
     let mut owner_parts_by_type = contract.owner_parts_by_type
         .may_load(deps.storage, (to.clone(), part_type_str.clone()))?
         .unwrap_or_default();
@@ -285,11 +761,7 @@ fn execute_mint(
     // Incrementar el ID para la siguiente parte
     contract.current_part_id.save(deps.storage, &(part_id + 1))?;
     
-    Ok(Response::new()
-        .add_attribute("method", "mint")
-        .add_attribute("part_id", part_id.to_string())
-        .add_attribute("owner", to.clone())
-        .add_attribute("part_type", format!("{:?}", part_type)))
+    Ok(part_id)
 }
 
 fn execute_set_equipped_state(
@@ -305,12 +777,29 @@ fn execute_set_equipped_state(
         return Err(StdError::generic_err("Solo el contrato de carros puede cambiar el estado de equipamiento"));
     }
 
-    // Actualizar el estado de equipamiento
-    if car_id > 0 {
+    // Una parte equipada no se puede equipar en otro carro sin desequiparla antes
+    let equipped_in_car = contract.equipped_in_car.may_load(deps.storage, part_id)?.unwrap_or(0);
+    if car_id > 0 && equipped_in_car > 0 {
+        return Err(StdError::generic_err(format!("La parte ya está equipada en el carro {}", equipped_in_car)));
+    }
+
+    // Actualizar el estado de equipamiento y mover la parte a la lista correspondiente del dueño
+    let owner = contract.part_owners.load(deps.storage, part_id)?;
+    let (from_list, to_list) = if car_id > 0 {
         contract.equipped_in_car.save(deps.storage, part_id, &car_id)?;
+        (&contract.owner_unequipped_parts, &contract.owner_equipped_parts)
     } else {
         contract.equipped_in_car.remove(deps.storage, part_id);
+        (&contract.owner_equipped_parts, &contract.owner_unequipped_parts)
+    };
+    let mut parts = from_list.may_load(deps.storage, owner.clone())?.unwrap_or_default();
+    parts.retain(|&x| x != part_id);
+    from_list.save(deps.storage, owner.clone(), &parts)?;
+    let mut parts = to_list.may_load(deps.storage, owner.clone())?.unwrap_or_default();
+    if !parts.contains(&part_id) {
+        parts.push(part_id);
     }
+    to_list.save(deps.storage, owner, &parts)?;
 
     Ok(Response::new()
         .add_attribute("method", "set_equipped_state")
@@ -326,53 +815,13 @@ fn execute_transfer_part(
     to: String,
     part_id: u64,
 ) -> StdResult<Response> {
-    // Verificar que la parte existe
-    let part_stats = contract.part_stats.load(deps.storage, part_id)?;
-    
     // Verificar que el remitente es el propietario o el contrato de carros
     let car_contract = contract.car_contract.load(deps.storage)?;
-    if info.sender != car_contract {
-        return Err(StdError::generic_err("Solo el contrato de carros puede transferir partes"));
+    if info.sender != car_contract && info.sender.as_str() != from {
+        return Err(StdError::generic_err("Solo el dueño o el contrato de carros pueden transferir la parte"));
     }
-
-    // Verificar que la parte no está equipada
-    if contract.equipped_in_car.may_load(deps.storage, part_id)?.unwrap_or(0) > 0 {
-        return Err(StdError::generic_err("No se puede transferir una parte equipada"));
-    }
-
-    // Actualizar los mapeos de seguimiento
-    // Remover de las listas del propietario anterior
-    let mut from_parts = contract.owner_parts.load(deps.storage, from.clone())?;
-    from_parts.retain(|&x| x != part_id);
-    contract.owner_parts.save(deps.storage, from.clone(), &from_parts)?;
-
-    let mut from_parts_by_type = contract.owner_parts_by_type
-        .load(deps.storage, (from.clone(), part_stats.part_type.to_string()))?;
-    from_parts_by_type.retain(|&x| x != part_id);
-    contract.owner_parts_by_type.save(deps.storage, (from.clone(), part_stats.part_type.to_string()), &from_parts_by_type)?;
-
-    let mut from_unequipped_parts = contract.owner_unequipped_parts.load(deps.storage, from.clone())?;
-    from_unequipped_parts.retain(|&x| x != part_id);
-    contract.owner_unequipped_parts.save(deps.storage, from.clone(), &from_unequipped_parts)?;
-
-    // Agregar a las listas del nuevo propietario
-    let mut to_parts = contract.owner_parts
-        .may_load(deps.storage, to.clone())?
-        .unwrap_or_default();
-    to_parts.push(part_id);
-    contract.owner_parts.save(deps.storage, to.clone(), &to_parts)?;
-
-    let mut to_parts_by_type = contract.owner_parts_by_type
-        .may_load(deps.storage, (to.clone(), part_stats.part_type.to_string()))?
-        .unwrap_or_default();
-    to_parts_by_type.push(part_id);
-    contract.owner_parts_by_type.save(deps.storage, (to.clone(), part_stats.part_type.to_string()), &to_parts_by_type)?;
-
-    let mut to_unequipped_parts = contract.owner_unequipped_parts
-        .may_load(deps.storage, to.clone())?
-        .unwrap_or_default();
-    to_unequipped_parts.push(part_id);
-    contract.owner_unequipped_parts.save(deps.storage, to.clone(), &to_unequipped_parts)?;
+    let to = deps.api.addr_validate(&to)?.to_string();
+    transfer_loose_part(deps.storage, &contract, &from, &to, part_id)?;
 
     Ok(Response::new()
         .add_attribute("method", "transfer_part")
@@ -381,45 +830,996 @@ fn execute_transfer_part(
         .add_attribute("to", to))
 }
 
-// Funciones de consulta
-fn query_part_stats(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<PartStats> {
-    contract.part_stats.load(deps.storage, part_id)
+// Transferir la parte a un contrato y avisarle con ReceiveNft, como send_nft de CW721
+fn execute_send_part(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    part_id: u64,
+    recipient: String,
+    msg: Binary,
+) -> StdResult<Response> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    transfer_loose_part(deps.storage, &contract, info.sender.as_str(), recipient.as_str(), part_id)?;
+
+    let receive_msg = ReceiverExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+        sender: info.sender.to_string(),
+        token_id: part_id.to_string(),
+        msg,
+    });
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: recipient.to_string(),
+            msg: to_json_binary(&receive_msg)?,
+            funds: vec![],
+        })
+        .add_attribute("method", "send_part")
+        .add_attribute("part_id", part_id.to_string())
+        .add_attribute("from", info.sender)
+        .add_attribute("to", recipient))
 }
 
-fn query_part_type(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<PartType> {
-    let part_stats = contract.part_stats.load(deps.storage, part_id)?;
-    Ok(part_stats.part_type)
-}
+// Las partes equipadas acompañan a su carro cuando este cambia de dueño
+fn execute_transfer_car_parts(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    from: String,
+    to: String,
+    car_id: u64,
+    part_ids: Vec<u64>,
+) -> StdResult<Response> {
+    let car_contract = contract.car_contract.load(deps.storage)?;
+    if info.sender != car_contract {
+        return Err(StdError::generic_err("Solo el contrato de carros puede transferir partes equipadas"));
+    }
 
-fn query_is_equipped(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<bool> {
-    Ok(contract.equipped_in_car.may_load(deps.storage, part_id)?.unwrap_or(0) > 0)
-}
+    for &part_id in &part_ids {
+        if contract.equipped_in_car.may_load(deps.storage, part_id)? != Some(car_id) {
+            return Err(StdError::generic_err(format!("La parte {} no está equipada en el carro {}", part_id, car_id)));
+        }
+        move_part(deps.storage, &contract, &from, &to, part_id)?;
+    }
 
-fn query_equipped_car(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<u64> {
-    Ok(contract.equipped_in_car.may_load(deps.storage, part_id)?.unwrap_or(0))
+    let part_ids: Vec<String> = part_ids.iter().map(|id| id.to_string()).collect();
+    Ok(Response::new()
+        .add_attribute("method", "transfer_car_parts")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("from", from)
+        .add_attribute("to", to)
+        .add_attribute("part_ids", part_ids.join(",")))
 }
 
-fn query_owner_parts(deps: Deps, contract: CarPartContract, owner: String) -> StdResult<Vec<u64>> {
-    Ok(contract.owner_parts.may_load(deps.storage, owner)?.unwrap_or_default())
+// Solo se transfieren partes sueltas; las equipadas viajan con su carro
+fn transfer_loose_part(storage: &mut dyn Storage, contract: &CarPartContract, from: &str, to: &str, part_id: u64) -> StdResult<()> {
+    if contract.equipped_in_car.may_load(storage, part_id)?.unwrap_or(0) > 0 {
+        return Err(StdError::generic_err("No se puede transferir una parte equipada"));
+    }
+    move_part(storage, contract, from, to, part_id)
 }
 
-fn query_owner_parts_by_type(deps: Deps, contract: CarPartContract, owner: String, part_type: PartType) -> StdResult<Vec<u64>> {
-    Ok(contract.owner_parts_by_type.may_load(deps.storage, (owner, part_type.to_string()))?.unwrap_or_default())
-}
+// Mover la parte de las listas de `from` a las de `to`, conservando si está equipada o no
+fn move_part(storage: &mut dyn Storage, contract: &CarPartContract, from: &str, to: &str, part_id: u64) -> StdResult<()> {
+    let part_stats = contract.part_stats.load(storage, part_id)?;
+    let from_parts = contract.owner_parts.may_load(storage, from.to_string())?.unwrap_or_default();
+    if !from_parts.contains(&part_id) {
+        return Err(StdError::generic_err(format!("La parte {} no pertenece a {}", part_id, from)));
+    }
 
-fn query_owner_equipped_parts(deps: Deps, contract: CarPartContract, owner: String) -> StdResult<Vec<u64>> {
-    Ok(contract.owner_equipped_parts.may_load(deps.storage, owner)?.unwrap_or_default())
-}
+    let equipped = contract.equipped_in_car.may_load(storage, part_id)?.unwrap_or(0) > 0;
+    let state_list = if equipped {
+        &contract.owner_equipped_parts
+    } else {
+        &contract.owner_unequipped_parts
+    };
+    let type_key = |owner: &str| (owner.to_string(), part_stats.part_type.to_string());
 
-fn query_owner_unequipped_parts(deps: Deps, contract: CarPartContract, owner: String) -> StdResult<Vec<u64>> {
+    // Remover de las listas del propietario anterior, incluidas ambas listas de estado
+    for list in [&contract.owner_parts, &contract.owner_equipped_parts, &contract.owner_unequipped_parts] {
+        let mut parts = list.may_load(storage, from.to_string())?.unwrap_or_default();
+        parts.retain(|&x| x != part_id);
+        list.save(storage, from.to_string(), &parts)?;
+    }
+    let mut parts = contract.owner_parts_by_type.may_load(storage, type_key(from))?.unwrap_or_default();
+    parts.retain(|&x| x != part_id);
+    contract.owner_parts_by_type.save(storage, type_key(from), &parts)?;
+
+    // Agregar a las listas del nuevo propietario
+    for list in [&contract.owner_parts, state_list] {
+        let mut parts = list.may_load(storage, to.to_string())?.unwrap_or_default();
+        parts.push(part_id);
+        list.save(storage, to.to_string(), &parts)?;
+    }
+    let mut parts = contract.owner_parts_by_type.may_load(storage, type_key(to))?.unwrap_or_default();
+    parts.push(part_id);
+    contract.owner_parts_by_type.save(storage, type_key(to), &parts)?;
+    contract.part_owners.save(storage, part_id, &to.to_string())
+}
+
+fn execute_set_rarity_bounds(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    rarity: Rarity,
+    min: u8,
+    max: u8,
+) -> StdResult<Response> {
+    // Solo el admin puede cambiar los límites de una rareza
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede configurar los límites de rareza"));
+    }
+
+    if min > max || max > MAX_STAT {
+        return Err(StdError::generic_err(format!(
+            "Límites inválidos: se requiere min <= max <= {}",
+            MAX_STAT
+        )));
+    }
+
+    contract.rarity_bounds.save(deps.storage, rarity.to_string(), &StatBounds { min, max })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_rarity_bounds")
+        .add_attribute("rarity", rarity.to_string())
+        .add_attribute("min", min.to_string())
+        .add_attribute("max", max.to_string()))
+}
+
+fn ensure_admin(deps: Deps, contract: &CarPartContract, info: &MessageInfo) -> StdResult<()> {
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede realizar esta acción"));
+    }
+    Ok(())
+}
+
+fn execute_set_pack(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    pack_id: String,
+    mut pack: PackConfig,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    if pack.size == 0 || pack.size > MAX_PACK_SIZE {
+        return Err(StdError::generic_err(format!(
+            "El tamaño del pack debe estar entre 1 y {}",
+            MAX_PACK_SIZE
+        )));
+    }
+    if pack.drop_table.is_empty() || pack.drop_table.iter().any(|entry| entry.weight == 0) {
+        return Err(StdError::generic_err("La tabla de drops debe tener entradas con peso mayor que 0"));
+    }
+    for entry in pack.drop_table.iter_mut() {
+        entry.image_uri = normalize_uri(&entry.image_uri).map_err(StdError::generic_err)?;
+    }
+
+    contract.packs.save(deps.storage, pack_id.clone(), &pack)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_pack")
+        .add_attribute("pack_id", pack_id)
+        .add_attribute("price", pack.price.to_string())
+        .add_attribute("size", pack.size.to_string()))
+}
+
+fn execute_remove_pack(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    pack_id: String,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+    contract.packs.remove(deps.storage, pack_id.clone());
+
+    Ok(Response::new()
+        .add_attribute("method", "remove_pack")
+        .add_attribute("pack_id", pack_id))
+}
+
+fn execute_buy_pack(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarPartContract,
+    pack_id: String,
+    commitment: String,
+) -> StdResult<Response> {
+    let pack = contract.packs
+        .may_load(deps.storage, pack_id.clone())?
+        .ok_or_else(|| StdError::generic_err("El pack no existe"))?;
+
+    // Sin servidor de juego nadie publicaría la segunda semilla y el pack no se podría abrir
+    if contract.game_server.may_load(deps.storage)?.is_none() {
+        return Err(StdError::generic_err("El servidor de juego no está configurado"));
+    }
+
+    // El pack se paga exacto, así no queda saldo de más en el contrato
+    check_exact_payment(&info, pack.price, "El pack")?;
+
+    let commitment = randomness::parse_commitment(&commitment).map_err(StdError::generic_err)?;
+
+    // Un pack sin abrir solo se puede reemplazar cuando su plazo ya expiró
+    if let Some(pending) = contract.pending_packs.may_load(deps.storage, &info.sender)? {
+        if env.block.height <= pending.height + PACK_REVEAL_WINDOW {
+            return Err(StdError::generic_err("Ya tienes un pack pendiente de abrir"));
+        }
+    }
+
+    contract.pending_packs.save(deps.storage, &info.sender, &PendingPack {
+        pack_id: pack_id.clone(),
+        pack,
+        commitment,
+        height: env.block.height,
+        server_seed: None,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "buy_pack")
+        .add_attribute("pack_id", pack_id)
+        .add_attribute("owner", info.sender)
+        .add_attribute("height", env.block.height.to_string()))
+}
+
+fn execute_open_pack(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarPartContract,
+    secret: String,
+) -> StdResult<Response> {
+    let pending = contract.pending_packs
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| StdError::generic_err("No tienes un pack pendiente de abrir"))?;
+
+    if env.block.height <= pending.height {
+        return Err(StdError::generic_err("El pack se debe abrir en un bloque posterior a la compra"));
+    }
+    if env.block.height > pending.height + PACK_REVEAL_WINDOW {
+        return Err(StdError::generic_err("El plazo para abrir el pack expiró"));
+    }
+    let server_seed = pending.server_seed.as_deref()
+        .ok_or_else(|| StdError::generic_err("El servidor de juego todavía no publicó su semilla"))?;
+    randomness::check_reveal(&pending.commitment, &secret).map_err(StdError::generic_err)?;
+    contract.pending_packs.remove(deps.storage, &info.sender);
+
+    // La semilla sale del secreto del comprador, la semilla del servidor y los bloques fijados en la compra;
+    // el bloque de apertura no entra, así revertir la transacción no cambia las partes
+    let seed = randomness::derive_seed(
+        &[(info.sender.as_str(), &secret), ("game_server", server_seed)],
+        pending.height,
+        pending.height + PACK_REVEAL_WINDOW,
+    );
+
+    // Las partes del pack llegan sin equipar
+    let mut part_ids = Vec::new();
+    for index in 0..pending.pack.size {
+        let roll = Sha256::new()
+            .chain_update(&seed)
+            .chain_update([index])
+            .finalize();
+        let part_stats = roll_drop(deps.as_ref(), &contract, &pending.pack.drop_table, &roll)?;
+        part_ids.push(mint_part(deps.branch(), &contract, info.sender.to_string(), &part_stats, 0)?);
+    }
+
+    let part_ids: Vec<String> = part_ids.iter().map(|id| id.to_string()).collect();
+    Ok(Response::new()
+        .add_attribute("method", "open_pack")
+        .add_attribute("pack_id", pending.pack_id)
+        .add_attribute("owner", info.sender)
+        .add_attribute("part_ids", part_ids.join(",")))
+}
+
+fn execute_set_game_server(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    address: String,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+    let server_addr = deps.api.addr_validate(&address)?;
+    contract.game_server.save(deps.storage, &server_addr)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_game_server")
+        .add_attribute("address", address))
+}
+
+// El servidor de juego publica su semilla para el pack pendiente de un comprador. Llega después
+// de la compra, así el comprador ya no puede cambiar su secreto, y no se puede reemplazar.
+fn execute_submit_pack_seed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarPartContract,
+    owner: String,
+    seed: String,
+) -> StdResult<Response> {
+    let game_server = contract.game_server.may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("El servidor de juego no está configurado"))?;
+    if info.sender != game_server {
+        return Err(StdError::generic_err("Solo el servidor de juego puede publicar la semilla"));
+    }
+    if seed.is_empty() {
+        return Err(StdError::generic_err("La semilla del servidor no puede estar vacía"));
+    }
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let mut pending = contract.pending_packs
+        .may_load(deps.storage, &owner_addr)?
+        .ok_or_else(|| StdError::generic_err("No hay un pack pendiente para este comprador"))?;
+    if env.block.height > pending.height + PACK_REVEAL_WINDOW {
+        return Err(StdError::generic_err("El plazo para abrir el pack expiró"));
+    }
+    if pending.server_seed.is_some() {
+        return Err(StdError::generic_err("El servidor ya publicó su semilla para este pack"));
+    }
+    pending.server_seed = Some(seed);
+    contract.pending_packs.save(deps.storage, &owner_addr, &pending)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "submit_pack_seed")
+        .add_attribute("owner", owner_addr))
+}
+
+// Verificar que se pagó exactamente `price` en uxion y ninguna otra moneda
+fn check_exact_payment(info: &MessageInfo, price: Uint128, what: &str) -> StdResult<()> {
+    let paid = info.funds.iter()
+        .filter(|coin| coin.denom == "uxion")
+        .map(|coin| coin.amount)
+        .sum::<Uint128>();
+    if paid != price || info.funds.iter().any(|coin| coin.denom != "uxion") {
+        return Err(StdError::generic_err(format!("{} cuesta exactamente {}uxion", what, price)));
+    }
+    Ok(())
+}
+
+// Elegir una entrada de la tabla según su peso y tirar los stats dentro de los límites de su rareza
+fn roll_drop(deps: Deps, contract: &CarPartContract, drop_table: &[DropEntry], roll: &[u8]) -> StdResult<PartStats> {
+    let total_weight: u64 = drop_table.iter().map(|entry| entry.weight as u64).sum();
+    let mut pick = u64::from_be_bytes(roll[0..8].try_into().unwrap()) % total_weight;
+    let entry = drop_table
+        .iter()
+        .find(|entry| {
+            if pick < entry.weight as u64 {
+                return true;
+            }
+            pick -= entry.weight as u64;
+            false
+        })
+        .ok_or_else(|| StdError::generic_err("Tabla de drops vacía"))?;
+
+    let bounds = load_rarity_bounds(deps, contract, &entry.rarity)?;
+    let span = bounds.max.saturating_sub(bounds.min) + 1;
+    Ok(PartStats {
+        part_type: entry.part_type.clone(),
+        stat1: bounds.min + roll[8] % span,
+        stat2: bounds.min + roll[9] % span,
+        stat3: bounds.min + roll[10] % span,
+        image_uri: entry.image_uri.clone(),
+        rarity: entry.rarity.clone(),
+    })
+}
+
+fn execute_withdraw_funds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarPartContract,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    // Retirar lo recaudado con la venta de packs. Se retira todo el balance, incluido lo que
+    // cubriría los rescates de las partes quemadas
+    let balance = deps.querier.query_all_balances(&env.contract.address)?;
+    if balance.is_empty() {
+        return Err(StdError::generic_err("El contrato no tiene fondos para retirar"));
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: balance.clone(),
+        })
+        .add_attribute("method", "withdraw_funds")
+        .add_attribute("recipient", info.sender)
+        .add_attribute("amount", format!("{:?}", balance)))
+}
+
+fn execute_burn_part(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarPartContract,
+    part_id: u64,
+) -> StdResult<Response> {
+    // Solo el propietario puede quemar una parte, y solo si no está equipada
+    let owner = info.sender.to_string();
+    let owner_parts = contract.owner_parts.may_load(deps.storage, owner.clone())?.unwrap_or_default();
+    if !owner_parts.contains(&part_id) {
+        return Err(StdError::generic_err("La parte no pertenece al remitente"));
+    }
+    if contract.equipped_in_car.may_load(deps.storage, part_id)?.unwrap_or(0) > 0 {
+        return Err(StdError::generic_err("No se puede quemar una parte equipada"));
+    }
+
+    let payout = salvage_payout(deps.as_ref(), &env, &contract, &owner, 1)?;
+    remove_part(deps.storage, &contract, &owner, part_id)?;
+
+    Ok(Response::new()
+        .add_messages(payout)
+        .add_attribute("method", "burn_part")
+        .add_attribute("part_id", part_id.to_string())
+        .add_attribute("owner", owner))
+}
+
+// Quemar las partes equipadas en un carro que se está quemando
+#[allow(clippy::too_many_arguments)]
+fn execute_burn_car_parts(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarPartContract,
+    owner: String,
+    car_id: u64,
+    part_ids: Vec<u64>,
+) -> StdResult<Response> {
+    // Verificar que el remitente es el contrato de carros
+    let car_contract = contract.car_contract.load(deps.storage)?;
+    if info.sender != car_contract {
+        return Err(StdError::generic_err("Solo el contrato de carros puede quemar partes equipadas"));
+    }
+
+    let payout = salvage_payout(deps.as_ref(), &env, &contract, &owner, part_ids.len() as u128)?;
+    let owner_parts = contract.owner_parts.may_load(deps.storage, owner.clone())?.unwrap_or_default();
+    for &part_id in &part_ids {
+        if !owner_parts.contains(&part_id) {
+            return Err(StdError::generic_err(format!("La parte {} no pertenece al dueño del carro", part_id)));
+        }
+        if contract.equipped_in_car.may_load(deps.storage, part_id)? != Some(car_id) {
+            return Err(StdError::generic_err(format!("La parte {} no está equipada en el carro {}", part_id, car_id)));
+        }
+        remove_part(deps.storage, &contract, &owner, part_id)?;
+    }
+
+    let part_ids: Vec<String> = part_ids.iter().map(|id| id.to_string()).collect();
+    Ok(Response::new()
+        .add_messages(payout)
+        .add_attribute("method", "burn_car_parts")
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("owner", owner)
+        .add_attribute("part_ids", part_ids.join(",")))
+}
+
+fn execute_set_salvage_value(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    amount: Uint128,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+    contract.salvage_value.save(deps.storage, &amount)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_salvage_value")
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_set_royalties(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    payment_address: String,
+    percent: u8,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+    if percent > MAX_ROYALTY_PERCENT {
+        return Err(StdError::generic_err(format!("La regalía no puede superar el {}%", MAX_ROYALTY_PERCENT)));
+    }
+    let payment_address = deps.api.addr_validate(&payment_address)?;
+    contract.royalties.save(deps.storage, &RoyaltyConfig { payment_address: payment_address.clone(), percent })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_royalties")
+        .add_attribute("payment_address", payment_address)
+        .add_attribute("percent", percent.to_string()))
+}
+
+// Regalía CW2981 para una venta de `sale_price`; cero si no hay regalías configuradas
+fn query_royalty_info(deps: Deps, contract: CarPartContract, token_id: String, sale_price: Uint128) -> StdResult<RoyaltiesInfoResponse> {
+    let part_id: u64 = token_id.parse()
+        .map_err(|_| StdError::generic_err("ID de token inválido"))?;
+    if !contract.part_stats.has(deps.storage, part_id) {
+        return Err(StdError::generic_err("La parte no existe"));
+    }
+    Ok(match contract.royalties.may_load(deps.storage)? {
+        Some(royalties) => RoyaltiesInfoResponse {
+            address: royalties.payment_address.to_string(),
+            royalty_amount: sale_price.multiply_ratio(royalties.percent, 100u128),
+        },
+        None => RoyaltiesInfoResponse {
+            address: String::new(),
+            royalty_amount: Uint128::zero(),
+        },
+    })
+}
+
+fn execute_fuse_part(
+    mut deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    part_ids: Vec<u64>,
+) -> StdResult<Response> {
+    let rules = contract.fusion_rules.may_load(deps.storage)?.unwrap_or_default();
+    if part_ids.len() != rules.inputs_required as usize {
+        return Err(StdError::generic_err(format!(
+            "La fusión requiere exactamente {} partes",
+            rules.inputs_required
+        )));
+    }
+    let mut unique_ids = part_ids.clone();
+    unique_ids.sort_unstable();
+    unique_ids.dedup();
+    if unique_ids.len() != part_ids.len() {
+        return Err(StdError::generic_err("Las partes a fusionar no se pueden repetir"));
+    }
+
+    // Todas las partes deben ser del remitente, estar sin equipar y ser del mismo tipo
+    let owner = info.sender.to_string();
+    let owner_parts = contract.owner_parts.may_load(deps.storage, owner.clone())?.unwrap_or_default();
+    let mut inputs = Vec::new();
+    for &part_id in &part_ids {
+        if !owner_parts.contains(&part_id) {
+            return Err(StdError::generic_err(format!("La parte {} no pertenece al remitente", part_id)));
+        }
+        if contract.equipped_in_car.may_load(deps.storage, part_id)?.unwrap_or(0) > 0 {
+            return Err(StdError::generic_err(format!("La parte {} está equipada", part_id)));
+        }
+        inputs.push(contract.part_stats.load(deps.storage, part_id)?);
+    }
+    if inputs.iter().any(|part| part.part_type != inputs[0].part_type) {
+        return Err(StdError::generic_err("Las partes a fusionar deben ser del mismo tipo"));
+    }
+
+    // La rareza resultante es la mayor de las entradas, un nivel más si las reglas lo indican
+    let mut rarity = inputs.iter().map(|part| part.rarity.clone()).max_by_key(Rarity::rank).unwrap_or_default();
+    if rules.rarity_upgrade {
+        rarity = next_rarity(&rarity);
+    }
+    let bounds = load_rarity_bounds(deps.as_ref(), &contract, &rarity)?;
+    let fuse_stat = |stat: fn(&PartStats) -> u8| {
+        let average = inputs.iter().map(|part| stat(part) as u32).sum::<u32>() / inputs.len() as u32;
+        (average as u8).saturating_add(rules.bonus).clamp(bounds.min, bounds.max)
+    };
+    let fused = PartStats {
+        part_type: inputs[0].part_type.clone(),
+        stat1: fuse_stat(|part| part.stat1),
+        stat2: fuse_stat(|part| part.stat2),
+        stat3: fuse_stat(|part| part.stat3),
+        image_uri: inputs[0].image_uri.clone(),
+        rarity,
+    };
+
+    for &part_id in &part_ids {
+        remove_part(deps.storage, &contract, &owner, part_id)?;
+    }
+    let new_part_id = mint_part(deps.branch(), &contract, owner.clone(), &fused, 0)?;
+
+    let input_ids: Vec<String> = part_ids.iter().map(|id| id.to_string()).collect();
+    Ok(Response::new()
+        .add_event(Event::new("part_fusion")
+            .add_attribute("owner", owner.clone())
+            .add_attribute("inputs", input_ids.join(","))
+            .add_attribute("output", new_part_id.to_string()))
+        .add_attribute("method", "fuse_part")
+        .add_attribute("owner", owner)
+        .add_attribute("part_id", new_part_id.to_string())
+        .add_attribute("rarity", fused.rarity.to_string()))
+}
+
+fn execute_set_fusion_rules(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    rules: FusionRules,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    if rules.inputs_required < 2 {
+        return Err(StdError::generic_err("La fusión requiere al menos 2 partes"));
+    }
+    if rules.bonus > MAX_STAT {
+        return Err(StdError::generic_err(format!("El bonus no puede superar {}", MAX_STAT)));
+    }
+    contract.fusion_rules.save(deps.storage, &rules)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_fusion_rules")
+        .add_attribute("inputs_required", rules.inputs_required.to_string())
+        .add_attribute("bonus", rules.bonus.to_string())
+        .add_attribute("rarity_upgrade", rules.rarity_upgrade.to_string()))
+}
+
+fn execute_tune_part(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: CarPartContract,
+    part_id: u64,
+    stat: String,
+) -> StdResult<Response> {
+    // Solo el propietario puede mejorar su parte
+    let owner_parts = contract.owner_parts.may_load(deps.storage, info.sender.to_string())?.unwrap_or_default();
+    if !owner_parts.contains(&part_id) {
+        return Err(StdError::generic_err("La parte no pertenece al remitente"));
+    }
+    let mut part_stats = contract.part_stats.load(deps.storage, part_id)?;
+    let stat_index = part_stats.part_type
+        .stat_names()
+        .iter()
+        .position(|name| *name == stat)
+        .ok_or_else(|| StdError::generic_err(format!(
+            "Stat inválido para {}: se espera uno de {:?}",
+            part_stats.part_type,
+            part_stats.part_type.stat_names()
+        )))?;
+
+    let config = contract.tuning_config.may_load(deps.storage)?.unwrap_or_default();
+    let mut upgrades = contract.part_upgrades.may_load(deps.storage, part_id)?.unwrap_or_default();
+    if upgrades.stat_upgrades[stat_index] >= config.max_upgrades_per_stat {
+        return Err(StdError::generic_err(format!(
+            "El stat {} ya alcanzó el máximo de {} mejoras",
+            stat, config.max_upgrades_per_stat
+        )));
+    }
+
+    // El stat no puede superar el máximo de la rareza de la parte
+    let bounds = load_rarity_bounds(deps.as_ref(), &contract, &part_stats.rarity)?;
+    let value = match stat_index {
+        0 => &mut part_stats.stat1,
+        1 => &mut part_stats.stat2,
+        _ => &mut part_stats.stat3,
+    };
+    if *value >= bounds.max {
+        return Err(StdError::generic_err(format!(
+            "El stat {} ya está en el máximo de una parte {}",
+            stat, part_stats.rarity
+        )));
+    }
+
+    // La tarifa sube con cada mejora de la parte y se paga exacta
+    let fee = tuning_fee(&config, upgrades.upgrade_level)?;
+    check_exact_payment(&info, fee, "El tuning")?;
+
+    let from = *value;
+    *value += 1;
+    let to = *value;
+    upgrades.upgrade_level += 1;
+    upgrades.stat_upgrades[stat_index] += 1;
+    upgrades.history.push(StatUpgrade {
+        stat: stat.clone(),
+        from,
+        to,
+        fee,
+        height: env.block.height,
+    });
+    contract.part_stats.save(deps.storage, part_id, &part_stats)?;
+    contract.part_upgrades.save(deps.storage, part_id, &upgrades)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "tune_part")
+        .add_attribute("part_id", part_id.to_string())
+        .add_attribute("stat", stat)
+        .add_attribute("value", to.to_string())
+        .add_attribute("upgrade_level", upgrades.upgrade_level.to_string())
+        .add_attribute("fee", fee.to_string()))
+}
+
+fn execute_set_tuning_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    config: TuningConfig,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    // Un stat no puede recibir más mejoras que valores posibles tiene
+    if config.max_upgrades_per_stat == 0 || config.max_upgrades_per_stat > MAX_STAT {
+        return Err(StdError::generic_err(format!(
+            "Las mejoras por stat deben estar entre 1 y {}",
+            MAX_STAT
+        )));
+    }
+    // La tarifa de la última mejora posible tiene que poder calcularse
+    tuning_fee(&config, config.max_upgrades_per_stat as u32 * 3)?;
+
+    contract.tuning_config.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_tuning_config")
+        .add_attribute("base_fee", config.base_fee.to_string())
+        .add_attribute("fee_step", config.fee_step.to_string())
+        .add_attribute("max_upgrades_per_stat", config.max_upgrades_per_stat.to_string()))
+}
+
+// Configurar (o quitar con `None`) el gateway con el que se resuelven las URIs ipfs://
+fn execute_set_image_gateway(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    gateway: Option<String>,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    let gateway = match gateway {
+        Some(gateway) => {
+            let gateway = normalize_gateway(&gateway).map_err(StdError::generic_err)?;
+            contract.image_gateway.save(deps.storage, &gateway)?;
+            gateway
+        },
+        None => {
+            contract.image_gateway.remove(deps.storage);
+            String::new()
+        },
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", "set_image_gateway")
+        .add_attribute("gateway", gateway))
+}
+
+fn tuning_fee(config: &TuningConfig, upgrade_level: u32) -> StdResult<Uint128> {
+    let fee = config.fee_step
+        .checked_mul(Uint128::from(upgrade_level))
+        .and_then(|step| config.base_fee.checked_add(step))
+        .map_err(|_| StdError::generic_err("La tarifa del tuning excede el máximo representable"))?;
+    Ok(fee)
+}
+
+// Desgaste de una carrera sobre las partes de un carro. Cada parte pierde `wear` escalado por su función.
+fn execute_degrade_parts(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    part_ids: Vec<u64>,
+    wear: u8,
+) -> StdResult<Response> {
+    // Verificar que el remitente es el contrato de carros
+    let car_contract = contract.car_contract.load(deps.storage)?;
+    if info.sender != car_contract {
+        return Err(StdError::generic_err("Solo el contrato de carros puede degradar partes"));
+    }
+
+    let mut durabilities = Vec::new();
+    for &part_id in &part_ids {
+        let part_stats = contract.part_stats.load(deps.storage, part_id)?;
+        let loss = (wear as u16 * part_stats.part_type.wear_factor() as u16 / 100).min(MAX_DURABILITY as u16) as u8;
+        let durability = contract.part_durability
+            .may_load(deps.storage, part_id)?
+            .unwrap_or(MAX_DURABILITY)
+            .saturating_sub(loss);
+        contract.part_durability.save(deps.storage, part_id, &durability)?;
+        durabilities.push(format!("{}:{}", part_id, durability));
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "degrade_parts")
+        .add_attribute("wear", wear.to_string())
+        .add_attribute("durability", durabilities.join(",")))
+}
+
+fn execute_repair_parts(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: CarPartContract,
+    part_ids: Vec<u64>,
+) -> StdResult<Response> {
+    // Las reparaciones llegan desde el contrato de carros, que solo acepta las del taller
+    let car_contract = contract.car_contract.load(deps.storage)?;
+    if info.sender != car_contract {
+        return Err(StdError::generic_err("Solo el contrato de carros puede reparar partes"));
+    }
+
+    for &part_id in &part_ids {
+        if !contract.part_stats.has(deps.storage, part_id) {
+            return Err(StdError::generic_err(format!("La parte {} no existe", part_id)));
+        }
+        contract.part_durability.remove(deps.storage, part_id);
+    }
+
+    let part_ids: Vec<String> = part_ids.iter().map(|id| id.to_string()).collect();
+    Ok(Response::new()
+        .add_attribute("method", "repair_parts")
+        .add_attribute("part_ids", part_ids.join(",")))
+}
+
+fn next_rarity(rarity: &Rarity) -> Rarity {
+    match rarity {
+        Rarity::Common => Rarity::Rare,
+        Rarity::Rare => Rarity::Epic,
+        Rarity::Epic | Rarity::Legendary => Rarity::Legendary,
+    }
+}
+
+// Borrar una parte de los stats, del estado de equipamiento y de todas las listas del propietario
+fn remove_part(storage: &mut dyn Storage, contract: &CarPartContract, owner: &str, part_id: u64) -> StdResult<()> {
+    let part_stats = contract.part_stats.load(storage, part_id)?;
+    contract.part_stats.remove(storage, part_id);
+    contract.part_owners.remove(storage, part_id);
+    contract.equipped_in_car.remove(storage, part_id);
+    contract.part_upgrades.remove(storage, part_id);
+    contract.part_durability.remove(storage, part_id);
+
+    let owner_lists = [
+        &contract.owner_parts,
+        &contract.owner_equipped_parts,
+        &contract.owner_unequipped_parts,
+    ];
+    for list in owner_lists {
+        if let Some(mut parts) = list.may_load(storage, owner.to_string())? {
+            parts.retain(|&x| x != part_id);
+            list.save(storage, owner.to_string(), &parts)?;
+        }
+    }
+
+    let type_key = (owner.to_string(), part_stats.part_type.to_string());
+    if let Some(mut parts) = contract.owner_parts_by_type.may_load(storage, type_key.clone())? {
+        parts.retain(|&x| x != part_id);
+        contract.owner_parts_by_type.save(storage, type_key, &parts)?;
+    }
+    Ok(())
+}
+
+// Pago de rescate por `count` partes quemadas, tomado del balance del contrato. No hay una reserva
+// aparte: si `withdraw_funds` vació el balance, la quema falla hasta que el admin vuelva a fondearlo.
+fn salvage_payout(deps: Deps, env: &Env, contract: &CarPartContract, to: &str, count: u128) -> StdResult<Option<BankMsg>> {
+    let salvage_value = contract.salvage_value.may_load(deps.storage)?.unwrap_or_default();
+    let amount = salvage_value
+        .checked_mul(Uint128::new(count))
+        .map_err(|_| StdError::generic_err("El rescate excede el máximo representable"))?;
+    if amount.is_zero() {
+        return Ok(None);
+    }
+
+    let balance = deps.querier.query_balance(&env.contract.address, "uxion")?;
+    if balance.amount < amount {
+        return Err(StdError::generic_err("El contrato no tiene fondos para pagar el rescate"));
+    }
+    Ok(Some(BankMsg::Send {
+        to_address: to.to_string(),
+        amount: vec![Coin::new(amount.u128(), "uxion")],
+    }))
+}
+
+// Límites de una rareza; si el admin no los configuró se permite todo el rango 0..=MAX_STAT
+fn load_rarity_bounds(deps: Deps, contract: &CarPartContract, rarity: &Rarity) -> StdResult<StatBounds> {
+    Ok(contract.rarity_bounds.may_load(deps.storage, rarity.to_string())?.unwrap_or_default())
+}
+
+// Funciones de consulta
+fn query_part_stats(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<PartStats> {
+    contract.part_stats.load(deps.storage, part_id)
+}
+
+fn query_part_type(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<PartType> {
+    let part_stats = contract.part_stats.load(deps.storage, part_id)?;
+    Ok(part_stats.part_type)
+}
+
+fn query_is_equipped(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<bool> {
+    Ok(contract.equipped_in_car.may_load(deps.storage, part_id)?.unwrap_or(0) > 0)
+}
+
+fn query_equipped_car(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<u64> {
+    Ok(contract.equipped_in_car.may_load(deps.storage, part_id)?.unwrap_or(0))
+}
+
+fn query_part_owner(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<PartOwnerResponse> {
+    Ok(PartOwnerResponse {
+        owner: contract.part_owners.load(deps.storage, part_id)?,
+        equipped_in_car: contract.equipped_in_car.may_load(deps.storage, part_id)?.unwrap_or(0),
+    })
+}
+
+fn query_owner_parts(deps: Deps, contract: CarPartContract, owner: String) -> StdResult<Vec<u64>> {
+    Ok(contract.owner_parts.may_load(deps.storage, owner)?.unwrap_or_default())
+}
+
+fn query_owner_parts_by_type(deps: Deps, contract: CarPartContract, owner: String, part_type: PartType) -> StdResult<Vec<u64>> {
+    Ok(contract.owner_parts_by_type.may_load(deps.storage, (owner, part_type.to_string()))?.unwrap_or_default())
+}
+
+fn query_owner_equipped_parts(deps: Deps, contract: CarPartContract, owner: String) -> StdResult<Vec<u64>> {
+    Ok(contract.owner_equipped_parts.may_load(deps.storage, owner)?.unwrap_or_default())
+}
+
+fn query_owner_unequipped_parts(deps: Deps, contract: CarPartContract, owner: String) -> StdResult<Vec<u64>> {
     Ok(contract.owner_unequipped_parts.may_load(deps.storage, owner)?.unwrap_or_default())
 }
 
+fn query_owner_parts_by_rarity(deps: Deps, contract: CarPartContract, owner: String, rarity: Rarity) -> StdResult<Vec<u64>> {
+    let owner_parts = contract.owner_parts.may_load(deps.storage, owner)?.unwrap_or_default();
+    let mut parts = Vec::new();
+    for part_id in owner_parts {
+        if contract.part_stats.load(deps.storage, part_id)?.rarity == rarity {
+            parts.push(part_id);
+        }
+    }
+    Ok(parts)
+}
+
+fn query_packs(deps: Deps, contract: CarPartContract) -> StdResult<PacksResponse> {
+    let packs = contract.packs
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(pack_id, pack)| PackInfo { pack_id, pack }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PacksResponse { packs })
+}
+
+fn query_pending_pack(deps: Deps, contract: CarPartContract, owner: String) -> StdResult<Option<PendingPack>> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    contract.pending_packs.may_load(deps.storage, &owner_addr)
+}
+
+// Metadata CW721 de una parte: tipo, stats con su nombre, rareza y durabilidad actuales
+fn query_nft_info(deps: Deps, contract: CarPartContract, token_id: String, resolve_ipfs: bool) -> StdResult<NftInfoResponse> {
+    let part_id: u64 = token_id
+        .parse()
+        .map_err(|_| StdError::generic_err(format!("token_id inválido: {}", token_id)))?;
+    let part_stats = contract.part_stats.load(deps.storage, part_id)?;
+    let upgrades = contract.part_upgrades.may_load(deps.storage, part_id)?.unwrap_or_default();
+    let durability = contract.part_durability.may_load(deps.storage, part_id)?.unwrap_or(MAX_DURABILITY);
+
+    let mut attributes = vec![Trait::text("part_type", &part_stats.part_type)];
+    let stats = [part_stats.stat1, part_stats.stat2, part_stats.stat3];
+    for (name, value) in part_stats.part_type.stat_names().iter().zip(stats) {
+        attributes.push(Trait::number(name, value));
+    }
+    attributes.push(Trait::text("rarity", &part_stats.rarity));
+    attributes.push(Trait::number("durability", durability));
+    attributes.push(Trait::number("upgrade_level", upgrades.upgrade_level));
+    if let Some(car_id) = contract.equipped_in_car.may_load(deps.storage, part_id)? {
+        attributes.push(Trait::number("equipped_car", car_id));
+    }
+
+    Ok(NftInfoResponse {
+        token_uri: None,
+        extension: Metadata {
+            name: format!("{} #{}", part_stats.part_type, part_id),
+            description: format!("Parte {} {} de Speed Rush 2D", part_stats.part_type, part_stats.rarity),
+            image: if resolve_ipfs {
+                let gateway = contract.image_gateway.may_load(deps.storage)?;
+                resolve_uri(&part_stats.image_uri, gateway.as_deref())
+            } else {
+                part_stats.image_uri
+            },
+            attributes,
+        },
+    })
+}
+
+fn query_part_durability(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<u8> {
+    Ok(contract.part_durability.may_load(deps.storage, part_id)?.unwrap_or(MAX_DURABILITY))
+}
+
+// Tarifa de la próxima mejora de una parte
+fn query_tuning_fee(deps: Deps, contract: CarPartContract, part_id: u64) -> StdResult<Uint128> {
+    let config = contract.tuning_config.may_load(deps.storage)?.unwrap_or_default();
+    let upgrades = contract.part_upgrades.may_load(deps.storage, part_id)?.unwrap_or_default();
+    tuning_fee(&config, upgrades.upgrade_level)
+}
+
+fn query_rarity_bounds(deps: Deps, contract: CarPartContract, rarity: Rarity) -> StdResult<StatBounds> {
+    load_rarity_bounds(deps, &contract, &rarity)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::from_json;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{coins, from_json, CosmosMsg, OwnedDeps};
 
     #[test]
     fn proper_initialization() {
@@ -454,15 +1854,31 @@ mod tests {
             stat1: 8,
             stat2: 7,
             stat3: 6,
-            image_uri: "engine_uri".to_string(),
+            image_uri: "ipfs://engine_uri".to_string(),
             car_id: 1,
+            rarity: Rarity::Common,
         };
 
-        let err = execute(deps.as_mut(), env.clone(), unauthorized_info, mint_msg.clone()).unwrap_err();
+        let err = execute(deps.as_mut(), env.clone(), unauthorized_info.clone(), mint_msg.clone()).unwrap_err();
         assert!(err.to_string().contains("Solo el contrato de carros puede mintear partes"));
 
-        // Mintear una parte desde el contrato de carros
+        // Tampoco puede hacerse pasar por el contrato de carros
+        let set_car_contract = ExecuteMsg::SetCarContract { address: "unauthorized".to_string() };
+        let err = execute(deps.as_mut(), env.clone(), unauthorized_info, set_car_contract).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+
+        // URIs sin esquema permitido o demasiado largas se rechazan
         let car_contract_info = mock_info("car_contract", &[]);
+        let bad_uris = ["engine_uri".to_string(), "http://engine_uri".to_string(), format!("ipfs://{}", "a".repeat(car_types::MAX_URI_LENGTH))];
+        for bad_uri in bad_uris {
+            let mut bad_msg = mint_msg.clone();
+            if let ExecuteMsg::Mint { image_uri, .. } = &mut bad_msg {
+                *image_uri = bad_uri;
+            }
+            assert!(execute(deps.as_mut(), env.clone(), car_contract_info.clone(), bad_msg).is_err());
+        }
+
+        // Mintear una parte desde el contrato de carros
         let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
         
         // Verificar que se guardaron los stats correctamente
@@ -474,7 +1890,7 @@ mod tests {
         assert_eq!(8, part_stats.stat1);
         assert_eq!(7, part_stats.stat2);
         assert_eq!(6, part_stats.stat3);
-        assert_eq!("engine_uri", part_stats.image_uri);
+        assert_eq!("ipfs://engine_uri", part_stats.image_uri);
 
         // Verificar que la parte está equipada
         let query_msg = QueryMsg::IsEquipped { part_id: 0 };
@@ -525,8 +1941,9 @@ mod tests {
             stat1: 8,
             stat2: 7,
             stat3: 6,
-            image_uri: "engine_uri".to_string(),
+            image_uri: "ipfs://engine_uri".to_string(),
             car_id: 1,
+            rarity: Rarity::Common,
         };
         let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
 
@@ -537,8 +1954,9 @@ mod tests {
             stat1: 5,
             stat2: 6,
             stat3: 7,
-            image_uri: "transmission_uri".to_string(),
+            image_uri: "ipfs://transmission_uri".to_string(),
             car_id: 1,
+            rarity: Rarity::Common,
         };
         let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
 
@@ -549,8 +1967,9 @@ mod tests {
             stat1: 4,
             stat2: 5,
             stat3: 6,
-            image_uri: "wheels_uri".to_string(),
+            image_uri: "ipfs://wheels_uri".to_string(),
             car_id: 1,
+            rarity: Rarity::Common,
         };
         let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
 
@@ -597,8 +2016,9 @@ mod tests {
             stat1: 8,
             stat2: 7,
             stat3: 6,
-            image_uri: "engine_uri".to_string(),
+            image_uri: "ipfs://engine_uri".to_string(),
             car_id: 1,
+            rarity: Rarity::Common,
         };
         let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
 
@@ -633,6 +2053,14 @@ mod tests {
         let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
         let equipped_car: u64 = from_json(&res).unwrap();
         assert_eq!(2, equipped_car);
+
+        // Una parte equipada no se puede equipar en un tercer carro
+        let set_equipped_msg = ExecuteMsg::SetEquippedState { part_id: 0, car_id: 3 };
+        let err = execute(deps.as_mut(), env.clone(), car_contract_info, set_equipped_msg).unwrap_err();
+        assert!(err.to_string().contains("La parte ya está equipada en el carro 2"));
+        let res = query(deps.as_ref(), env, QueryMsg::GetPartOwner { part_id: 0 }).unwrap();
+        let expected = PartOwnerResponse { owner: "owner".to_string(), equipped_in_car: 2 };
+        assert_eq!(expected, from_json::<PartOwnerResponse>(&res).unwrap());
     }
 
     #[test]
@@ -655,8 +2083,9 @@ mod tests {
             stat1: 8,
             stat2: 7,
             stat3: 6,
-            image_uri: "engine_uri".to_string(),
+            image_uri: "ipfs://engine_uri".to_string(),
             car_id: 0, // No equipada
+            rarity: Rarity::Common,
         };
         let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
 
@@ -680,4 +2109,786 @@ mod tests {
         let owner_parts: Vec<u64> = from_json(&res).unwrap();
         assert_eq!(vec![0], owner_parts);
     }
-} 
\ No newline at end of file
+
+
+    #[test]
+    fn test_send_part() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        // Parte 0 suelta y parte 1 equipada en el carro 7
+        let car_contract_info = mock_info("car_contract", &[]);
+        for car_id in [0, 7] {
+            let mint_msg = ExecuteMsg::Mint {
+                to: "owner1".to_string(),
+                part_type: PartType::Engine,
+                stat1: 5,
+                stat2: 5,
+                stat3: 5,
+                image_uri: "ipfs://engine_uri".to_string(),
+                car_id,
+                rarity: Rarity::Common,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
+        }
+        let owner_parts = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, msg: QueryMsg| {
+            from_json::<Vec<u64>>(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+        };
+
+        // Solo el dueño, o el contrato de carros, y solo partes propias
+        let transfer = |from: &str| ExecuteMsg::TransferPart { from: from.to_string(), to: "owner2".to_string(), part_id: 0 };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("owner2", &[]), transfer("owner1")).is_err());
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner3", &[]), transfer("owner3")).unwrap_err();
+        assert!(err.to_string().contains("no pertenece a owner3"));
+
+        let send = |part_id| ExecuteMsg::SendPart { part_id, contract: "marketplace".to_string(), msg: Binary::from(b"{}".to_vec()) };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner1", &[]), send(1)).unwrap_err();
+        assert!(err.to_string().contains("equipada"));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner1", &[]), send(0)).unwrap();
+        let receive = ReceiverExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: "owner1".to_string(),
+            token_id: "0".to_string(),
+            msg: Binary::from(b"{}".to_vec()),
+        });
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "marketplace".to_string(),
+                msg: to_json_binary(&receive).unwrap(),
+                funds: vec![],
+            }),
+            res.messages[0].msg
+        );
+        assert_eq!(vec![0], owner_parts(&deps, QueryMsg::GetOwnerUnequippedParts { owner: "marketplace".to_string() }));
+
+        // El marketplace, ahora dueño, la entrega al comprador
+        let msg = ExecuteMsg::TransferPart { from: "marketplace".to_string(), to: "owner2".to_string(), part_id: 0 };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("marketplace", &[]), msg).unwrap();
+        assert_eq!(vec![0], owner_parts(&deps, QueryMsg::GetOwnerPartsByType { owner: "owner2".to_string(), part_type: PartType::Engine }));
+
+        // Las partes equipadas solo viajan con su carro
+        let car_parts = |car_id| ExecuteMsg::TransferCarParts { from: "owner1".to_string(), to: "owner2".to_string(), car_id, part_ids: vec![1] };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("owner1", &[]), car_parts(7)).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), car_contract_info.clone(), car_parts(8)).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info, car_parts(7)).unwrap();
+        assert!(owner_parts(&deps, QueryMsg::GetOwnerParts { owner: "owner1".to_string() }).is_empty());
+        assert_eq!(vec![1], owner_parts(&deps, QueryMsg::GetOwnerEquippedParts { owner: "owner2".to_string() }));
+        assert_eq!(vec![0, 1], owner_parts(&deps, QueryMsg::GetOwnerParts { owner: "owner2".to_string() }));
+    }
+
+    #[test]
+    fn test_equipped_lists_follow_owner() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let car_contract_info = mock_info("car_contract", &[]);
+        let mint_msg = ExecuteMsg::Mint {
+            to: "owner1".to_string(),
+            part_type: PartType::Engine,
+            stat1: 5,
+            stat2: 5,
+            stat3: 5,
+            image_uri: "ipfs://engine_uri".to_string(),
+            car_id: 0,
+            rarity: Rarity::Common,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
+        let lists = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, owner: &str| -> (Vec<u64>, Vec<u64>) {
+            let equipped = query(deps.as_ref(), mock_env(), QueryMsg::GetOwnerEquippedParts { owner: owner.to_string() }).unwrap();
+            let unequipped = query(deps.as_ref(), mock_env(), QueryMsg::GetOwnerUnequippedParts { owner: owner.to_string() }).unwrap();
+            (from_json(&equipped).unwrap(), from_json(&unequipped).unwrap())
+        };
+
+        // Equipar mueve la parte a la lista de equipadas del dueño
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), ExecuteMsg::SetEquippedState { part_id: 0, car_id: 7 }).unwrap();
+        assert_eq!((vec![0], vec![]), lists(&deps, "owner1"));
+
+        // Al transferir el carro el dueño anterior no conserva la parte en ninguna lista
+        let car_parts = ExecuteMsg::TransferCarParts { from: "owner1".to_string(), to: "owner2".to_string(), car_id: 7, part_ids: vec![0] };
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), car_parts).unwrap();
+        assert_eq!((vec![], vec![]), lists(&deps, "owner1"));
+        assert_eq!((vec![0], vec![]), lists(&deps, "owner2"));
+
+        // Lo mismo al desequiparla y transferirla suelta
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info, ExecuteMsg::SetEquippedState { part_id: 0, car_id: 0 }).unwrap();
+        assert_eq!((vec![], vec![0]), lists(&deps, "owner2"));
+        let transfer = ExecuteMsg::TransferPart { from: "owner2".to_string(), to: "owner3".to_string(), part_id: 0 };
+        let _res = execute(deps.as_mut(), env, mock_info("owner2", &[]), transfer).unwrap();
+        assert_eq!((vec![], vec![]), lists(&deps, "owner2"));
+        assert_eq!((vec![], vec![0]), lists(&deps, "owner3"));
+    }
+
+    #[test]
+    fn test_royalties() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let mint_msg = ExecuteMsg::Mint {
+            to: "owner1".to_string(),
+            part_type: PartType::Wheels,
+            stat1: 5,
+            stat2: 5,
+            stat3: 5,
+            image_uri: "ipfs://wheels_uri".to_string(),
+            car_id: 0,
+            rarity: Rarity::Common,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("car_contract", &[]), mint_msg).unwrap();
+
+        let royalty_info = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, token_id: &str| {
+            let msg = QueryMsg::RoyaltyInfo { token_id: token_id.to_string(), sale_price: Uint128::new(999) };
+            query(deps.as_ref(), mock_env(), msg).and_then(|res| from_json::<RoyaltiesInfoResponse>(&res))
+        };
+        let check_royalties = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::CheckRoyalties {}).unwrap();
+            from_json::<CheckRoyaltiesResponse>(&res).unwrap().royalty_payments
+        };
+        assert!(!check_royalties(&deps));
+        assert_eq!(Uint128::zero(), royalty_info(&deps, "0").unwrap().royalty_amount);
+
+        let set_royalties = |percent| ExecuteMsg::SetRoyalties { payment_address: "studio".to_string(), percent };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("owner1", &[]), set_royalties(10)).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), set_royalties(26)).is_err());
+        let _res = execute(deps.as_mut(), env, mock_info("creator", &[]), set_royalties(10)).unwrap();
+
+        // El monto se redondea hacia abajo
+        assert!(check_royalties(&deps));
+        assert_eq!(
+            RoyaltiesInfoResponse { address: "studio".to_string(), royalty_amount: Uint128::new(99) },
+            royalty_info(&deps, "0").unwrap()
+        );
+        assert!(royalty_info(&deps, "1").is_err());
+    }
+    #[test]
+    fn test_rarity_bounds() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        // Inicializar el contrato; "creator" queda como admin
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Solo el admin puede configurar los límites
+        let set_bounds_msg = ExecuteMsg::SetRarityBounds {
+            rarity: Rarity::Legendary,
+            min: 7,
+            max: 10,
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("unauthorized", &[]), set_bounds_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), set_bounds_msg).unwrap();
+
+        // Límites fuera de rango
+        let invalid_msg = ExecuteMsg::SetRarityBounds {
+            rarity: Rarity::Epic,
+            min: 8,
+            max: 5,
+        };
+        assert!(execute(deps.as_mut(), env.clone(), info, invalid_msg).is_err());
+
+        // Una parte legendaria con stats por debajo del mínimo no se puede mintear
+        let car_contract_info = mock_info("car_contract", &[]);
+        let mint_msg = ExecuteMsg::Mint {
+            to: "owner".to_string(),
+            part_type: PartType::Engine,
+            stat1: 9,
+            stat2: 5,
+            stat3: 8,
+            image_uri: "ipfs://engine_uri".to_string(),
+            car_id: 0,
+            rarity: Rarity::Legendary,
+        };
+        let err = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap_err();
+        assert!(err.to_string().contains("entre 7 y 10"));
+
+        // Mintear una parte legendaria válida y una común
+        let mint_msg = ExecuteMsg::Mint {
+            to: "owner".to_string(),
+            part_type: PartType::Engine,
+            stat1: 9,
+            stat2: 7,
+            stat3: 8,
+            image_uri: "ipfs://engine_uri".to_string(),
+            car_id: 0,
+            rarity: Rarity::Legendary,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
+        let mint_msg = ExecuteMsg::Mint {
+            to: "owner".to_string(),
+            part_type: PartType::Wheels,
+            stat1: 2,
+            stat2: 3,
+            stat3: 1,
+            image_uri: "ipfs://wheels_uri".to_string(),
+            car_id: 0,
+            rarity: Rarity::Common,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info, mint_msg).unwrap();
+
+        // Filtrar las partes del propietario por rareza
+        let query_msg = QueryMsg::GetOwnerPartsByRarity {
+            owner: "owner".to_string(),
+            rarity: Rarity::Legendary,
+        };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let legendary_parts: Vec<u64> = from_json(&res).unwrap();
+        assert_eq!(vec![0], legendary_parts);
+
+        // Las rarezas sin configurar usan el rango completo
+        let query_msg = QueryMsg::GetRarityBounds { rarity: Rarity::Rare };
+        let res = query(deps.as_ref(), env, query_msg).unwrap();
+        let bounds: StatBounds = from_json(&res).unwrap();
+        assert_eq!(StatBounds { min: 0, max: MAX_STAT }, bounds);
+    }
+
+    #[test]
+    fn test_buy_and_open_pack() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let admin_info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), admin_info.clone(), msg).unwrap();
+
+        // Solo el admin configura los packs
+        let set_pack_msg = ExecuteMsg::SetPack {
+            pack_id: "starter".to_string(),
+            price: Uint128::new(50),
+            size: 3,
+            drop_table: vec![
+                DropEntry {
+                    part_type: PartType::Engine,
+                    rarity: Rarity::Common,
+                    weight: 3,
+                    image_uri: "ipfs://engine_uri".to_string(),
+                },
+                DropEntry {
+                    part_type: PartType::Wheels,
+                    rarity: Rarity::Rare,
+                    weight: 1,
+                    image_uri: "ipfs://wheels_uri".to_string(),
+                },
+            ],
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("unauthorized", &[]), set_pack_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+        let _res = execute(deps.as_mut(), env.clone(), admin_info, set_pack_msg).unwrap();
+
+        let query_msg = QueryMsg::GetPacks {};
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let packs: PacksResponse = from_json(&res).unwrap();
+        assert_eq!(1, packs.packs.len());
+        assert_eq!(3, packs.packs[0].pack.size);
+
+        // Sin servidor de juego no se venden packs
+        let secret = "pack-secret".to_string();
+        let commitment: String = Sha256::digest(secret.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+        let buy_msg = ExecuteMsg::BuyPack {
+            pack_id: "starter".to_string(),
+            commitment,
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("buyer", &coins(50, "uxion")), buy_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("servidor de juego no está configurado"));
+        let set_server = ExecuteMsg::SetGameServer { address: "game_server".to_string() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("buyer", &[]), set_server.clone()).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), set_server).unwrap();
+
+        // El pack se paga exacto: ni de menos, ni de más, ni con otra moneda
+        let err = execute(deps.as_mut(), env.clone(), mock_info("buyer", &coins(10, "uxion")), buy_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("cuesta exactamente 50uxion"));
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("buyer", &coins(60, "uxion")), buy_msg.clone()).is_err());
+        let mixed = [Coin::new(50, "uxion"), Coin::new(5, "uatom")];
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("buyer", &mixed), buy_msg.clone()).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("buyer", &coins(50, "uxion")), buy_msg).unwrap();
+
+        // No se puede abrir en el mismo bloque, ni antes de la semilla del servidor, ni con otro secreto
+        let open_msg = ExecuteMsg::OpenPack { secret: secret.clone() };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("buyer", &[]), open_msg.clone()).is_err());
+        env.block.height += 1;
+        let err = execute(deps.as_mut(), env.clone(), mock_info("buyer", &[]), open_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("todavía no publicó su semilla"));
+
+        // Solo el servidor de juego publica la semilla, y una sola vez
+        let seed_msg = ExecuteMsg::SubmitPackSeed { owner: "buyer".to_string(), seed: "semilla".to_string() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("buyer", &[]), seed_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("Solo el servidor de juego"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("game_server", &[]), seed_msg.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("game_server", &[]), seed_msg).unwrap_err();
+        assert!(err.to_string().contains("ya publicó su semilla"));
+
+        let wrong_msg = ExecuteMsg::OpenPack { secret: "otro".to_string() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("buyer", &[]), wrong_msg).unwrap_err();
+        assert!(err.to_string().contains("no corresponde"));
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("buyer", &[]), open_msg).unwrap();
+        assert_eq!("open_pack", res.attributes[0].value);
+
+        // Las partes llegan al comprador sin equipar y salen de la tabla de drops
+        let query_msg = QueryMsg::GetOwnerUnequippedParts { owner: "buyer".to_string() };
+        let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let unequipped: Vec<u64> = from_json(&res).unwrap();
+        assert_eq!(vec![0, 1, 2], unequipped);
+        for part_id in unequipped {
+            let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPartStats { part_id }).unwrap();
+            let stats: PartStats = from_json(&res).unwrap();
+            match stats.part_type {
+                PartType::Engine => assert_eq!(Rarity::Common, stats.rarity),
+                PartType::Wheels => assert_eq!(Rarity::Rare, stats.rarity),
+                other => panic!("{} no está en la tabla de drops", other),
+            }
+        }
+
+        // El pack pendiente ya se consumió
+        let query_msg = QueryMsg::GetPendingPack { owner: "buyer".to_string() };
+        let res = query(deps.as_ref(), env, query_msg).unwrap();
+        let pending: Option<PendingPack> = from_json(&res).unwrap();
+        assert!(pending.is_none());
+    }
+
+    #[test]
+    fn test_open_height_does_not_change_parts() {
+        // Comprar y abrir el mismo pack con el mismo secreto y la misma semilla del servidor
+        let open_at = |open_height: u64| -> Vec<PartStats> {
+            let mut deps = mock_dependencies();
+            let mut env = mock_env();
+            let msg = InstantiateMsg {
+                car_contract: "car_contract".to_string(),
+            };
+            let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+            let set_pack_msg = ExecuteMsg::SetPack {
+                pack_id: "starter".to_string(),
+                price: Uint128::new(50),
+                size: 3,
+                drop_table: vec![
+                    DropEntry {
+                        part_type: PartType::Engine,
+                        rarity: Rarity::Common,
+                        weight: 1,
+                        image_uri: "ipfs://engine_uri".to_string(),
+                    },
+                    DropEntry {
+                        part_type: PartType::Wheels,
+                        rarity: Rarity::Epic,
+                        weight: 1,
+                        image_uri: "ipfs://wheels_uri".to_string(),
+                    },
+                ],
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), set_pack_msg).unwrap();
+            let set_server = ExecuteMsg::SetGameServer { address: "game_server".to_string() };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), set_server).unwrap();
+
+            let buy_msg = ExecuteMsg::BuyPack {
+                pack_id: "starter".to_string(),
+                commitment: randomness::commitment_of("pack-secret"),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("buyer", &coins(50, "uxion")), buy_msg).unwrap();
+            let seed_msg = ExecuteMsg::SubmitPackSeed { owner: "buyer".to_string(), seed: "semilla".to_string() };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("game_server", &[]), seed_msg).unwrap();
+
+            env.block.height += open_height;
+            env.block.time = env.block.time.plus_seconds(open_height * 5);
+            let open_msg = ExecuteMsg::OpenPack { secret: "pack-secret".to_string() };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("buyer", &[]), open_msg).unwrap();
+            (0..3)
+                .map(|part_id| from_json(query(deps.as_ref(), env.clone(), QueryMsg::GetPartStats { part_id }).unwrap()).unwrap())
+                .collect()
+        };
+
+        // Abrir en otro bloque del plazo da exactamente las mismas partes
+        assert_eq!(open_at(1), open_at(PACK_REVEAL_WINDOW));
+    }
+
+    #[test]
+    fn test_burn_parts() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let admin_info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), admin_info.clone(), msg).unwrap();
+
+        // Una parte suelta (0) y dos equipadas en el carro 1 (1 y 2)
+        let car_contract_info = mock_info("car_contract", &[]);
+        for (part_type, car_id) in [(PartType::Engine, 0), (PartType::Transmission, 1), (PartType::Wheels, 1)] {
+            let mint_msg = ExecuteMsg::Mint {
+                to: "owner".to_string(),
+                part_type,
+                stat1: 5,
+                stat2: 5,
+                stat3: 5,
+                image_uri: "ipfs://uri".to_string(),
+                car_id,
+                rarity: Rarity::Common,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
+        }
+
+        // Configurar el rescate; sin fondos en el contrato no se puede pagar
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::SetSalvageValue { amount: Uint128::new(20) }).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+        let _res = execute(deps.as_mut(), env.clone(), admin_info, ExecuteMsg::SetSalvageValue { amount: Uint128::new(20) }).unwrap();
+        let burn_msg = ExecuteMsg::BurnPart { part_id: 0 };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), burn_msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("fondos"));
+        deps.querier.update_balance(env.contract.address.clone(), coins(100, "uxion"));
+
+        // Solo el propietario quema, y no puede quemar partes equipadas
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("other", &[]), burn_msg.clone()).is_err());
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::BurnPart { part_id: 1 }).unwrap_err();
+        assert!(err.to_string().contains("equipada"));
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), burn_msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send { to_address: "owner".to_string(), amount: coins(20, "uxion") }),
+            res.messages[0].msg
+        );
+        assert!(query(deps.as_ref(), env.clone(), QueryMsg::GetPartStats { part_id: 0 }).is_err());
+
+        // Las partes equipadas solo las quema el contrato de carros, junto con el carro
+        let burn_car_parts_msg = ExecuteMsg::BurnCarParts {
+            owner: "owner".to_string(),
+            car_id: 1,
+            part_ids: vec![1, 2],
+        };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), burn_car_parts_msg.clone()).is_err());
+        let res = execute(deps.as_mut(), env.clone(), car_contract_info, burn_car_parts_msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send { to_address: "owner".to_string(), amount: coins(40, "uxion") }),
+            res.messages[0].msg
+        );
+
+        // No queda rastro de las partes en ningún índice
+        let queries = [
+            QueryMsg::GetOwnerParts { owner: "owner".to_string() },
+            QueryMsg::GetOwnerEquippedParts { owner: "owner".to_string() },
+            QueryMsg::GetOwnerUnequippedParts { owner: "owner".to_string() },
+            QueryMsg::GetOwnerPartsByType { owner: "owner".to_string(), part_type: PartType::Wheels },
+        ];
+        for query_msg in queries {
+            let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+            let parts: Vec<u64> = from_json(&res).unwrap();
+            assert!(parts.is_empty());
+        }
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::IsEquipped { part_id: 1 }).unwrap();
+        let equipped: bool = from_json(&res).unwrap();
+        assert!(!equipped);
+
+        // Un rescate que desborda al multiplicarlo por las partes quemadas es un error, no un pánico
+        let contract = CarPartContract::default();
+        contract.salvage_value.save(&mut deps.storage, &Uint128::MAX).unwrap();
+        let err = salvage_payout(deps.as_ref(), &env, &contract, "owner", 2).unwrap_err();
+        assert!(err.to_string().contains("máximo representable"));
+    }
+
+    #[test]
+    fn test_fuse_parts() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let admin_info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), admin_info.clone(), msg).unwrap();
+
+        // Tres motores sueltos (0, 1, 2), un motor equipado (3) y unas ruedas sueltas (4)
+        let car_contract_info = mock_info("car_contract", &[]);
+        let parts = [
+            (PartType::Engine, 4, Rarity::Common, 0),
+            (PartType::Engine, 6, Rarity::Rare, 0),
+            (PartType::Engine, 9, Rarity::Common, 0),
+            (PartType::Engine, 5, Rarity::Common, 1),
+            (PartType::Wheels, 5, Rarity::Common, 0),
+        ];
+        for (part_type, stat, rarity, car_id) in parts {
+            let mint_msg = ExecuteMsg::Mint {
+                to: "owner".to_string(),
+                part_type,
+                stat1: stat,
+                stat2: stat,
+                stat3: 10,
+                image_uri: "ipfs://uri".to_string(),
+                car_id,
+                rarity,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
+        }
+
+        // Solo el admin cambia las reglas
+        let rules_msg = ExecuteMsg::SetFusionRules { inputs_required: 3, bonus: 2, rarity_upgrade: false };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), rules_msg.clone()).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), admin_info, rules_msg).unwrap();
+
+        // Cantidad incorrecta, partes repetidas, equipadas o de otro tipo
+        let owner_info = mock_info("owner", &[]);
+        let invalid = [vec![0, 1], vec![0, 0, 1], vec![0, 1, 3], vec![0, 1, 4]];
+        for part_ids in invalid {
+            assert!(execute(deps.as_mut(), env.clone(), owner_info.clone(), ExecuteMsg::FusePart { part_ids }).is_err());
+        }
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("other", &[]), ExecuteMsg::FusePart { part_ids: vec![0, 1, 2] }).is_err());
+
+        let res = execute(deps.as_mut(), env.clone(), owner_info, ExecuteMsg::FusePart { part_ids: vec![0, 1, 2] }).unwrap();
+        let lineage = res.events.iter().find(|e| e.ty == "part_fusion").unwrap();
+        assert!(lineage.attributes.iter().any(|a| a.key == "inputs" && a.value == "0,1,2"));
+        assert!(lineage.attributes.iter().any(|a| a.key == "output" && a.value == "5"));
+
+        // Promedio (19 / 3 = 6) + bonus 2, limitado al máximo de la rareza mayor (Rare)
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPartStats { part_id: 5 }).unwrap();
+        let fused: PartStats = from_json(&res).unwrap();
+        assert_eq!(PartType::Engine, fused.part_type);
+        assert_eq!(Rarity::Rare, fused.rarity);
+        assert_eq!((8, 8, 10), (fused.stat1, fused.stat2, fused.stat3));
+
+        // Las entradas se quemaron y la nueva parte llega sin equipar
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetOwnerPartsByType { owner: "owner".to_string(), part_type: PartType::Engine }).unwrap();
+        let engines: Vec<u64> = from_json(&res).unwrap();
+        assert_eq!(vec![3, 5], engines);
+        let res = query(deps.as_ref(), env, QueryMsg::GetOwnerUnequippedParts { owner: "owner".to_string() }).unwrap();
+        let unequipped: Vec<u64> = from_json(&res).unwrap();
+        assert_eq!(vec![4, 5], unequipped);
+    }
+
+    #[test]
+    fn test_tune_part() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let admin_info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), admin_info.clone(), msg).unwrap();
+
+        let mint_msg = ExecuteMsg::Mint {
+            to: "owner".to_string(),
+            part_type: PartType::Engine,
+            stat1: 7,
+            stat2: 9,
+            stat3: 5,
+            image_uri: "ipfs://engine_uri".to_string(),
+            car_id: 0,
+            rarity: Rarity::Common,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("car_contract", &[]), mint_msg).unwrap();
+
+        // Cada mejora cuesta 100 + 50 por nivel; máximo 2 mejoras por stat
+        let config_msg = ExecuteMsg::SetTuningConfig {
+            base_fee: Uint128::new(100),
+            fee_step: Uint128::new(50),
+            max_upgrades_per_stat: 2,
+        };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), config_msg.clone()).is_err());
+
+        // Las mejoras por stat van de 1 a MAX_STAT y la tarifa máxima no puede desbordar
+        let invalid_configs = [
+            (Uint128::new(100), Uint128::new(50), 0),
+            (Uint128::new(100), Uint128::new(50), MAX_STAT + 1),
+            (Uint128::MAX, Uint128::new(1), 2),
+            (Uint128::new(100), Uint128::MAX, 2),
+        ];
+        for (base_fee, fee_step, max_upgrades_per_stat) in invalid_configs {
+            let invalid = ExecuteMsg::SetTuningConfig { base_fee, fee_step, max_upgrades_per_stat };
+            assert!(execute(deps.as_mut(), env.clone(), admin_info.clone(), invalid).is_err());
+        }
+        let _res = execute(deps.as_mut(), env.clone(), admin_info, config_msg).unwrap();
+
+        let tune = |stat: &str| ExecuteMsg::TunePart { part_id: 0, stat: stat.to_string() };
+
+        // Stat inexistente para un motor, parte ajena y pago distinto de la tarifa
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("owner", &coins(100, "uxion")), tune("handling")).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("other", &coins(100, "uxion")), tune("speed")).is_err());
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &coins(99, "uxion")), tune("speed")).unwrap_err();
+        assert!(err.to_string().contains("El tuning cuesta exactamente 100uxion"));
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("owner", &coins(101, "uxion")), tune("speed")).is_err());
+
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &coins(100, "uxion")), tune("speed")).unwrap();
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetTuningFee { part_id: 0 }).unwrap();
+        let fee: Uint128 = from_json(&res).unwrap();
+        assert_eq!(Uint128::new(150), fee);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &coins(150, "uxion")), tune("speed")).unwrap();
+
+        // Límite de mejoras por stat y tope de la rareza
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &coins(200, "uxion")), tune("speed")).unwrap_err();
+        assert!(err.to_string().contains("máximo de 2 mejoras"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &coins(200, "uxion")), tune("max_speed")).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &coins(250, "uxion")), tune("max_speed")).unwrap_err();
+        assert!(err.to_string().contains("ya está en el máximo"));
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPartStats { part_id: 0 }).unwrap();
+        let stats: PartStats = from_json(&res).unwrap();
+        assert_eq!((9, 10, 5), (stats.stat1, stats.stat2, stats.stat3));
+
+        let res = query(deps.as_ref(), env, QueryMsg::GetPartUpgrades { part_id: 0 }).unwrap();
+        let upgrades: PartUpgrades = from_json(&res).unwrap();
+        assert_eq!(3, upgrades.upgrade_level);
+        assert_eq!([2, 1, 0], upgrades.stat_upgrades);
+        assert_eq!(
+            vec![("speed", 7, 8, 100), ("speed", 8, 9, 150), ("max_speed", 9, 10, 200)],
+            upgrades.history.iter().map(|u| (u.stat.as_str(), u.from, u.to, u.fee.u128())).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_part_durability() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let car_contract_info = mock_info("car_contract", &[]);
+        for part_type in [PartType::Engine, PartType::Transmission, PartType::Wheels] {
+            let mint_msg = ExecuteMsg::Mint {
+                to: "owner".to_string(),
+                part_type,
+                stat1: 5,
+                stat2: 5,
+                stat3: 5,
+                image_uri: "ipfs://uri".to_string(),
+                car_id: 1,
+                rarity: Rarity::Common,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
+        }
+
+        // Solo el contrato de carros degrada y repara
+        let degrade_msg = ExecuteMsg::DegradeParts { part_ids: vec![0, 1, 2], wear: 10 };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), degrade_msg.clone()).is_err());
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), degrade_msg).unwrap();
+
+        // El desgaste depende de la función de la parte
+        let durability = |deps: Deps, part_id| -> u8 {
+            from_json(query(deps, mock_env(), QueryMsg::GetPartDurability { part_id }).unwrap()).unwrap()
+        };
+        assert_eq!(
+            (90, 94, 86),
+            (durability(deps.as_ref(), 0), durability(deps.as_ref(), 1), durability(deps.as_ref(), 2))
+        );
+
+        // La durabilidad acompaña a la parte aunque se desequipe
+        let unequip_msg = ExecuteMsg::SetEquippedState { part_id: 0, car_id: 0 };
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), unequip_msg).unwrap();
+        assert_eq!(90, durability(deps.as_ref(), 0));
+
+        let repair_msg = ExecuteMsg::RepairParts { part_ids: vec![2] };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), repair_msg.clone()).is_err());
+        let _res = execute(deps.as_mut(), env, car_contract_info, repair_msg).unwrap();
+        assert_eq!(MAX_DURABILITY, durability(deps.as_ref(), 2));
+        assert_eq!(94, durability(deps.as_ref(), 1));
+    }
+
+    #[test]
+    fn test_nft_info() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let mint_msg = ExecuteMsg::Mint {
+            to: "owner".to_string(),
+            part_type: PartType::Wheels,
+            stat1: 4,
+            stat2: 6,
+            stat3: 8,
+            image_uri: "ipfs://wheels".to_string(),
+            car_id: 3,
+            rarity: Rarity::Epic,
+        };
+        let car_contract_info = mock_info("car_contract", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info.clone(), mint_msg).unwrap();
+        let degrade_msg = ExecuteMsg::DegradeParts { part_ids: vec![0], wear: 10 };
+        let _res = execute(deps.as_mut(), env.clone(), car_contract_info, degrade_msg).unwrap();
+
+        assert!(query(deps.as_ref(), env.clone(), QueryMsg::NftInfo { token_id: "rueda".to_string(), resolve_ipfs: false }).is_err());
+
+        // La metadata refleja el estado actual de la parte
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::NftInfo { token_id: "0".to_string(), resolve_ipfs: false }).unwrap();
+        let info: NftInfoResponse = from_json(&res).unwrap();
+        assert_eq!("Wheels #0", info.extension.name);
+        assert_eq!("ipfs://wheels", info.extension.image);
+        let attributes: Vec<(&str, &str)> = info.extension.attributes
+            .iter()
+            .map(|a| (a.trait_type.as_str(), a.value.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("part_type", "Wheels"),
+                ("handling", "4"),
+                ("drift_factor", "6"),
+                ("turn_factor", "8"),
+                ("rarity", "Epic"),
+                ("durability", "86"),
+                ("upgrade_level", "0"),
+                ("equipped_car", "3"),
+            ],
+            attributes
+        );
+
+        // Sin gateway la URI se devuelve tal cual; con gateway el CID se resuelve por https
+        let nft_info = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::NftInfo { token_id: "0".to_string(), resolve_ipfs: true }).unwrap();
+            from_json::<NftInfoResponse>(&res).unwrap().extension.image
+        };
+        assert_eq!("ipfs://wheels", nft_info(&deps));
+        let set_gateway = |gateway: Option<&str>| ExecuteMsg::SetImageGateway { gateway: gateway.map(str::to_string) };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("other", &[]), set_gateway(Some("https://gw.io/ipfs/"))).is_err());
+        let err = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), set_gateway(Some("http://gw.io/ipfs/"))).unwrap_err();
+        assert!(err.to_string().contains("Esquema de URI no permitido"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), set_gateway(Some("https://gw.io/ipfs/"))).unwrap();
+        assert_eq!("https://gw.io/ipfs/wheels", nft_info(&deps));
+        let _res = execute(deps.as_mut(), env, mock_info("creator", &[]), set_gateway(None)).unwrap();
+        assert_eq!("ipfs://wheels", nft_info(&deps));
+    }
+
+    #[test]
+    fn test_migrate_admin() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Un contrato desplegado antes de que existiera el admin
+        let contract = CarPartContract::default();
+        contract.admin.remove(&mut deps.storage);
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }).unwrap_err();
+        assert!(err.to_string().contains("El contrato no tiene admin"));
+
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: Some("creator".to_string()) }).unwrap();
+        assert_eq!(Addr::unchecked("creator"), contract.admin.load(&deps.storage).unwrap());
+        let set_salvage = ExecuteMsg::SetSalvageValue { amount: Uint128::new(5) };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_salvage).unwrap();
+
+        // Migraciones posteriores conservan el admin guardado
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }).unwrap();
+        assert_eq!(Addr::unchecked("creator"), contract.admin.load(&deps.storage).unwrap());
+
+        // Las partes anteriores al índice de dueños lo reciben al migrar
+        contract.owner_parts.save(&mut deps.storage, "owner".to_string(), &vec![7, 9]).unwrap();
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }).unwrap();
+        assert_eq!("owner", contract.part_owners.load(&deps.storage, 9).unwrap());
+    }
+}
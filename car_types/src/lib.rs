@@ -0,0 +1,234 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[cfg(feature = "randomness")]
+pub mod randomness;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum PartType {
+    Engine,
+    Transmission,
+    Wheels,
+    Body,
+    Tires,
+    Turbo,
+    Nitro,
+    Spoiler,
+}
+
+impl fmt::Display for PartType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PartType::Engine => write!(f, "Engine"),
+            PartType::Transmission => write!(f, "Transmission"),
+            PartType::Wheels => write!(f, "Wheels"),
+            PartType::Body => write!(f, "Body"),
+            PartType::Tires => write!(f, "Tires"),
+            PartType::Turbo => write!(f, "Turbo"),
+            PartType::Nitro => write!(f, "Nitro"),
+            PartType::Spoiler => write!(f, "Spoiler"),
+        }
+    }
+}
+
+impl PartType {
+    // Nombre de stat1, stat2 y stat3 según el tipo de parte
+    pub fn stat_names(&self) -> [&'static str; 3] {
+        match self {
+            PartType::Engine => ["speed", "max_speed", "acceleration"],
+            PartType::Transmission => ["acceleration", "speed", "handling"],
+            PartType::Wheels => ["handling", "drift_factor", "turn_factor"],
+            PartType::Body => ["handling", "max_speed", "turn_factor"],
+            PartType::Tires => ["handling", "acceleration", "drift_factor"],
+            PartType::Turbo => ["acceleration", "max_speed", "speed"],
+            PartType::Nitro => ["speed", "acceleration", "max_speed"],
+            PartType::Spoiler => ["handling", "turn_factor", "max_speed"],
+        }
+    }
+
+    // Porcentaje del desgaste de una carrera que sufre la parte según su función
+    pub fn wear_factor(&self) -> u8 {
+        match self {
+            PartType::Engine => 100,
+            PartType::Transmission => 60,
+            PartType::Wheels => 140,
+            PartType::Body => 40,
+            PartType::Tires => 160,
+            PartType::Turbo => 120,
+            PartType::Nitro => 150,
+            PartType::Spoiler => 50,
+        }
+    }
+}
+
+// Durabilidad de una parte nueva o recién reparada
+pub const MAX_DURABILITY: u8 = 100;
+
+// Rareza de una parte. Cada nivel tiene sus propios límites de stats, configurados por el admin
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl fmt::Display for Rarity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rarity::Common => write!(f, "Common"),
+            Rarity::Rare => write!(f, "Rare"),
+            Rarity::Epic => write!(f, "Epic"),
+            Rarity::Legendary => write!(f, "Legendary"),
+        }
+    }
+}
+
+impl Rarity {
+    // Orden de la rareza, de Common (0) a Legendary (3)
+    pub fn rank(&self) -> u8 {
+        match self {
+            Rarity::Common => 0,
+            Rarity::Rare => 1,
+            Rarity::Epic => 2,
+            Rarity::Legendary => 3,
+        }
+    }
+}
+
+// Valor máximo absoluto de un stat, sea cual sea la rareza
+pub const MAX_STAT: u8 = 10;
+
+// Límites (inclusivos) de los stats de una parte según su rareza
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatBounds {
+    pub min: u8,
+    pub max: u8,
+}
+
+impl Default for StatBounds {
+    fn default() -> Self {
+        Self { min: 0, max: MAX_STAT }
+    }
+}
+
+impl StatBounds {
+    pub fn contains(&self, stat: u8) -> bool {
+        stat >= self.min && stat <= self.max
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PartStats {
+    pub part_type: PartType,
+    pub stat1: u8,
+    pub stat2: u8,
+    pub stat3: u8,
+    pub image_uri: String,
+    #[serde(default)]
+    pub rarity: Rarity,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PartData {
+    pub part_type: PartType,
+    pub stat1: u8,
+    pub stat2: u8,
+    pub stat3: u8,
+    pub image_uri: String,
+    #[serde(default)]
+    pub rarity: Rarity,
+} 
+// Atributo de la metadata CW721 (formato de cw721-metadata-onchain)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Trait {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_type: Option<String>,
+    pub trait_type: String,
+    pub value: String,
+}
+
+impl Trait {
+    pub fn text(trait_type: &str, value: impl fmt::Display) -> Self {
+        Self {
+            display_type: None,
+            trait_type: trait_type.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    pub fn number(trait_type: &str, value: impl fmt::Display) -> Self {
+        Self {
+            display_type: Some("number".to_string()),
+            trait_type: trait_type.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+// Metadata on-chain de un token, calculada en cada consulta a partir del estado actual
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Metadata {
+    pub name: String,
+    pub description: String,
+    pub image: String,
+    pub attributes: Vec<Trait>,
+}
+
+// Respuesta de la consulta `nft_info` de CW721
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftInfoResponse {
+    pub token_uri: Option<String>,
+    pub extension: Metadata,
+}
+
+// Respuesta de `get_part_owner` del contrato de partes: dueño y carro en que está equipada (0 si está suelta)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PartOwnerResponse {
+    pub owner: String,
+    pub equipped_in_car: u64,
+}
+
+// Longitud máxima de una URI de imagen y esquemas admitidos
+pub const MAX_URI_LENGTH: usize = 256;
+pub const ALLOWED_URI_SCHEMES: [&str; 2] = ["ipfs://", "https://"];
+
+// Normalizar y validar una URI de imagen: quita espacios, colapsa `ipfs://ipfs/` y exige
+// un esquema permitido, contenido tras el esquema y la longitud máxima
+pub fn normalize_uri(uri: &str) -> Result<String, String> {
+    let uri = uri.trim();
+    let uri = match uri.strip_prefix("ipfs://ipfs/") {
+        Some(path) => format!("ipfs://{}", path),
+        None => uri.to_string(),
+    };
+    if uri.len() > MAX_URI_LENGTH {
+        return Err(format!("La URI supera el máximo de {} caracteres", MAX_URI_LENGTH));
+    }
+    let scheme = ALLOWED_URI_SCHEMES
+        .iter()
+        .find(|scheme| uri.starts_with(*scheme))
+        .ok_or_else(|| format!("Esquema de URI no permitido: {} (se admiten {})", uri, ALLOWED_URI_SCHEMES.join(", ")))?;
+    if uri.len() == scheme.len() || uri.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("URI inválida: {}", uri));
+    }
+    Ok(uri)
+}
+
+// Validar el gateway de imágenes: una URI https:// válida
+pub fn normalize_gateway(gateway: &str) -> Result<String, String> {
+    let gateway = normalize_uri(gateway)?;
+    if !gateway.starts_with("https://") {
+        return Err(format!("El gateway debe ser una URL https://: {}", gateway));
+    }
+    Ok(gateway)
+}
+
+// Resolver una URI `ipfs://` a través del gateway configurado; el resto se devuelve tal cual
+pub fn resolve_uri(uri: &str, gateway: Option<&str>) -> String {
+    match (uri.strip_prefix("ipfs://"), gateway) {
+        (Some(path), Some(gateway)) => format!("{}/{}", gateway.trim_end_matches('/'), path),
+        _ => uri.to_string(),
+    }
+}
@@ -0,0 +1,216 @@
+// Aleatoriedad verificable por commit-reveal, compartida por los contratos.
+// Cada participante envía sha256(secret) antes del plazo de commits y revela su secreto
+// antes del plazo de revelación; quien no revela queda señalado para que el contrato aplique
+// su penalización.
+//
+// Toda semilla sale de `derive_seed`: los secretos revelados más los datos de bloque fijados al
+// abrir la ronda (sus plazos de commit y de revelación). Nunca usa datos del bloque en que se
+// revela o se cierra, porque ese bloque lo elige quien envía la transacción y podría revertirla
+// hasta que el resultado le convenga. Las rondas de un solo jugador (minteos y packs) suman el
+// secreto del servidor de juego, publicado después del commit del jugador.
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// sha256(secret) en hexadecimal, tal como se envía en un commit
+pub fn commitment_of(secret: &str) -> String {
+    to_hex(&Sha256::digest(secret.as_bytes()))
+}
+
+// Validar y normalizar un commitment recibido
+pub fn parse_commitment(commitment: &str) -> Result<String, String> {
+    let commitment = commitment.to_lowercase();
+    if commitment.len() != 64 || !commitment.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("El commitment debe ser sha256(secret) en hexadecimal".to_string());
+    }
+    Ok(commitment)
+}
+
+pub fn check_reveal(commitment: &str, secret: &str) -> Result<(), String> {
+    if commitment_of(secret) != commitment {
+        return Err("El secreto no corresponde al commit".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Commit {
+    pub participant: String,
+    pub commitment: String,
+    pub secret: Option<String>, // None hasta que se revela
+}
+
+// Ronda de commit-reveal: commits hasta `commit_deadline` (inclusive) y revelaciones
+// en los bloques siguientes hasta `reveal_deadline` (inclusive)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RandomnessRound {
+    pub commits: Vec<Commit>,
+    pub commit_deadline: u64,
+    pub reveal_deadline: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundOutcome {
+    pub seed: Vec<u8>,
+    pub non_revealers: Vec<String>,
+}
+
+impl RandomnessRound {
+    pub fn new(commit_deadline: u64, reveal_deadline: u64) -> Result<Self, String> {
+        if reveal_deadline <= commit_deadline {
+            return Err("El plazo de revelación debe terminar después del plazo de commits".to_string());
+        }
+        Ok(Self {
+            commits: vec![],
+            commit_deadline,
+            reveal_deadline,
+        })
+    }
+
+    pub fn commit(&mut self, participant: &str, commitment: &str, height: u64) -> Result<(), String> {
+        if height > self.commit_deadline {
+            return Err("El plazo para enviar commits terminó".to_string());
+        }
+        if self.commits.iter().any(|commit| commit.participant == participant) {
+            return Err(format!("Ya existe un commit de {}", participant));
+        }
+        self.commits.push(Commit {
+            participant: participant.to_string(),
+            commitment: parse_commitment(commitment)?,
+            secret: None,
+        });
+        Ok(())
+    }
+
+    pub fn reveal(&mut self, participant: &str, secret: &str, height: u64) -> Result<(), String> {
+        if height <= self.commit_deadline {
+            return Err("Los secretos se revelan después del plazo de commits".to_string());
+        }
+        if height > self.reveal_deadline {
+            return Err("El plazo para revelar el secreto expiró".to_string());
+        }
+        let commit = self.commits
+            .iter_mut()
+            .find(|commit| commit.participant == participant)
+            .ok_or_else(|| format!("No hay un commit de {}", participant))?;
+        if commit.secret.is_some() {
+            return Err("El secreto ya fue revelado".to_string());
+        }
+        check_reveal(&commit.commitment, secret)?;
+        commit.secret = Some(secret.to_string());
+        Ok(())
+    }
+
+    pub fn all_revealed(&self) -> bool {
+        self.commits.iter().all(|commit| commit.secret.is_some())
+    }
+
+    // Cerrar la ronda: cuando todos revelaron o pasó el plazo de revelación.
+    // La semilla no depende de `height`, que solo valida el cierre.
+    pub fn finalize(&self, height: u64) -> Result<RoundOutcome, String> {
+        if height <= self.commit_deadline {
+            return Err("La ronda todavía acepta commits".to_string());
+        }
+        if !self.all_revealed() && height <= self.reveal_deadline {
+            return Err("La ronda sigue abierta a revelaciones".to_string());
+        }
+
+        let mut reveals = vec![];
+        let mut non_revealers = vec![];
+        for commit in &self.commits {
+            match &commit.secret {
+                Some(secret) => reveals.push((commit.participant.as_str(), secret.as_str())),
+                None => non_revealers.push(commit.participant.clone()),
+            }
+        }
+
+        Ok(RoundOutcome {
+            seed: derive_seed(&reveals, self.commit_deadline, self.reveal_deadline),
+            non_revealers,
+        })
+    }
+}
+
+// Semilla de una ronda: sha256 de cada (participante, secreto) revelado, en orden de commit,
+// más los plazos de commit y de revelación de la ronda
+pub fn derive_seed(reveals: &[(&str, &str)], commit_deadline: u64, reveal_deadline: u64) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    for (participant, secret) in reveals {
+        hasher.update(participant.as_bytes());
+        hasher.update(secret.as_bytes());
+    }
+    hasher.update(commit_deadline.to_be_bytes());
+    hasher.update(reveal_deadline.to_be_bytes());
+    hasher.finalize().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_with(participants: &[&str]) -> RandomnessRound {
+        let mut round = RandomnessRound::new(100, 110).unwrap();
+        for participant in participants {
+            round.commit(participant, &commitment_of(&format!("secreto-{}", participant)), 100).unwrap();
+        }
+        round
+    }
+
+    #[test]
+    fn test_commit_deadline() {
+        assert!(RandomnessRound::new(100, 100).is_err());
+        let mut round = RandomnessRound::new(100, 110).unwrap();
+        assert!(round.commit("a", "no-es-hex", 100).is_err());
+        round.commit("a", &commitment_of("x").to_uppercase(), 100).unwrap();
+        assert_eq!(commitment_of("x"), round.commits[0].commitment);
+        assert_eq!(Err("Ya existe un commit de a".to_string()), round.commit("a", &commitment_of("y"), 100));
+        assert_eq!(Err("El plazo para enviar commits terminó".to_string()), round.commit("b", &commitment_of("y"), 101));
+    }
+
+    #[test]
+    fn test_reveal_window() {
+        let mut round = round_with(&["a", "b", "c"]);
+        assert!(round.reveal("a", "secreto-a", 100).is_err());
+        assert!(round.reveal("a", "otro", 101).is_err());
+        assert!(round.reveal("z", "secreto-z", 101).is_err());
+        round.reveal("a", "secreto-a", 101).unwrap();
+        assert_eq!(Err("El secreto ya fue revelado".to_string()), round.reveal("a", "secreto-a", 102));
+        round.reveal("b", "secreto-b", 110).unwrap();
+        assert_eq!(Err("El plazo para revelar el secreto expiró".to_string()), round.reveal("c", "secreto-c", 111));
+    }
+
+    #[test]
+    fn test_finalize_and_non_revealers() {
+        let mut round = round_with(&["a", "b"]);
+        assert!(round.finalize(100).is_err());
+        round.reveal("a", "secreto-a", 105).unwrap();
+
+        // Mientras falte alguien la ronda espera al plazo de revelación
+        assert_eq!(Err("La ronda sigue abierta a revelaciones".to_string()), round.finalize(110));
+        let outcome = round.finalize(111).unwrap();
+        assert_eq!(vec!["b".to_string()], outcome.non_revealers);
+        assert_eq!(derive_seed(&[("a", "secreto-a")], 100, 110), outcome.seed);
+
+        // Con todos revelados se puede cerrar antes del plazo
+        round.commits[1].secret = Some("secreto-b".to_string());
+        assert!(round.finalize(106).unwrap().non_revealers.is_empty());
+    }
+
+    #[test]
+    fn test_seed_determinism() {
+        let mut round = round_with(&["a", "b"]);
+        round.reveal("a", "secreto-a", 101).unwrap();
+        round.reveal("b", "secreto-b", 102).unwrap();
+
+        // La altura de cierre no cambia la semilla; los secretos y los plazos sí
+        let seed = round.finalize(103).unwrap().seed;
+        assert_eq!(seed, round.finalize(500).unwrap().seed);
+        assert_eq!(seed, derive_seed(&[("a", "secreto-a"), ("b", "secreto-b")], 100, 110));
+        assert_ne!(seed, derive_seed(&[("b", "secreto-b"), ("a", "secreto-a")], 100, 110));
+        assert_ne!(seed, derive_seed(&[("a", "secreto-a"), ("b", "secreto-b")], 100, 111));
+    }
+}
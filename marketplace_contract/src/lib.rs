@@ -0,0 +1,796 @@
+use cosmwasm_std::{
+    entry_point, from_json, to_json_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Order, Response, StdError, StdResult, Timestamp, Uint128, WasmMsg, coins,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use cw_storage_plus::{Bound, Item, Map};
+
+// Denominación en la que se puja y se paga
+pub const DENOM: &str = "uxion";
+// Duración máxima de una subasta, en segundos (30 días)
+pub const MAX_AUCTION_DURATION: u64 = 30 * 24 * 60 * 60;
+// Extensión máxima anti-sniping, en segundos
+pub const MAX_AUCTION_EXTENSION: u64 = 60 * 60;
+pub const MAX_AUCTIONS_LIMIT: u32 = 30;
+
+// Lo que se subasta: un carro viaja con sus partes equipadas; una parte debe estar suelta
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Asset {
+    Car { car_id: u64 },
+    Part { part_id: u64 },
+}
+
+// Tiempos en segundos
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuctionConfig {
+    // Puja ascendente: la primera puja debe cubrir la reserva y cada puja nueva superar
+    // a la anterior por al menos min_increment. Una puja en los últimos `extension`
+    // segundos alarga el cierre para evitar el sniping.
+    English {
+        reserve_price: Uint128,
+        min_increment: Uint128,
+        duration: u64,
+        extension: u64,
+    },
+    // Precio descendente lineal de start_price a end_price durante `duration`; después
+    // se mantiene en end_price hasta que alguien compre o el vendedor cancele.
+    // Con start_price == end_price funciona como una venta a precio fijo.
+    Dutch {
+        start_price: Uint128,
+        end_price: Uint128,
+        duration: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Bid {
+    pub bidder: Addr,
+    pub amount: Uint128, // custodiado por el contrato hasta que lo superen o se liquide
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuctionStatus {
+    Active,
+    Sold,
+    Unsold, // inglesa cerrada sin pujas; el activo volvió al vendedor
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Auction {
+    pub id: u64,
+    pub seller: Addr,
+    pub asset: Asset,
+    pub config: AuctionConfig,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp, // en la inglesa se mueve con las extensiones anti-sniping
+    pub highest_bid: Option<Bid>,
+    pub status: AuctionStatus,
+}
+
+// Estado del contrato
+pub struct MarketplaceContract<'a> {
+    pub car_contract: Item<'a, Addr>,
+    pub part_contract: Item<'a, Addr>,
+    pub auctions: Map<'a, u64, Auction>,
+    pub current_auction_id: Item<'a, u64>,
+}
+
+impl<'a> Default for MarketplaceContract<'a> {
+    fn default() -> Self {
+        Self {
+            car_contract: Item::new("car_contract"),
+            part_contract: Item::new("part_contract"),
+            auctions: Map::new("auctions"),
+            current_auction_id: Item::new("current_auction_id"),
+        }
+    }
+}
+
+// Mensajes de instanciación
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub car_contract: String,
+    pub part_contract: String,
+}
+
+// Mensajes de ejecución
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    // Lo envían los contratos de carros y partes tras SendCar/SendPart
+    ReceiveNft(Cw721ReceiveMsg),
+    PlaceBid {
+        auction_id: u64,
+    },
+    Buy {
+        auction_id: u64,
+    },
+    SettleAuction {
+        auction_id: u64,
+    },
+    CancelAuction {
+        auction_id: u64,
+    },
+}
+
+// Aviso CW721 que acompaña al activo enviado al marketplace
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+// Contenido de Cw721ReceiveMsg.msg
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    CreateAuction {
+        config: AuctionConfig,
+    },
+}
+
+// Mensajes de consulta
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    GetAuction {
+        auction_id: u64,
+    },
+    GetAuctions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    GetCurrentPrice {
+        auction_id: u64,
+    },
+}
+
+// Mensajes de ejecución del contrato de carros
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CarExecuteMsg {
+    TransferCar {
+        car_id: u64,
+        recipient: String,
+    },
+}
+
+// Mensajes de ejecución del contrato de partes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PartExecuteMsg {
+    TransferPart {
+        from: String,
+        to: String,
+        part_id: u64,
+    },
+}
+
+// Consulta CW2981 a los contratos de carros y partes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RoyaltyQueryMsg {
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+// Entry points
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    let contract = MarketplaceContract::default();
+
+    let car_contract = deps.api.addr_validate(&msg.car_contract)?;
+    contract.car_contract.save(deps.storage, &car_contract)?;
+    let part_contract = deps.api.addr_validate(&msg.part_contract)?;
+    contract.part_contract.save(deps.storage, &part_contract)?;
+    contract.current_auction_id.save(deps.storage, &0)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", info.sender)
+        .add_attribute("car_contract", msg.car_contract)
+        .add_attribute("part_contract", msg.part_contract))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    let contract = MarketplaceContract::default();
+
+    match msg {
+        ExecuteMsg::ReceiveNft(receive_msg) => {
+            execute_receive_nft(deps, env, info, contract, receive_msg)
+        },
+        ExecuteMsg::PlaceBid { auction_id } => {
+            execute_place_bid(deps, env, info, contract, auction_id)
+        },
+        ExecuteMsg::Buy { auction_id } => {
+            execute_buy(deps, env, info, contract, auction_id)
+        },
+        ExecuteMsg::SettleAuction { auction_id } => {
+            execute_settle_auction(deps, env, contract, auction_id)
+        },
+        ExecuteMsg::CancelAuction { auction_id } => {
+            execute_cancel_auction(deps, env, info, contract, auction_id)
+        },
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(
+    deps: Deps,
+    env: Env,
+    msg: QueryMsg,
+) -> StdResult<Binary> {
+    let contract = MarketplaceContract::default();
+
+    match msg {
+        QueryMsg::GetAuction { auction_id } => {
+            to_json_binary(&contract.auctions.load(deps.storage, auction_id)?)
+        },
+        QueryMsg::GetAuctions { start_after, limit } => {
+            let limit = limit.unwrap_or(MAX_AUCTIONS_LIMIT).min(MAX_AUCTIONS_LIMIT) as usize;
+            let auctions = contract.auctions
+                .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, auction)| auction))
+                .collect::<StdResult<Vec<Auction>>>()?;
+            to_json_binary(&auctions)
+        },
+        QueryMsg::GetCurrentPrice { auction_id } => {
+            let auction = contract.auctions.load(deps.storage, auction_id)?;
+            to_json_binary(&current_price(&auction, env.block.time))
+        },
+    }
+}
+
+// Funciones auxiliares
+
+// Inglesa: la puja mínima aceptada ahora. Holandesa: el precio de compra en este momento.
+fn current_price(auction: &Auction, now: Timestamp) -> Uint128 {
+    match &auction.config {
+        AuctionConfig::English { reserve_price, min_increment, .. } => match &auction.highest_bid {
+            Some(bid) => bid.amount + min_increment,
+            None => *reserve_price,
+        },
+        AuctionConfig::Dutch { start_price, end_price, duration } => {
+            let elapsed = now.seconds().saturating_sub(auction.start_time.seconds()).min(*duration);
+            let decay = (*start_price - *end_price).multiply_ratio(elapsed, *duration);
+            *start_price - decay
+        },
+    }
+}
+
+fn validate_config(config: &AuctionConfig) -> StdResult<()> {
+    let duration = match config {
+        AuctionConfig::English { reserve_price, min_increment, duration, extension } => {
+            if reserve_price.is_zero() {
+                return Err(StdError::generic_err("El precio de reserva debe ser mayor a cero"));
+            }
+            if min_increment.is_zero() {
+                return Err(StdError::generic_err("El incremento mínimo debe ser mayor a cero"));
+            }
+            if *extension > MAX_AUCTION_EXTENSION {
+                return Err(StdError::generic_err(format!(
+                    "La extensión no puede superar {} segundos", MAX_AUCTION_EXTENSION
+                )));
+            }
+            duration
+        },
+        AuctionConfig::Dutch { start_price, end_price, duration } => {
+            if end_price.is_zero() || start_price < end_price {
+                return Err(StdError::generic_err(
+                    "El precio final debe ser mayor a cero y no superar al inicial"
+                ));
+            }
+            duration
+        },
+    };
+    if *duration == 0 || *duration > MAX_AUCTION_DURATION {
+        return Err(StdError::generic_err(format!(
+            "La duración debe estar entre 1 y {} segundos", MAX_AUCTION_DURATION
+        )));
+    }
+    Ok(())
+}
+
+// Solo se acepta uxion; cualquier otra moneda quedaría atrapada en el contrato
+fn paid_amount(info: &MessageInfo) -> StdResult<Uint128> {
+    if info.funds.iter().any(|coin| coin.denom != DENOM) {
+        return Err(StdError::generic_err(format!("Solo se acepta {}", DENOM)));
+    }
+    Ok(info.funds.iter().map(|coin| coin.amount).sum())
+}
+
+fn send_funds(to: &Addr, amount: Uint128) -> CosmosMsg {
+    BankMsg::Send {
+        to_address: to.to_string(),
+        amount: coins(amount.u128(), DENOM),
+    }
+    .into()
+}
+
+// Entregar el activo en custodia a su nuevo dueño (o devolverlo al vendedor)
+fn deliver_asset(deps: Deps, env: &Env, contract: &MarketplaceContract, asset: &Asset, to: &Addr) -> StdResult<CosmosMsg> {
+    let (contract_addr, msg) = match asset {
+        Asset::Car { car_id } => (
+            contract.car_contract.load(deps.storage)?,
+            to_json_binary(&CarExecuteMsg::TransferCar {
+                car_id: *car_id,
+                recipient: to.to_string(),
+            })?,
+        ),
+        Asset::Part { part_id } => (
+            contract.part_contract.load(deps.storage)?,
+            to_json_binary(&PartExecuteMsg::TransferPart {
+                from: env.contract.address.to_string(),
+                to: to.to_string(),
+                part_id: *part_id,
+            })?,
+        ),
+    };
+    Ok(WasmMsg::Execute {
+        contract_addr: contract_addr.to_string(),
+        msg,
+        funds: vec![],
+    }
+    .into())
+}
+
+// Cobros de una venta: la regalía CW2981 de la colección y el resto para el vendedor
+fn sale_payments(deps: Deps, contract: &MarketplaceContract, auction: &Auction, price: Uint128) -> StdResult<Vec<CosmosMsg>> {
+    let (token_contract, token_id) = match auction.asset {
+        Asset::Car { car_id } => (contract.car_contract.load(deps.storage)?, car_id),
+        Asset::Part { part_id } => (contract.part_contract.load(deps.storage)?, part_id),
+    };
+    let royalty: RoyaltiesInfoResponse = deps.querier.query_wasm_smart(
+        token_contract,
+        &RoyaltyQueryMsg::RoyaltyInfo { token_id: token_id.to_string(), sale_price: price },
+    )?;
+    let seller_amount = price.checked_sub(royalty.royalty_amount)
+        .map_err(|_| StdError::generic_err("La regalía supera el precio de venta"))?;
+
+    let mut payments = vec![];
+    if !royalty.royalty_amount.is_zero() {
+        let address = deps.api.addr_validate(&royalty.address)?;
+        payments.push(send_funds(&address, royalty.royalty_amount));
+    }
+    if !seller_amount.is_zero() {
+        payments.push(send_funds(&auction.seller, seller_amount));
+    }
+    Ok(payments)
+}
+
+fn load_active_auction(deps: Deps, contract: &MarketplaceContract, auction_id: u64) -> StdResult<Auction> {
+    let auction = contract.auctions.load(deps.storage, auction_id)?;
+    if auction.status != AuctionStatus::Active {
+        return Err(StdError::generic_err("La subasta no está activa"));
+    }
+    Ok(auction)
+}
+
+// Funciones de ejecución
+fn execute_receive_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: MarketplaceContract,
+    receive_msg: Cw721ReceiveMsg,
+) -> StdResult<Response> {
+    // El activo se identifica por el contrato que lo envía
+    let token_id: u64 = receive_msg.token_id.parse()
+        .map_err(|_| StdError::generic_err("ID de token inválido"))?;
+    let asset = if info.sender == contract.car_contract.load(deps.storage)? {
+        Asset::Car { car_id: token_id }
+    } else if info.sender == contract.part_contract.load(deps.storage)? {
+        Asset::Part { part_id: token_id }
+    } else {
+        return Err(StdError::generic_err("Solo se aceptan carros y partes del juego"));
+    };
+
+    let ReceiveMsg::CreateAuction { config } = from_json(&receive_msg.msg)?;
+    validate_config(&config)?;
+    let duration = match &config {
+        AuctionConfig::English { duration, .. } | AuctionConfig::Dutch { duration, .. } => *duration,
+    };
+
+    let auction_id = contract.current_auction_id.load(deps.storage)? + 1;
+    let auction = Auction {
+        id: auction_id,
+        seller: deps.api.addr_validate(&receive_msg.sender)?,
+        asset,
+        config,
+        start_time: env.block.time,
+        end_time: env.block.time.plus_seconds(duration),
+        highest_bid: None,
+        status: AuctionStatus::Active,
+    };
+    contract.auctions.save(deps.storage, auction_id, &auction)?;
+    contract.current_auction_id.save(deps.storage, &auction_id)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "create_auction")
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("seller", auction.seller))
+}
+
+fn execute_place_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: MarketplaceContract,
+    auction_id: u64,
+) -> StdResult<Response> {
+    let mut auction = load_active_auction(deps.as_ref(), &contract, auction_id)?;
+    let extension = match &auction.config {
+        AuctionConfig::English { extension, .. } => *extension,
+        AuctionConfig::Dutch { .. } => {
+            return Err(StdError::generic_err("En una subasta holandesa se compra con Buy"));
+        },
+    };
+    if env.block.time >= auction.end_time {
+        return Err(StdError::generic_err("La subasta ya terminó"));
+    }
+    if info.sender == auction.seller {
+        return Err(StdError::generic_err("El vendedor no puede pujar"));
+    }
+
+    // La puja queda en custodia del contrato
+    let amount = paid_amount(&info)?;
+    let min_bid = current_price(&auction, env.block.time);
+    if amount < min_bid {
+        return Err(StdError::generic_err(format!("La puja mínima es {}{}", min_bid, DENOM)));
+    }
+
+    // Devolver la puja superada
+    let mut response = Response::new();
+    if let Some(previous) = auction.highest_bid.take() {
+        response = response.add_message(send_funds(&previous.bidder, previous.amount));
+    }
+    auction.highest_bid = Some(Bid { bidder: info.sender.clone(), amount });
+
+    // Anti-sniping: una puja cerca del cierre lo aleja
+    let extended_end = env.block.time.plus_seconds(extension);
+    if extended_end > auction.end_time {
+        auction.end_time = extended_end;
+    }
+    contract.auctions.save(deps.storage, auction_id, &auction)?;
+
+    Ok(response
+        .add_attribute("method", "place_bid")
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("bidder", info.sender)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("end_time", auction.end_time.seconds().to_string()))
+}
+
+fn execute_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: MarketplaceContract,
+    auction_id: u64,
+) -> StdResult<Response> {
+    let mut auction = load_active_auction(deps.as_ref(), &contract, auction_id)?;
+    if let AuctionConfig::English { .. } = auction.config {
+        return Err(StdError::generic_err("En una subasta inglesa se compra pujando"));
+    }
+    if info.sender == auction.seller {
+        return Err(StdError::generic_err("El vendedor no puede comprar su propio activo"));
+    }
+
+    let price = current_price(&auction, env.block.time);
+    let paid = paid_amount(&info)?;
+    if paid < price {
+        return Err(StdError::generic_err(format!("El precio actual es {}{}", price, DENOM)));
+    }
+
+    auction.status = AuctionStatus::Sold;
+    auction.highest_bid = Some(Bid { bidder: info.sender.clone(), amount: price });
+    contract.auctions.save(deps.storage, auction_id, &auction)?;
+
+    // Lo pagado de más vuelve al comprador: el precio puede haber bajado desde que firmó
+    let mut response = Response::new()
+        .add_message(deliver_asset(deps.as_ref(), &env, &contract, &auction.asset, &info.sender)?)
+        .add_messages(sale_payments(deps.as_ref(), &contract, &auction, price)?);
+    if paid > price {
+        response = response.add_message(send_funds(&info.sender, paid - price));
+    }
+
+    Ok(response
+        .add_attribute("method", "buy")
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("buyer", info.sender)
+        .add_attribute("price", price.to_string()))
+}
+
+// Cualquiera puede liquidar una subasta inglesa terminada
+fn execute_settle_auction(
+    deps: DepsMut,
+    env: Env,
+    contract: MarketplaceContract,
+    auction_id: u64,
+) -> StdResult<Response> {
+    let mut auction = load_active_auction(deps.as_ref(), &contract, auction_id)?;
+    if let AuctionConfig::Dutch { .. } = auction.config {
+        return Err(StdError::generic_err("Las subastas holandesas no se liquidan"));
+    }
+    if env.block.time < auction.end_time {
+        return Err(StdError::generic_err("La subasta aún no termina"));
+    }
+
+    let mut response = Response::new();
+    match &auction.highest_bid {
+        Some(bid) => {
+            auction.status = AuctionStatus::Sold;
+            response = response
+                .add_message(deliver_asset(deps.as_ref(), &env, &contract, &auction.asset, &bid.bidder)?)
+                .add_messages(sale_payments(deps.as_ref(), &contract, &auction, bid.amount)?);
+        },
+        None => {
+            auction.status = AuctionStatus::Unsold;
+            response = response
+                .add_message(deliver_asset(deps.as_ref(), &env, &contract, &auction.asset, &auction.seller)?);
+        },
+    }
+    contract.auctions.save(deps.storage, auction_id, &auction)?;
+
+    Ok(response
+        .add_attribute("method", "settle_auction")
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("sold", (auction.status == AuctionStatus::Sold).to_string()))
+}
+
+// El vendedor recupera el activo: la inglesa solo sin pujas, la holandesa mientras no se venda
+fn execute_cancel_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: MarketplaceContract,
+    auction_id: u64,
+) -> StdResult<Response> {
+    let mut auction = load_active_auction(deps.as_ref(), &contract, auction_id)?;
+    if info.sender != auction.seller {
+        return Err(StdError::generic_err("Solo el vendedor puede cancelar la subasta"));
+    }
+    if auction.highest_bid.is_some() {
+        return Err(StdError::generic_err("No se puede cancelar una subasta con pujas"));
+    }
+
+    auction.status = AuctionStatus::Cancelled;
+    contract.auctions.save(deps.storage, auction_id, &auction)?;
+
+    Ok(Response::new()
+        .add_message(deliver_asset(deps.as_ref(), &env, &contract, &auction.asset, &auction.seller)?)
+        .add_attribute("method", "cancel_auction")
+        .add_attribute("auction_id", auction_id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+    use cosmwasm_std::{ContractResult, MemoryStorage, OwnedDeps, SubMsg, SystemResult, WasmQuery};
+
+    // Simula las regalías: 5% de los carros para "studio"; las partes no cobran
+    fn setup() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } => {
+                let RoyaltyQueryMsg::RoyaltyInfo { sale_price, .. } = from_json(msg).unwrap();
+                let response = match contract_addr.as_str() {
+                    "car_contract" => RoyaltiesInfoResponse {
+                        address: "studio".to_string(),
+                        royalty_amount: sale_price.multiply_ratio(5u128, 100u128),
+                    },
+                    _ => RoyaltiesInfoResponse { address: String::new(), royalty_amount: Uint128::zero() },
+                };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
+            },
+            _ => panic!("Consulta inesperada"),
+        });
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+            part_contract: "part_contract".to_string(),
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    // Lo que llega del contrato de carros o partes tras SendCar/SendPart
+    fn receive(token_contract: &str, seller: &str, token_id: u64, config: AuctionConfig) -> (MessageInfo, ExecuteMsg) {
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: seller.to_string(),
+            token_id: token_id.to_string(),
+            msg: to_json_binary(&ReceiveMsg::CreateAuction { config }).unwrap(),
+        });
+        (mock_info(token_contract, &[]), msg)
+    }
+
+    fn bank_send(to: &str, amount: u128) -> SubMsg {
+        SubMsg::new(BankMsg::Send { to_address: to.to_string(), amount: coins(amount, DENOM) })
+    }
+
+    fn transfer_car(car_id: u64, recipient: &str) -> SubMsg {
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: "car_contract".to_string(),
+            msg: to_json_binary(&CarExecuteMsg::TransferCar { car_id, recipient: recipient.to_string() }).unwrap(),
+            funds: vec![],
+        })
+    }
+
+    fn get_auction(deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>, auction_id: u64) -> Auction {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAuction { auction_id }).unwrap();
+        from_json(&res).unwrap()
+    }
+
+    #[test]
+    fn test_english_auction() {
+        let mut deps = setup();
+        let mut env = mock_env();
+        let config = AuctionConfig::English {
+            reserve_price: Uint128::new(1000),
+            min_increment: Uint128::new(100),
+            duration: 3600,
+            extension: 300,
+        };
+
+        // Solo los contratos del juego crean subastas y la configuración se valida
+        let (_, msg) = receive("car_contract", "seller", 1, config.clone());
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("other_nft", &[]), msg).is_err());
+        let (info, msg) = receive("car_contract", "seller", 1, AuctionConfig::English {
+            reserve_price: Uint128::new(1000),
+            min_increment: Uint128::zero(),
+            duration: 3600,
+            extension: 300,
+        });
+        assert!(execute(deps.as_mut(), env.clone(), info, msg).is_err());
+        let (info, msg) = receive("car_contract", "seller", 1, config);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let auction = get_auction(&deps, 1);
+        assert_eq!(Asset::Car { car_id: 1 }, auction.asset);
+        assert_eq!(Addr::unchecked("seller"), auction.seller);
+        assert_eq!(env.block.time.plus_seconds(3600), auction.end_time);
+
+        // La primera puja cubre la reserva; el vendedor no puja y solo se acepta uxion
+        let bid = ExecuteMsg::PlaceBid { auction_id: 1 };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &coins(999, DENOM)), bid.clone()).unwrap_err();
+        assert!(err.to_string().contains("La puja mínima es 1000uxion"));
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("seller", &coins(1000, DENOM)), bid.clone()).is_err());
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("alice", &coins(1000, "uatom")), bid.clone()).is_err());
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &coins(1000, DENOM)), bid.clone()).unwrap();
+        assert!(res.messages.is_empty());
+
+        // Cada puja supera a la anterior por el incremento y devuelve la puja superada
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("bob", &coins(1099, DENOM)), bid.clone()).is_err());
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bob", &coins(1100, DENOM)), bid.clone()).unwrap();
+        assert_eq!(vec![bank_send("alice", 1000)], res.messages);
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetCurrentPrice { auction_id: 1 }).unwrap();
+        assert_eq!(Uint128::new(1200), from_json::<Uint128>(&res).unwrap());
+
+        // No se liquida antes del cierre; una puja en los últimos 5 minutos lo extiende
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::SettleAuction { auction_id: 1 }).is_err());
+        env.block.time = env.block.time.plus_seconds(3500);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &coins(1200, DENOM)), bid.clone()).unwrap();
+        assert_eq!(vec![bank_send("bob", 1100)], res.messages);
+        assert_eq!(env.block.time.plus_seconds(300), get_auction(&deps, 1).end_time);
+        let cancel = ExecuteMsg::CancelAuction { auction_id: 1 };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("seller", &[]), cancel).is_err());
+
+        env.block.time = env.block.time.plus_seconds(300);
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("bob", &coins(1300, DENOM)), bid).is_err());
+
+        // Cualquiera liquida: el carro va al ganador, la regalía a la colección y el resto al vendedor
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::SettleAuction { auction_id: 1 }).unwrap();
+        assert_eq!(vec![transfer_car(1, "alice"), bank_send("studio", 60), bank_send("seller", 1140)], res.messages);
+        assert_eq!(AuctionStatus::Sold, get_auction(&deps, 1).status);
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::SettleAuction { auction_id: 1 }).is_err());
+
+        // Sin pujas el carro vuelve al vendedor
+        let (info, msg) = receive("car_contract", "seller", 2, AuctionConfig::English {
+            reserve_price: Uint128::new(1000),
+            min_increment: Uint128::new(100),
+            duration: 60,
+            extension: 0,
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(60);
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), ExecuteMsg::SettleAuction { auction_id: 2 }).unwrap();
+        assert_eq!(vec![transfer_car(2, "seller")], res.messages);
+        assert_eq!(AuctionStatus::Unsold, get_auction(&deps, 2).status);
+    }
+
+    #[test]
+    fn test_dutch_auction() {
+        let mut deps = setup();
+        let mut env = mock_env();
+        let config = AuctionConfig::Dutch {
+            start_price: Uint128::new(10000),
+            end_price: Uint128::new(2000),
+            duration: 1000,
+        };
+        let (info, msg) = receive("part_contract", "seller", 7, AuctionConfig::Dutch {
+            start_price: Uint128::new(1000),
+            end_price: Uint128::new(2000),
+            duration: 1000,
+        });
+        assert!(execute(deps.as_mut(), env.clone(), info, msg).is_err());
+        let (info, msg) = receive("part_contract", "seller", 7, config.clone());
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(Asset::Part { part_id: 7 }, get_auction(&deps, 1).asset);
+
+        // El precio baja linealmente y se queda en el precio final
+        let price = |deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>, env: &Env| {
+            let res = query(deps.as_ref(), env.clone(), QueryMsg::GetCurrentPrice { auction_id: 1 }).unwrap();
+            from_json::<Uint128>(&res).unwrap().u128()
+        };
+        assert_eq!(10000, price(&deps, &env));
+        env.block.time = env.block.time.plus_seconds(250);
+        assert_eq!(8000, price(&deps, &env));
+        let mut later = env.clone();
+        later.block.time = later.block.time.plus_seconds(5000);
+        assert_eq!(2000, price(&deps, &later));
+
+        // En la holandesa no se puja; se compra al precio del momento y se devuelve lo de más
+        let buy = ExecuteMsg::Buy { auction_id: 1 };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("alice", &coins(8000, DENOM)), ExecuteMsg::PlaceBid { auction_id: 1 }).is_err());
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &coins(7999, DENOM)), buy.clone()).unwrap_err();
+        assert!(err.to_string().contains("El precio actual es 8000uxion"));
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("seller", &coins(8000, DENOM)), buy.clone()).is_err());
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &coins(8500, DENOM)), buy.clone()).unwrap();
+        assert_eq!(
+            vec![
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: "part_contract".to_string(),
+                    msg: to_json_binary(&PartExecuteMsg::TransferPart {
+                        from: env.contract.address.to_string(),
+                        to: "alice".to_string(),
+                        part_id: 7,
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                bank_send("seller", 8000),
+                bank_send("alice", 500),
+            ],
+            res.messages
+        );
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("bob", &coins(8000, DENOM)), buy).is_err());
+
+        // El vendedor puede retirar un carro que nadie compró
+        let (info, msg) = receive("car_contract", "seller", 3, config);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let cancel = ExecuteMsg::CancelAuction { auction_id: 2 };
+        assert!(execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), cancel.clone()).is_err());
+        let res = execute(deps.as_mut(), env.clone(), mock_info("seller", &[]), cancel).unwrap();
+        assert_eq!(vec![transfer_car(3, "seller")], res.messages);
+        assert_eq!(AuctionStatus::Cancelled, get_auction(&deps, 2).status);
+
+        let res = query(deps.as_ref(), env, QueryMsg::GetAuctions { start_after: Some(1), limit: None }).unwrap();
+        let auctions: Vec<Auction> = from_json(&res).unwrap();
+        assert_eq!(vec![2], auctions.iter().map(|auction| auction.id).collect::<Vec<u64>>());
+    }
+}
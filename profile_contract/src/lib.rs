@@ -0,0 +1,664 @@
+use cosmwasm_std::{
+    entry_point, to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128, WasmQuery, QueryRequest, coins,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use cw_storage_plus::{Item, Map};
+use car_types::normalize_uri;
+
+// Denominación en la que se cobra el cambio de apodo
+pub const DENOM: &str = "uxion";
+// Largo permitido del apodo, en caracteres
+pub const MIN_NICKNAME_LENGTH: usize = 3;
+pub const MAX_NICKNAME_LENGTH: usize = 20;
+// Máximo de carros favoritos que se muestran en el garaje
+pub const MAX_GARAGE_SIZE: usize = 5;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Profile {
+    pub nickname: String,
+    pub avatar_uri: Option<String>,
+    pub garage: Vec<u64>, // carros favoritos, en el orden elegido por el jugador
+}
+
+// Mejor tiempo de un jugador en una pista, en milisegundos
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TrackTime {
+    pub track_id: u64,
+    pub finish_time: u64,
+}
+
+// Estadísticas acumuladas; las actualiza el leaderboard aunque el jugador no tenga perfil
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PlayerStats {
+    pub races: u32,
+    pub wins: u32,
+    pub best_times: Vec<TrackTime>,
+}
+
+// Resultado de un jugador en una carrera, enviado por el leaderboard
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlayerResult {
+    pub player: String,
+    pub won: bool,
+    pub finish_time: Option<u64>, // solo carreras simuladas
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProfileResponse {
+    pub address: Addr,
+    pub profile: Option<Profile>,
+    pub stats: PlayerStats,
+}
+
+// Estado del contrato
+pub struct ProfileContract<'a> {
+    pub admin: Item<'a, Addr>,
+    pub car_contract: Item<'a, Addr>,
+    pub leaderboard_contract: Item<'a, Addr>, // único autorizado a registrar resultados
+    pub name_change_fee: Item<'a, Uint128>, // cero = cambiar el apodo es gratis
+    pub fee_balance: Item<'a, Uint128>,
+    pub profiles: Map<'a, &'a Addr, Profile>,
+    pub nicknames: Map<'a, &'a str, Addr>, // apodo en minúsculas => dueño
+    pub reserved_nicknames: Map<'a, &'a str, bool>, // apodos en minúsculas que nadie puede tomar
+    pub stats: Map<'a, &'a Addr, PlayerStats>,
+}
+
+impl<'a> Default for ProfileContract<'a> {
+    fn default() -> Self {
+        Self {
+            admin: Item::new("admin"),
+            car_contract: Item::new("car_contract"),
+            leaderboard_contract: Item::new("leaderboard_contract"),
+            name_change_fee: Item::new("name_change_fee"),
+            fee_balance: Item::new("fee_balance"),
+            profiles: Map::new("profiles"),
+            nicknames: Map::new("nicknames"),
+            reserved_nicknames: Map::new("reserved_nicknames"),
+            stats: Map::new("stats"),
+        }
+    }
+}
+
+// Mensajes de instanciación
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub car_contract: String,
+    pub leaderboard_contract: String,
+}
+
+// Mensajes de ejecución
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    RegisterProfile {
+        nickname: String,
+        avatar_uri: Option<String>,
+    },
+    SetNickname {
+        nickname: String,
+    },
+    SetAvatar {
+        avatar_uri: Option<String>,
+    },
+    SetGarage {
+        car_ids: Vec<u64>,
+    },
+    RecordResults {
+        track_id: u64,
+        results: Vec<PlayerResult>,
+    },
+    ReserveNicknames {
+        nicknames: Vec<String>,
+    },
+    ReleaseNicknames {
+        nicknames: Vec<String>,
+    },
+    SetNameChangeFee {
+        fee: Uint128,
+    },
+    SetLeaderboardContract {
+        address: String,
+    },
+    WithdrawFees {},
+}
+
+// Mensajes de consulta
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    GetProfile {
+        address: String,
+    },
+    ResolveNickname {
+        nickname: String,
+    },
+    IsNicknameAvailable {
+        nickname: String,
+    },
+    GetNameChangeFee {},
+}
+
+// Consultas al contrato de carros
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CarQueryMsg {
+    GetOwnerCars {
+        owner: String,
+    },
+}
+
+// Respuesta de GetOwnerCars del contrato de carros
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerCarsResponse {
+    pub car_ids: Vec<u64>,
+}
+
+// Entry points
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    let contract = ProfileContract::default();
+
+    // Quien instancia el contrato queda como admin
+    contract.admin.save(deps.storage, &info.sender)?;
+    let car_contract = deps.api.addr_validate(&msg.car_contract)?;
+    contract.car_contract.save(deps.storage, &car_contract)?;
+    let leaderboard_contract = deps.api.addr_validate(&msg.leaderboard_contract)?;
+    contract.leaderboard_contract.save(deps.storage, &leaderboard_contract)?;
+    contract.name_change_fee.save(deps.storage, &Uint128::zero())?;
+    contract.fee_balance.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", info.sender)
+        .add_attribute("car_contract", msg.car_contract)
+        .add_attribute("leaderboard_contract", msg.leaderboard_contract))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    let contract = ProfileContract::default();
+
+    match msg {
+        ExecuteMsg::RegisterProfile { nickname, avatar_uri } => {
+            execute_register_profile(deps, info, contract, nickname, avatar_uri)
+        },
+        ExecuteMsg::SetNickname { nickname } => {
+            execute_set_nickname(deps, info, contract, nickname)
+        },
+        ExecuteMsg::SetAvatar { avatar_uri } => {
+            execute_set_avatar(deps, info, contract, avatar_uri)
+        },
+        ExecuteMsg::SetGarage { car_ids } => {
+            execute_set_garage(deps, info, contract, car_ids)
+        },
+        ExecuteMsg::RecordResults { track_id, results } => {
+            execute_record_results(deps, info, contract, track_id, results)
+        },
+        ExecuteMsg::ReserveNicknames { nicknames } => {
+            execute_reserve_nicknames(deps, info, contract, nicknames, true)
+        },
+        ExecuteMsg::ReleaseNicknames { nicknames } => {
+            execute_reserve_nicknames(deps, info, contract, nicknames, false)
+        },
+        ExecuteMsg::SetNameChangeFee { fee } => {
+            execute_set_name_change_fee(deps, info, contract, fee)
+        },
+        ExecuteMsg::SetLeaderboardContract { address } => {
+            execute_set_leaderboard_contract(deps, info, contract, address)
+        },
+        ExecuteMsg::WithdrawFees {} => {
+            execute_withdraw_fees(deps, info, contract)
+        },
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(
+    deps: Deps,
+    _env: Env,
+    msg: QueryMsg,
+) -> StdResult<Binary> {
+    let contract = ProfileContract::default();
+
+    match msg {
+        QueryMsg::GetProfile { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&ProfileResponse {
+                profile: contract.profiles.may_load(deps.storage, &address)?,
+                stats: contract.stats.may_load(deps.storage, &address)?.unwrap_or_default(),
+                address,
+            })
+        },
+        QueryMsg::ResolveNickname { nickname } => {
+            to_json_binary(&contract.nicknames.may_load(deps.storage, &nickname.trim().to_lowercase())?)
+        },
+        QueryMsg::IsNicknameAvailable { nickname } => {
+            let available = validate_nickname(&nickname)
+                .and_then(|key| check_nickname_free(deps, &contract, &key))
+                .is_ok();
+            to_json_binary(&available)
+        },
+        QueryMsg::GetNameChangeFee {} => {
+            to_json_binary(&contract.name_change_fee.load(deps.storage)?)
+        },
+    }
+}
+
+// Funciones de ejecución
+fn ensure_admin(deps: Deps, contract: &ProfileContract, info: &MessageInfo) -> StdResult<()> {
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede realizar esta acción"));
+    }
+    Ok(())
+}
+
+// Un apodo válido tiene entre 3 y 20 letras, números, `_` o `-`. Devuelve la clave en minúsculas
+// con la que se comprueba que sea único sin distinguir mayúsculas.
+fn validate_nickname(nickname: &str) -> StdResult<String> {
+    let length = nickname.chars().count();
+    if !(MIN_NICKNAME_LENGTH..=MAX_NICKNAME_LENGTH).contains(&length) {
+        return Err(StdError::generic_err(format!(
+            "El apodo debe tener entre {} y {} caracteres", MIN_NICKNAME_LENGTH, MAX_NICKNAME_LENGTH
+        )));
+    }
+    if !nickname.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(StdError::generic_err("El apodo solo puede tener letras, números, '_' o '-'"));
+    }
+    Ok(nickname.to_lowercase())
+}
+
+fn check_nickname_free(deps: Deps, contract: &ProfileContract, key: &str) -> StdResult<()> {
+    if contract.reserved_nicknames.has(deps.storage, key) {
+        return Err(StdError::generic_err("El apodo está reservado"));
+    }
+    if contract.nicknames.has(deps.storage, key) {
+        return Err(StdError::generic_err("El apodo ya está en uso"));
+    }
+    Ok(())
+}
+
+fn normalize_avatar(avatar_uri: Option<String>) -> StdResult<Option<String>> {
+    avatar_uri
+        .map(|uri| normalize_uri(&uri).map_err(StdError::generic_err))
+        .transpose()
+}
+
+fn execute_register_profile(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: ProfileContract,
+    nickname: String,
+    avatar_uri: Option<String>,
+) -> StdResult<Response> {
+    if contract.profiles.has(deps.storage, &info.sender) {
+        return Err(StdError::generic_err("Ya tienes un perfil"));
+    }
+    let key = validate_nickname(&nickname)?;
+    check_nickname_free(deps.as_ref(), &contract, &key)?;
+    let avatar_uri = normalize_avatar(avatar_uri)?;
+
+    contract.nicknames.save(deps.storage, &key, &info.sender)?;
+    let profile = Profile {
+        nickname: nickname.clone(),
+        avatar_uri,
+        garage: vec![],
+    };
+    contract.profiles.save(deps.storage, &info.sender, &profile)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "register_profile")
+        .add_attribute("owner", info.sender)
+        .add_attribute("nickname", nickname))
+}
+
+// Cambiar el apodo cobra la tarifa configurada y libera el anterior
+fn execute_set_nickname(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: ProfileContract,
+    nickname: String,
+) -> StdResult<Response> {
+    let mut profile = contract.profiles.may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| StdError::generic_err("No tienes un perfil"))?;
+    let key = validate_nickname(&nickname)?;
+    let old_key = profile.nickname.to_lowercase();
+    // Cambiar solo mayúsculas y minúsculas conserva el mismo apodo
+    if key != old_key {
+        check_nickname_free(deps.as_ref(), &contract, &key)?;
+    }
+
+    let fee = contract.name_change_fee.load(deps.storage)?;
+    let paid: Uint128 = info.funds.iter()
+        .filter(|coin| coin.denom == DENOM)
+        .map(|coin| coin.amount)
+        .sum();
+    if paid != fee {
+        return Err(StdError::generic_err(format!(
+            "Debes pagar exactamente {}{} para cambiar el apodo", fee, DENOM
+        )));
+    }
+
+    contract.nicknames.remove(deps.storage, &old_key);
+    contract.nicknames.save(deps.storage, &key, &info.sender)?;
+    let old_nickname = std::mem::replace(&mut profile.nickname, nickname.clone());
+    contract.profiles.save(deps.storage, &info.sender, &profile)?;
+    contract.fee_balance.update(deps.storage, |balance| -> StdResult<_> { Ok(balance + fee) })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_nickname")
+        .add_attribute("owner", info.sender)
+        .add_attribute("old_nickname", old_nickname)
+        .add_attribute("nickname", nickname))
+}
+
+fn execute_set_avatar(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: ProfileContract,
+    avatar_uri: Option<String>,
+) -> StdResult<Response> {
+    let mut profile = contract.profiles.may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| StdError::generic_err("No tienes un perfil"))?;
+    profile.avatar_uri = normalize_avatar(avatar_uri)?;
+    contract.profiles.save(deps.storage, &info.sender, &profile)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_avatar")
+        .add_attribute("owner", info.sender)
+        .add_attribute("avatar_uri", profile.avatar_uri.unwrap_or_default()))
+}
+
+// El garaje solo puede mostrar carros propios, sin repetir
+fn execute_set_garage(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: ProfileContract,
+    car_ids: Vec<u64>,
+) -> StdResult<Response> {
+    let mut profile = contract.profiles.may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| StdError::generic_err("No tienes un perfil"))?;
+    if car_ids.len() > MAX_GARAGE_SIZE {
+        return Err(StdError::generic_err(format!("El garaje admite hasta {} carros", MAX_GARAGE_SIZE)));
+    }
+    if car_ids.iter().enumerate().any(|(index, car_id)| car_ids[..index].contains(car_id)) {
+        return Err(StdError::generic_err("El garaje no puede repetir carros"));
+    }
+
+    let car_contract = contract.car_contract.load(deps.storage)?;
+    let owned: OwnerCarsResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: car_contract.to_string(),
+        msg: to_json_binary(&CarQueryMsg::GetOwnerCars { owner: info.sender.to_string() })?,
+    }))?;
+    if let Some(car_id) = car_ids.iter().find(|car_id| !owned.car_ids.contains(car_id)) {
+        return Err(StdError::generic_err(format!("No eres el dueño del carro {}", car_id)));
+    }
+
+    profile.garage = car_ids;
+    contract.profiles.save(deps.storage, &info.sender, &profile)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_garage")
+        .add_attribute("owner", info.sender)
+        .add_attribute("garage", format!("{:?}", profile.garage)))
+}
+
+// El leaderboard reporta el resultado de cada jugador de una carrera liquidada
+fn execute_record_results(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: ProfileContract,
+    track_id: u64,
+    results: Vec<PlayerResult>,
+) -> StdResult<Response> {
+    let leaderboard = contract.leaderboard_contract.load(deps.storage)?;
+    if info.sender != leaderboard {
+        return Err(StdError::generic_err("Solo el leaderboard puede registrar resultados"));
+    }
+
+    for result in &results {
+        let player = deps.api.addr_validate(&result.player)?;
+        let mut stats = contract.stats.may_load(deps.storage, &player)?.unwrap_or_default();
+        stats.races += 1;
+        if result.won {
+            stats.wins += 1;
+        }
+        if let Some(finish_time) = result.finish_time {
+            match stats.best_times.iter_mut().find(|best| best.track_id == track_id) {
+                Some(best) => best.finish_time = best.finish_time.min(finish_time),
+                None => stats.best_times.push(TrackTime { track_id, finish_time }),
+            }
+        }
+        contract.stats.save(deps.storage, &player, &stats)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "record_results")
+        .add_attribute("track_id", track_id.to_string())
+        .add_attribute("players", results.len().to_string()))
+}
+
+// Reservar o liberar apodos. Reservar uno en uso no se lo quita a su dueño actual.
+fn execute_reserve_nicknames(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: ProfileContract,
+    nicknames: Vec<String>,
+    reserve: bool,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    for nickname in &nicknames {
+        let key = nickname.trim().to_lowercase();
+        if reserve {
+            contract.reserved_nicknames.save(deps.storage, &key, &true)?;
+        } else {
+            contract.reserved_nicknames.remove(deps.storage, &key);
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", if reserve { "reserve_nicknames" } else { "release_nicknames" })
+        .add_attribute("nicknames", nicknames.join(",")))
+}
+
+fn execute_set_name_change_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: ProfileContract,
+    fee: Uint128,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+    contract.name_change_fee.save(deps.storage, &fee)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_name_change_fee")
+        .add_attribute("fee", fee.to_string()))
+}
+
+fn execute_set_leaderboard_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: ProfileContract,
+    address: String,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+    let leaderboard = deps.api.addr_validate(&address)?;
+    contract.leaderboard_contract.save(deps.storage, &leaderboard)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_leaderboard_contract")
+        .add_attribute("address", address))
+}
+
+fn execute_withdraw_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: ProfileContract,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    let amount = contract.fee_balance.load(deps.storage)?;
+    if amount.is_zero() {
+        return Err(StdError::generic_err("No hay tarifas para retirar"));
+    }
+    contract.fee_balance.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), DENOM),
+        })
+        .add_attribute("method", "withdraw_fees")
+        .add_attribute("recipient", info.sender)
+        .add_attribute("amount", amount.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+    use cosmwasm_std::{from_json, CosmosMsg, MemoryStorage, OwnedDeps, SystemResult, ContractResult};
+
+    // Simula el contrato de carros: "ownerN" tiene los carros N y N + 100
+    fn setup() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { msg, .. } => {
+                let CarQueryMsg::GetOwnerCars { owner } = from_json(msg).unwrap();
+                let car_id: u64 = owner.trim_start_matches("owner").parse().unwrap();
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&OwnerCarsResponse { car_ids: vec![car_id, car_id + 100] }).unwrap()))
+            },
+            _ => panic!("Consulta inesperada"),
+        });
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+            leaderboard_contract: "race_contract".to_string(),
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn register(deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier>, owner: &str, nickname: &str) -> StdResult<Response> {
+        let msg = ExecuteMsg::RegisterProfile { nickname: nickname.to_string(), avatar_uri: None };
+        execute(deps.as_mut(), mock_env(), mock_info(owner, &[]), msg)
+    }
+
+    fn get_profile(deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>, address: &str) -> ProfileResponse {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProfile { address: address.to_string() }).unwrap();
+        from_json(&res).unwrap()
+    }
+
+    #[test]
+    fn test_register_profile() {
+        let mut deps = setup();
+
+        for bad in ["ab", "demasiado_largo_para_apodo", "con espacio", "ñandú"] {
+            assert!(register(&mut deps, "owner1", bad).is_err());
+        }
+        let msg = ExecuteMsg::RegisterProfile { nickname: "Turbo".to_string(), avatar_uri: Some("ftp://avatar".to_string()) };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("owner1", &[]), msg).is_err());
+        let msg = ExecuteMsg::RegisterProfile { nickname: "Turbo".to_string(), avatar_uri: Some(" ipfs://ipfs/avatar ".to_string()) };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("owner1", &[]), msg).unwrap();
+        assert!(register(&mut deps, "owner1", "Otro").is_err());
+
+        // Único sin distinguir mayúsculas
+        let err = register(&mut deps, "owner2", "TURBO").unwrap_err();
+        assert!(err.to_string().contains("ya está en uso"));
+
+        // Apodos reservados por el admin
+        let reserve = ExecuteMsg::ReserveNicknames { nicknames: vec!["Admin".to_string()] };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("owner2", &[]), reserve.clone()).is_err());
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reserve).unwrap();
+        let err = register(&mut deps, "owner2", "admin").unwrap_err();
+        assert!(err.to_string().contains("reservado"));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::IsNicknameAvailable { nickname: "ADMIN".to_string() }).unwrap();
+        assert!(!from_json::<bool>(&res).unwrap());
+        let release = ExecuteMsg::ReleaseNicknames { nicknames: vec!["admin".to_string()] };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), release).unwrap();
+        let _res = register(&mut deps, "owner2", "Admin").unwrap();
+
+        let profile = get_profile(&deps, "owner1").profile.unwrap();
+        assert_eq!("Turbo", profile.nickname);
+        assert_eq!(Some("ipfs://avatar".to_string()), profile.avatar_uri);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ResolveNickname { nickname: "turbo".to_string() }).unwrap();
+        assert_eq!(Some(Addr::unchecked("owner1")), from_json::<Option<Addr>>(&res).unwrap());
+        assert!(get_profile(&deps, "owner3").profile.is_none());
+    }
+
+    #[test]
+    fn test_nickname_change_fee() {
+        let mut deps = setup();
+        let _res = register(&mut deps, "owner1", "Turbo").unwrap();
+        let _res = register(&mut deps, "owner2", "Nitro").unwrap();
+
+        let fee = ExecuteMsg::SetNameChangeFee { fee: Uint128::new(500) };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), fee).unwrap();
+
+        let rename = |nickname: &str| ExecuteMsg::SetNickname { nickname: nickname.to_string() };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("owner1", &coins(500, DENOM)), rename("nitro")).is_err());
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner1", &coins(400, DENOM)), rename("Drifter")).unwrap_err();
+        assert!(err.to_string().contains("exactamente 500uxion"));
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("owner1", &coins(500, DENOM)), rename("Drifter")).unwrap();
+
+        // El apodo anterior queda libre
+        let _res = register(&mut deps, "owner3", "turbo").unwrap();
+        assert_eq!("Drifter", get_profile(&deps, "owner1").profile.unwrap().nickname);
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::WithdrawFees {}).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send { to_address: "creator".to_string(), amount: coins(500, DENOM) }),
+            res.messages[0].msg
+        );
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::WithdrawFees {}).is_err());
+    }
+
+    #[test]
+    fn test_garage_and_stats() {
+        let mut deps = setup();
+        let garage = |car_ids: Vec<u64>| ExecuteMsg::SetGarage { car_ids };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("owner1", &[]), garage(vec![1])).is_err());
+        let _res = register(&mut deps, "owner1", "Turbo").unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner1", &[]), garage(vec![1, 2])).unwrap_err();
+        assert!(err.to_string().contains("No eres el dueño del carro 2"));
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("owner1", &[]), garage(vec![1, 1])).is_err());
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("owner1", &[]), garage(vec![1; 6])).is_err());
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("owner1", &[]), garage(vec![101, 1])).unwrap();
+        assert_eq!(vec![101, 1], get_profile(&deps, "owner1").profile.unwrap().garage);
+
+        // Solo el leaderboard registra resultados, tenga o no perfil el jugador
+        let record = |track_id: u64, results: Vec<(&str, bool, Option<u64>)>| ExecuteMsg::RecordResults {
+            track_id,
+            results: results.into_iter()
+                .map(|(player, won, finish_time)| PlayerResult { player: player.to_string(), won, finish_time })
+                .collect(),
+        };
+        let msg = record(1, vec![("owner1", true, None)]);
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("owner1", &[]), msg).is_err());
+        let leaderboard = mock_info("race_contract", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), leaderboard.clone(), record(1, vec![("owner2", true, Some(900)), ("owner1", false, Some(950))])).unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), leaderboard.clone(), record(1, vec![("owner1", true, Some(920)), ("owner2", false, Some(990))])).unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), leaderboard, record(2, vec![("owner1", true, None)])).unwrap();
+
+        let stats = get_profile(&deps, "owner1").stats;
+        assert_eq!((3, 2), (stats.races, stats.wins));
+        assert_eq!(vec![TrackTime { track_id: 1, finish_time: 920 }], stats.best_times);
+        let response = get_profile(&deps, "owner2");
+        assert!(response.profile.is_none());
+        assert_eq!((2, 1), (response.stats.races, response.stats.wins));
+        assert_eq!(900, response.stats.best_times[0].finish_time);
+    }
+}
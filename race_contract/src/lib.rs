@@ -0,0 +1,1683 @@
+use cosmwasm_std::{
+    entry_point, to_json_binary, Addr, BankMsg, Binary, BlockInfo, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdError, StdResult, Storage, Timestamp, Uint128, WasmMsg, coins,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use cw_storage_plus::{Item, Map};
+use sha2::{Digest, Sha256};
+use car_types::randomness::{to_hex, RandomnessRound};
+
+// Denominación en la que se pagan las inscripciones y los premios
+pub const DENOM: &str = "uxion";
+
+// Bloques tras los que cualquiera puede cancelar una carrera sin liquidar (~7 días a 6s por bloque)
+pub const RACE_TIMEOUT_BLOCKS: u64 = 100_800;
+
+// Reparto del pozo: porcentaje para cada puesto (1º, 2º, ...) y comisión de la casa
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayoutConfig {
+    pub splits: Vec<u8>,
+    pub house_fee: u8,
+}
+
+impl Default for PayoutConfig {
+    fn default() -> Self {
+        Self {
+            splits: vec![60, 30, 10],
+            house_fee: 5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Entrant {
+    pub car_id: u64,
+    pub owner: Addr, // quien corre: el dueño o el inquilino del carro, y quien cobra el premio
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum RaceStatus {
+    Open,
+    Settled,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Payout {
+    pub car_id: u64,
+    pub owner: Addr,
+    pub amount: Uint128,
+}
+
+// Perfil de la pista: largo, peso de rectas frente a curvas (suman 100) y desgaste que deja en cada carro
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TrackProfile {
+    pub length: u32,
+    pub straight_weight: u8,
+    pub corner_weight: u8,
+    pub wear: u8,
+}
+
+// Cómo se decide el resultado: lo reporta el servidor de juego o se simula on-chain.
+// En una carrera simulada cada inscrito compromete un secreto al inscribirse (hasta `entry_deadline`)
+// y lo revela en los `reveal_window` bloques siguientes; la semilla sale de todos los secretos revelados.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RaceMode {
+    #[default]
+    Reported,
+    Simulated {
+        track: TrackProfile,
+        entry_deadline: u64,
+        reveal_window: u64,
+    },
+}
+
+// Tiempo de llegada simulado de un carro, en milisegundos
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RaceResult {
+    pub car_id: u64,
+    pub finish_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Race {
+    #[serde(default)]
+    pub mode: RaceMode,
+    #[serde(default)]
+    pub track_id: u64, // pista en la que puntúa dentro de la temporada
+    pub entry_fee: Uint128,
+    pub min_condition: u8,
+    pub max_entrants: u32,
+    pub entrants: Vec<Entrant>,
+    pub status: RaceStatus,
+    pub finishing_order: Vec<u64>, // car_ids del primero al último, vacío hasta liquidar
+    pub payouts: Vec<Payout>,
+    pub house_cut: Uint128,
+    #[serde(default)]
+    pub results: Vec<RaceResult>, // solo en carreras simuladas
+    #[serde(default)]
+    pub randomness: Option<RandomnessRound>, // commits de los inscritos en carreras simuladas
+    #[serde(default)]
+    pub disqualified: Vec<u64>, // carros que no revelaron su secreto o ya no existen: llegan últimos y sin premio
+    #[serde(default)]
+    pub cancel_after: Option<u64>, // bloque desde el que cualquiera puede cancelarla; sin él solo el admin
+}
+
+// Vigencia de una temporada, por altura de bloque o por hora (ambos extremos inclusive)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SeasonPeriod {
+    Blocks { start: u64, end: u64 },
+    Time { start: Timestamp, end: Timestamp },
+}
+
+impl SeasonPeriod {
+    pub fn is_active(&self, block: &BlockInfo) -> bool {
+        match self {
+            SeasonPeriod::Blocks { start, end } => (*start..=*end).contains(&block.height),
+            SeasonPeriod::Time { start, end } => (*start..=*end).contains(&block.time),
+        }
+    }
+
+    pub fn has_ended(&self, block: &BlockInfo) -> bool {
+        match self {
+            SeasonPeriod::Blocks { end, .. } => block.height > *end,
+            SeasonPeriod::Time { end, .. } => block.time > *end,
+        }
+    }
+}
+
+// Puntos de un carro en una pista durante una temporada
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Standing {
+    pub car_id: u64,
+    pub owner: Addr,
+    pub points: u32,
+    pub races: u32,
+    pub wins: u32,
+    pub best_time: Option<u64>, // solo carreras simuladas
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SeasonReward {
+    pub rank: u32,
+    pub standing: Standing,
+    pub reward: Uint128,
+}
+
+// Top N de una pista al cerrar la temporada
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TrackSnapshot {
+    pub track_id: u64,
+    pub entries: Vec<SeasonReward>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Season {
+    pub period: SeasonPeriod,
+    pub points: Vec<u32>,        // puntos por puesto en cada carrera (1º, 2º, ...)
+    pub reward_splits: Vec<u8>,  // porcentaje del premio de cada pista para el top N; N = largo de la lista
+    pub reward_pool: Uint128,
+    pub closed: bool,
+    pub snapshot: Vec<TrackSnapshot>,
+}
+
+// Estado del contrato
+pub struct RaceContract<'a> {
+    pub admin: Item<'a, Addr>,
+    pub car_contract: Item<'a, Addr>,
+    pub game_server: Item<'a, Addr>,
+    pub payout_config: Item<'a, PayoutConfig>,
+    pub races: Map<'a, u64, Race>,
+    pub house_balance: Item<'a, Uint128>, // comisiones acumuladas que el admin puede retirar
+    pub seasons: Map<'a, u64, Season>,
+    pub current_season: Item<'a, u64>, // última temporada creada
+    pub standings: Map<'a, (u64, u64, u64), Standing>, // (season_id, track_id, car_id) => puntos
+    pub profile_contract: Item<'a, Addr>, // opcional: recibe las estadísticas de cada jugador
+}
+
+impl<'a> Default for RaceContract<'a> {
+    fn default() -> Self {
+        Self {
+            admin: Item::new("admin"),
+            car_contract: Item::new("car_contract"),
+            game_server: Item::new("game_server"),
+            payout_config: Item::new("payout_config"),
+            races: Map::new("races"),
+            house_balance: Item::new("house_balance"),
+            seasons: Map::new("seasons"),
+            current_season: Item::new("current_season"),
+            standings: Map::new("standings"),
+            profile_contract: Item::new("profile_contract"),
+        }
+    }
+}
+
+// Mensajes de instanciación
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub car_contract: String,
+    pub game_server: String,
+}
+
+// Mensajes de ejecución
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    CreateRace {
+        race_id: u64,
+        entry_fee: Uint128,
+        min_condition: u8,
+        max_entrants: u32,
+        #[serde(default)]
+        mode: RaceMode,
+        #[serde(default)]
+        track_id: u64,
+    },
+    JoinRace {
+        race_id: u64,
+        car_id: u64,
+        #[serde(default)]
+        commitment: Option<String>,
+    },
+    RevealSeed {
+        race_id: u64,
+        car_id: u64,
+        secret: String,
+    },
+    SubmitResults {
+        race_id: u64,
+        finishing_order: Vec<u64>,
+    },
+    RunRace {
+        race_id: u64,
+    },
+    CancelRace {
+        race_id: u64,
+    },
+    SetPayoutConfig {
+        splits: Vec<u8>,
+        house_fee: u8,
+    },
+    SetGameServer {
+        address: String,
+    },
+    WithdrawFunds {},
+    CreateSeason {
+        period: SeasonPeriod,
+        points: Vec<u32>,
+        reward_splits: Vec<u8>,
+    },
+    FundSeason {
+        season_id: u64,
+    },
+    CloseSeason {
+        season_id: u64,
+    },
+    SetProfileContract {
+        address: String,
+    },
+}
+
+// Mensajes de consulta
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    GetRace {
+        race_id: u64,
+    },
+    GetPayoutConfig {},
+    GetGameServer {},
+    GetHouseBalance {},
+    GetSeason {
+        season_id: u64,
+    },
+    GetCurrentSeason {},
+    GetTrackRanking {
+        season_id: u64,
+        track_id: u64,
+        limit: Option<u32>,
+    },
+    GetProfileContract {},
+}
+
+// Consultas al contrato de carros
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CarQueryMsg {
+    GetOwnerCars {
+        owner: String,
+    },
+    GetCompactCarStats {
+        car_id: u64,
+    },
+    GetFullCarMetadata {
+        car_id: u64,
+    },
+    UserOf {
+        car_id: u64,
+    },
+}
+
+// Mensajes al contrato de carros; este contrato debe estar configurado como su leaderboard
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CarExecuteMsg {
+    DegradeCar {
+        car_id: u64,
+        wear: u8,
+    },
+}
+
+// Mensajes al contrato de perfiles; este contrato debe estar configurado como su leaderboard
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileExecuteMsg {
+    RecordResults {
+        track_id: u64,
+        results: Vec<PlayerResult>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlayerResult {
+    pub player: String,
+    pub won: bool,
+    pub finish_time: Option<u64>,
+}
+
+// Respuesta de GetOwnerCars del contrato de carros
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerCarsResponse {
+    pub car_ids: Vec<u64>,
+}
+
+// Parte de la respuesta de UserOf: quien tiene alquilado el carro
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CarUser {
+    pub user: Addr,
+}
+
+// Parte de la respuesta de GetFullCarMetadata que usa la simulación
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FullCarMetadata {
+    pub car_id: u64,
+    pub total_stats: CompactCarStats,
+}
+
+// Respuesta de GetCompactCarStats del contrato de carros
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CompactCarStats {
+    pub image_uri: String,
+    pub speed: u8,
+    pub acceleration: u8,
+    pub handling: u8,
+    pub drift_factor: u8,
+    pub turn_factor: u8,
+    pub max_speed: u8,
+    pub condition: u8,
+}
+
+// Entry points
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    let contract = RaceContract::default();
+
+    // Quien instancia el contrato queda como admin
+    contract.admin.save(deps.storage, &info.sender)?;
+    let car_contract = deps.api.addr_validate(&msg.car_contract)?;
+    contract.car_contract.save(deps.storage, &car_contract)?;
+    let game_server = deps.api.addr_validate(&msg.game_server)?;
+    contract.game_server.save(deps.storage, &game_server)?;
+    contract.payout_config.save(deps.storage, &PayoutConfig::default())?;
+    contract.house_balance.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", info.sender)
+        .add_attribute("car_contract", msg.car_contract)
+        .add_attribute("game_server", msg.game_server))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    let contract = RaceContract::default();
+
+    match msg {
+        ExecuteMsg::CreateRace { race_id, entry_fee, min_condition, max_entrants, mode, track_id } => {
+            execute_create_race(deps, env, info, contract, race_id, entry_fee, min_condition, max_entrants, mode, track_id)
+        },
+        ExecuteMsg::JoinRace { race_id, car_id, commitment } => {
+            execute_join_race(deps, env, info, contract, race_id, car_id, commitment)
+        },
+        ExecuteMsg::RevealSeed { race_id, car_id, secret } => {
+            execute_reveal_seed(deps, env, info, contract, race_id, car_id, secret)
+        },
+        ExecuteMsg::SubmitResults { race_id, finishing_order } => {
+            execute_submit_results(deps, env, info, contract, race_id, finishing_order)
+        },
+        ExecuteMsg::RunRace { race_id } => {
+            execute_run_race(deps, env, contract, race_id)
+        },
+        ExecuteMsg::CancelRace { race_id } => {
+            execute_cancel_race(deps, env, info, contract, race_id)
+        },
+        ExecuteMsg::SetPayoutConfig { splits, house_fee } => {
+            execute_set_payout_config(deps, info, contract, PayoutConfig { splits, house_fee })
+        },
+        ExecuteMsg::SetGameServer { address } => {
+            execute_set_game_server(deps, info, contract, address)
+        },
+        ExecuteMsg::WithdrawFunds {} => {
+            execute_withdraw_funds(deps, env, info, contract)
+        },
+        ExecuteMsg::CreateSeason { period, points, reward_splits } => {
+            execute_create_season(deps, env, info, contract, period, points, reward_splits)
+        },
+        ExecuteMsg::FundSeason { season_id } => {
+            execute_fund_season(deps, info, contract, season_id)
+        },
+        ExecuteMsg::CloseSeason { season_id } => {
+            execute_close_season(deps, env, info, contract, season_id)
+        },
+        ExecuteMsg::SetProfileContract { address } => {
+            execute_set_profile_contract(deps, info, contract, address)
+        },
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(
+    deps: Deps,
+    _env: Env,
+    msg: QueryMsg,
+) -> StdResult<Binary> {
+    let contract = RaceContract::default();
+
+    match msg {
+        QueryMsg::GetRace { race_id } => {
+            to_json_binary(&contract.races.load(deps.storage, race_id)?)
+        },
+        QueryMsg::GetPayoutConfig {} => {
+            to_json_binary(&contract.payout_config.load(deps.storage)?)
+        },
+        QueryMsg::GetGameServer {} => {
+            to_json_binary(&contract.game_server.load(deps.storage)?)
+        },
+        QueryMsg::GetHouseBalance {} => {
+            to_json_binary(&contract.house_balance.load(deps.storage)?)
+        },
+        QueryMsg::GetSeason { season_id } => {
+            to_json_binary(&contract.seasons.load(deps.storage, season_id)?)
+        },
+        QueryMsg::GetCurrentSeason {} => {
+            to_json_binary(&contract.current_season.may_load(deps.storage)?)
+        },
+        QueryMsg::GetTrackRanking { season_id, track_id, limit } => {
+            let mut ranking = track_ranking(deps.storage, &contract, season_id, track_id)?;
+            if let Some(limit) = limit {
+                ranking.truncate(limit as usize);
+            }
+            to_json_binary(&ranking)
+        },
+        QueryMsg::GetProfileContract {} => {
+            to_json_binary(&contract.profile_contract.may_load(deps.storage)?)
+        },
+    }
+}
+
+// Funciones de ejecución
+fn ensure_admin(deps: Deps, contract: &RaceContract, info: &MessageInfo) -> StdResult<()> {
+    let admin = contract.admin.load(deps.storage)?;
+    if info.sender != admin {
+        return Err(StdError::generic_err("Solo el admin puede realizar esta acción"));
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_create_race(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: RaceContract,
+    race_id: u64,
+    entry_fee: Uint128,
+    min_condition: u8,
+    max_entrants: u32,
+    mode: RaceMode,
+    track_id: u64,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    if contract.races.has(deps.storage, race_id) {
+        return Err(StdError::generic_err(format!("La carrera {} ya existe", race_id)));
+    }
+    if max_entrants < 2 {
+        return Err(StdError::generic_err("Una carrera necesita al menos 2 participantes"));
+    }
+    if min_condition > 100 {
+        return Err(StdError::generic_err("La condición mínima no puede superar 100"));
+    }
+    let (randomness, cancel_after) = match &mode {
+        RaceMode::Simulated { track, entry_deadline, reveal_window } => {
+            if track.length == 0 || track.straight_weight as u16 + track.corner_weight as u16 != 100 {
+                return Err(StdError::generic_err("La pista necesita largo y pesos de rectas y curvas que sumen 100"));
+            }
+            if *entry_deadline <= env.block.height {
+                return Err(StdError::generic_err("El cierre de inscripciones debe ser un bloque futuro"));
+            }
+            let reveal_deadline = entry_deadline.saturating_add(*reveal_window);
+            let round = RandomnessRound::new(*entry_deadline, reveal_deadline).map_err(StdError::generic_err)?;
+            (Some(round), reveal_deadline.saturating_add(RACE_TIMEOUT_BLOCKS))
+        },
+        RaceMode::Reported => (None, env.block.height.saturating_add(RACE_TIMEOUT_BLOCKS)),
+    };
+
+    let race = Race {
+        mode,
+        track_id,
+        entry_fee,
+        min_condition,
+        max_entrants,
+        entrants: vec![],
+        status: RaceStatus::Open,
+        finishing_order: vec![],
+        payouts: vec![],
+        house_cut: Uint128::zero(),
+        results: vec![],
+        randomness,
+        disqualified: vec![],
+        cancel_after: Some(cancel_after),
+    };
+    contract.races.save(deps.storage, race_id, &race)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "create_race")
+        .add_attribute("race_id", race_id.to_string())
+        .add_attribute("entry_fee", entry_fee.to_string())
+        .add_attribute("min_condition", min_condition.to_string()))
+}
+
+fn execute_join_race(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: RaceContract,
+    race_id: u64,
+    car_id: u64,
+    commitment: Option<String>,
+) -> StdResult<Response> {
+    let mut race = contract.races.load(deps.storage, race_id)?;
+    if race.status != RaceStatus::Open {
+        return Err(StdError::generic_err("La carrera ya no admite inscripciones"));
+    }
+    if race.entrants.len() as u32 >= race.max_entrants {
+        return Err(StdError::generic_err("La carrera está llena"));
+    }
+    if race.entrants.iter().any(|entrant| entrant.car_id == car_id) {
+        return Err(StdError::generic_err("El carro ya está inscrito en esta carrera"));
+    }
+
+    // La cuota se paga exacta; el pozo es la suma de las cuotas
+    let paid = info.funds.iter()
+        .filter(|coin| coin.denom == DENOM)
+        .map(|coin| coin.amount)
+        .sum::<Uint128>();
+    if paid != race.entry_fee || info.funds.iter().any(|coin| coin.denom != DENOM) {
+        return Err(StdError::generic_err(format!(
+            "La inscripción cuesta exactamente {}{}",
+            race.entry_fee, DENOM
+        )));
+    }
+
+    // Corre el dueño o, mientras el carro esté alquilado, solo su inquilino
+    let car_contract = contract.car_contract.load(deps.storage)?;
+    let car_user: Option<CarUser> = deps.querier.query_wasm_smart(
+        car_contract.clone(),
+        &CarQueryMsg::UserOf { car_id },
+    )?;
+    match car_user {
+        Some(car_user) if car_user.user != info.sender => {
+            return Err(StdError::generic_err("El carro está alquilado a otro jugador"));
+        },
+        Some(_) => {},
+        None => {
+            let owner_cars: OwnerCarsResponse = deps.querier.query_wasm_smart(
+                car_contract.clone(),
+                &CarQueryMsg::GetOwnerCars { owner: info.sender.to_string() },
+            )?;
+            if !owner_cars.car_ids.contains(&car_id) {
+                return Err(StdError::generic_err("No eres el dueño de este carro"));
+            }
+        },
+    }
+
+    // Condición mínima del carro según el contrato de carros
+    let stats: CompactCarStats = deps.querier.query_wasm_smart(
+        car_contract,
+        &CarQueryMsg::GetCompactCarStats { car_id },
+    )?;
+    if stats.condition < race.min_condition {
+        return Err(StdError::generic_err(format!(
+            "La condición del carro ({}) es menor que la mínima de la carrera ({})",
+            stats.condition, race.min_condition
+        )));
+    }
+
+    // En carreras simuladas la inscripción incluye el commit del secreto de cada carro
+    match (race.randomness.as_mut(), commitment) {
+        (Some(round), Some(commitment)) => {
+            round.commit(&car_id.to_string(), &commitment, env.block.height).map_err(StdError::generic_err)?;
+        },
+        (Some(_), None) => return Err(StdError::generic_err("Esta carrera requiere el commit de un secreto")),
+        (None, Some(_)) => return Err(StdError::generic_err("Esta carrera no usa commit de secretos")),
+        (None, None) => {},
+    }
+
+    race.entrants.push(Entrant { car_id, owner: info.sender.clone() });
+    contract.races.save(deps.storage, race_id, &race)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "join_race")
+        .add_attribute("race_id", race_id.to_string())
+        .add_attribute("car_id", car_id.to_string())
+        .add_attribute("owner", info.sender))
+}
+
+fn execute_submit_results(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: RaceContract,
+    race_id: u64,
+    finishing_order: Vec<u64>,
+) -> StdResult<Response> {
+    // Solo el servidor de juego autorizado reporta resultados
+    let game_server = contract.game_server.load(deps.storage)?;
+    if info.sender != game_server {
+        return Err(StdError::generic_err("Solo el servidor de juego puede enviar resultados"));
+    }
+
+    let race = load_open_race(deps.as_ref(), &contract, race_id)?;
+    if race.mode != RaceMode::Reported {
+        return Err(StdError::generic_err("Esta carrera se simula on-chain con RunRace"));
+    }
+    check_finishing_order(&race, &finishing_order)?;
+
+    let paid_positions = finishing_order.len();
+    settle_race(deps, &env, &contract, race_id, race, finishing_order, paid_positions, "submit_results")
+}
+
+fn execute_reveal_seed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: RaceContract,
+    race_id: u64,
+    car_id: u64,
+    secret: String,
+) -> StdResult<Response> {
+    let mut race = load_open_race(deps.as_ref(), &contract, race_id)?;
+    if !race.entrants.iter().any(|entrant| entrant.car_id == car_id && entrant.owner == info.sender) {
+        return Err(StdError::generic_err("Solo el dueño inscrito puede revelar el secreto de su carro"));
+    }
+    let round = race.randomness
+        .as_mut()
+        .ok_or_else(|| StdError::generic_err("Esta carrera no usa commit de secretos"))?;
+    round.reveal(&car_id.to_string(), &secret, env.block.height).map_err(StdError::generic_err)?;
+    contract.races.save(deps.storage, race_id, &race)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "reveal_seed")
+        .add_attribute("race_id", race_id.to_string())
+        .add_attribute("car_id", car_id.to_string()))
+}
+
+// Simular la carrera cuando todos revelaron o venció el plazo. Cualquiera puede ejecutarla
+// y recalcular el resultado con `simulate_race` y la semilla publicada.
+fn execute_run_race(
+    deps: DepsMut,
+    env: Env,
+    contract: RaceContract,
+    race_id: u64,
+) -> StdResult<Response> {
+    let mut race = load_open_race(deps.as_ref(), &contract, race_id)?;
+    let track = match (&race.mode, &race.randomness) {
+        (RaceMode::Simulated { track, .. }, Some(_)) => track.clone(),
+        _ => return Err(StdError::generic_err("El resultado de esta carrera lo reporta el servidor de juego")),
+    };
+    let outcome = race.randomness
+        .as_ref()
+        .map(|round| round.finalize(env.block.height))
+        .transpose()
+        .map_err(StdError::generic_err)?
+        .expect("carrera simulada con ronda de commits");
+
+    // Quien no reveló queda descalificado: llega último, sin premio y su cuota queda en el pozo
+    race.disqualified = race.entrants
+        .iter()
+        .map(|entrant| entrant.car_id)
+        .filter(|car_id| outcome.non_revealers.contains(&car_id.to_string()))
+        .collect();
+
+    // Stats actuales de cada carro que corre según el contrato de carros. Un carro quemado
+    // después de inscribirse ya no responde: queda descalificado y no recibe desgaste.
+    let car_contract = contract.car_contract.load(deps.storage)?;
+    let mut runners = vec![];
+    let mut missing = vec![];
+    for entrant in race.entrants.iter().filter(|entrant| !race.disqualified.contains(&entrant.car_id)) {
+        let metadata: StdResult<FullCarMetadata> = deps.querier.query_wasm_smart(
+            car_contract.clone(),
+            &CarQueryMsg::GetFullCarMetadata { car_id: entrant.car_id },
+        );
+        match metadata {
+            Ok(metadata) => runners.push((entrant.car_id, metadata.total_stats)),
+            Err(_) => missing.push(entrant.car_id),
+        }
+    }
+    race.disqualified.extend(missing);
+    if runners.is_empty() {
+        return Err(StdError::generic_err("Ningún carro inscrito puede correr; la carrera solo puede cancelarse"));
+    }
+    race.results = simulate_race(&track, &outcome.seed, &runners);
+    let finishing_order: Vec<u64> = race.results
+        .iter()
+        .map(|result| result.car_id)
+        .chain(race.disqualified.iter().copied())
+        .collect();
+
+    // El desgaste de la pista llega a las partes de cada carro que corrió
+    let degrade_msgs = race.results
+        .iter()
+        .filter(|_| track.wear > 0)
+        .map(|result| -> StdResult<WasmMsg> {
+            Ok(WasmMsg::Execute {
+                contract_addr: car_contract.to_string(),
+                msg: to_json_binary(&CarExecuteMsg::DegradeCar { car_id: result.car_id, wear: track.wear })?,
+                funds: vec![],
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let paid_positions = race.results.len();
+    let disqualified = race.disqualified.len();
+    let response = settle_race(deps, &env, &contract, race_id, race, finishing_order, paid_positions, "run_race")?;
+    Ok(response
+        .add_messages(degrade_msgs)
+        .add_attribute("seed", to_hex(&outcome.seed))
+        .add_attribute("disqualified", disqualified.to_string()))
+}
+
+// Cancelar una carrera sin liquidar y devolver la cuota a cada inscrito. El admin puede hacerlo
+// en cualquier momento y cualquiera una vez pasado `cancel_after`, por si el servidor nunca reporta.
+fn execute_cancel_race(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: RaceContract,
+    race_id: u64,
+) -> StdResult<Response> {
+    let mut race = contract.races.load(deps.storage, race_id)?;
+    if race.status != RaceStatus::Open {
+        return Err(StdError::generic_err("La carrera ya fue liquidada o cancelada"));
+    }
+    let timed_out = race.cancel_after.is_some_and(|cancel_after| env.block.height >= cancel_after);
+    if !timed_out {
+        ensure_admin(deps.as_ref(), &contract, &info)?;
+    }
+
+    race.status = RaceStatus::Cancelled;
+    contract.races.save(deps.storage, race_id, &race)?;
+
+    let refunds = race.entrants
+        .iter()
+        .filter(|_| !race.entry_fee.is_zero())
+        .map(|entrant| BankMsg::Send {
+            to_address: entrant.owner.to_string(),
+            amount: coins(race.entry_fee.u128(), DENOM),
+        });
+
+    Ok(Response::new()
+        .add_messages(refunds)
+        .add_attribute("method", "cancel_race")
+        .add_attribute("race_id", race_id.to_string())
+        .add_attribute("refunded", race.entrants.len().to_string()))
+}
+
+// Tiempos de llegada deterministas: el ritmo combina los stats de recta y de curva según la pista,
+// la condición del carro lo reduce hasta la mitad y la semilla suma hasta un 5% de variación.
+// Empates por car_id.
+pub fn simulate_race(track: &TrackProfile, seed: &[u8], entrants: &[(u64, CompactCarStats)]) -> Vec<RaceResult> {
+    let mut results: Vec<RaceResult> = entrants
+        .iter()
+        .map(|(car_id, stats)| {
+            let straight = stats.speed as u64 + stats.max_speed as u64 + stats.acceleration as u64;
+            let corner = stats.handling as u64 + stats.turn_factor as u64 + stats.drift_factor as u64;
+            let pace = straight * track.straight_weight as u64 + corner * track.corner_weight as u64;
+            let pace = pace * (50 + stats.condition.min(100) as u64 / 2) / 100;
+            let base_time = track.length as u64 * 1_000_000 / (pace + 1);
+
+            let roll = Sha256::new().chain_update(seed).chain_update(car_id.to_be_bytes()).finalize();
+            let variation = u16::from_be_bytes([roll[0], roll[1]]) as u64 % 51;
+            RaceResult {
+                car_id: *car_id,
+                finish_time: base_time * (1000 + variation) / 1000,
+            }
+        })
+        .collect();
+    results.sort_by_key(|result| (result.finish_time, result.car_id));
+    results
+}
+
+fn load_open_race(deps: Deps, contract: &RaceContract, race_id: u64) -> StdResult<Race> {
+    let race = contract.races.load(deps.storage, race_id)?;
+    if race.status != RaceStatus::Open {
+        return Err(StdError::generic_err("La carrera ya fue liquidada o cancelada"));
+    }
+    if race.entrants.is_empty() {
+        return Err(StdError::generic_err("La carrera no tiene participantes"));
+    }
+    Ok(race)
+}
+
+// Cerrar la carrera con su orden de llegada y pagar los premios a los primeros `paid_positions` puestos
+#[allow(clippy::too_many_arguments)]
+fn settle_race(
+    deps: DepsMut,
+    env: &Env,
+    contract: &RaceContract,
+    race_id: u64,
+    mut race: Race,
+    finishing_order: Vec<u64>,
+    paid_positions: usize,
+    method: &str,
+) -> StdResult<Response> {
+    let config = contract.payout_config.load(deps.storage)?;
+    let (payouts, house_cut) = compute_payouts(&race, &finishing_order[..paid_positions], &config);
+    let winner = finishing_order[..paid_positions].first().map(u64::to_string).unwrap_or_default();
+
+    race.status = RaceStatus::Settled;
+    race.finishing_order = finishing_order;
+    race.payouts = payouts;
+    race.house_cut = house_cut;
+    contract.races.save(deps.storage, race_id, &race)?;
+    record_season_results(deps.storage, env, contract, &race, paid_positions)?;
+    contract.house_balance.update(deps.storage, |balance| -> StdResult<_> { Ok(balance + house_cut) })?;
+
+    let messages = race.payouts.iter().map(|payout| BankMsg::Send {
+        to_address: payout.owner.to_string(),
+        amount: coins(payout.amount.u128(), DENOM),
+    });
+    let mut response = Response::new().add_messages(messages);
+
+    // Estadísticas de cada jugador para su perfil
+    if let Some(profile_contract) = contract.profile_contract.may_load(deps.storage)? {
+        let results = race.finishing_order.iter().enumerate().map(|(position, &car_id)| PlayerResult {
+            player: race.entrants.iter()
+                .find(|entrant| entrant.car_id == car_id)
+                .map(|entrant| entrant.owner.to_string())
+                .expect("orden de llegada validado"),
+            won: position == 0 && paid_positions > 0,
+            finish_time: race.results.iter()
+                .find(|result| result.car_id == car_id)
+                .map(|result| result.finish_time),
+        }).collect();
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr: profile_contract.to_string(),
+            msg: to_json_binary(&ProfileExecuteMsg::RecordResults { track_id: race.track_id, results })?,
+            funds: vec![],
+        });
+    }
+
+    Ok(response
+        .add_attribute("method", method)
+        .add_attribute("race_id", race_id.to_string())
+        .add_attribute("winner", winner)
+        .add_attribute("house_cut", house_cut.to_string()))
+}
+
+// El orden de llegada debe incluir a cada carro inscrito exactamente una vez
+fn check_finishing_order(race: &Race, finishing_order: &[u64]) -> StdResult<()> {
+    let complete = finishing_order.len() == race.entrants.len()
+        && race.entrants.iter().all(|entrant| finishing_order.contains(&entrant.car_id));
+    if !complete {
+        return Err(StdError::generic_err("El orden de llegada debe incluir a cada carro inscrito una sola vez"));
+    }
+    Ok(())
+}
+
+// Repartir el pozo menos la comisión de la casa según los porcentajes de cada puesto premiable.
+// Si hay menos carros que puestos premiados, los porcentajes usados se reescalan al 100%.
+// El redondeo queda para la casa.
+fn compute_payouts(race: &Race, finishing_order: &[u64], config: &PayoutConfig) -> (Vec<Payout>, Uint128) {
+    let pool = race.entry_fee * Uint128::from(race.entrants.len() as u128);
+    let prizes = pool - pool.multiply_ratio(config.house_fee as u128, 100u128);
+
+    let mut payouts = vec![];
+    for (&car_id, amount) in finishing_order.iter().zip(split_amount(prizes, &config.splits, finishing_order.len())) {
+        if amount.is_zero() {
+            continue;
+        }
+        let owner = race.entrants.iter()
+            .find(|entrant| entrant.car_id == car_id)
+            .map(|entrant| entrant.owner.clone())
+            .expect("orden de llegada validado");
+        payouts.push(Payout { car_id, owner, amount });
+    }
+
+    let paid: Uint128 = payouts.iter().map(|payout| payout.amount).sum();
+    (payouts, pool - paid)
+}
+
+// Repartir `total` entre los primeros `count` porcentajes, reescalados para sumar el 100%
+fn split_amount(total: Uint128, splits: &[u8], count: usize) -> Vec<Uint128> {
+    let used_splits = &splits[..splits.len().min(count)];
+    let total_split: u128 = used_splits.iter().map(|&split| split as u128).sum();
+    used_splits
+        .iter()
+        .map(|&split| total.multiply_ratio(split as u128, total_split.max(1)))
+        .collect()
+}
+
+fn execute_set_payout_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: RaceContract,
+    config: PayoutConfig,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    let total: u32 = config.splits.iter().map(|&split| split as u32).sum();
+    if config.splits.is_empty() || total != 100 {
+        return Err(StdError::generic_err("Los porcentajes por puesto deben sumar 100"));
+    }
+    if config.house_fee > 100 {
+        return Err(StdError::generic_err("La comisión de la casa no puede superar el 100%"));
+    }
+    contract.payout_config.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_payout_config")
+        .add_attribute("splits", format!("{:?}", config.splits))
+        .add_attribute("house_fee", config.house_fee.to_string()))
+}
+
+fn execute_set_game_server(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: RaceContract,
+    address: String,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+    let game_server = deps.api.addr_validate(&address)?;
+    contract.game_server.save(deps.storage, &game_server)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_game_server")
+        .add_attribute("address", address))
+}
+
+fn execute_set_profile_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: RaceContract,
+    address: String,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+    let profile_contract = deps.api.addr_validate(&address)?;
+    contract.profile_contract.save(deps.storage, &profile_contract)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_profile_contract")
+        .add_attribute("address", address))
+}
+
+// El admin retira solo las comisiones acumuladas; las cuotas de carreras abiertas siguen en el contrato
+fn execute_withdraw_funds(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: RaceContract,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    let amount = contract.house_balance.load(deps.storage)?;
+    if amount.is_zero() {
+        return Err(StdError::generic_err("No hay comisiones para retirar"));
+    }
+    contract.house_balance.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), DENOM),
+        })
+        .add_attribute("method", "withdraw_funds")
+        .add_attribute("recipient", info.sender)
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_create_season(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: RaceContract,
+    period: SeasonPeriod,
+    points: Vec<u32>,
+    reward_splits: Vec<u8>,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    // Una temporada a la vez: la anterior debe estar cerrada
+    let previous = contract.current_season.may_load(deps.storage)?.unwrap_or_default();
+    if previous > 0 && !contract.seasons.load(deps.storage, previous)?.closed {
+        return Err(StdError::generic_err("La temporada actual todavía no se cerró"));
+    }
+    let valid_period = match &period {
+        SeasonPeriod::Blocks { start, end } => start < end,
+        SeasonPeriod::Time { start, end } => start < end,
+    };
+    if !valid_period || period.has_ended(&env.block) {
+        return Err(StdError::generic_err("La temporada debe terminar en el futuro y después de empezar"));
+    }
+    if points.is_empty() {
+        return Err(StdError::generic_err("La tabla de puntos no puede estar vacía"));
+    }
+    let total: u32 = reward_splits.iter().map(|&split| split as u32).sum();
+    if reward_splits.is_empty() || total != 100 {
+        return Err(StdError::generic_err("Los porcentajes de premio deben sumar 100"));
+    }
+
+    let season_id = previous + 1;
+    let season = Season {
+        period,
+        points,
+        reward_splits,
+        reward_pool: paid_amount(&info),
+        closed: false,
+        snapshot: vec![],
+    };
+    contract.seasons.save(deps.storage, season_id, &season)?;
+    contract.current_season.save(deps.storage, &season_id)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "create_season")
+        .add_attribute("season_id", season_id.to_string())
+        .add_attribute("reward_pool", season.reward_pool.to_string()))
+}
+
+// Cualquiera puede aportar uxion al premio de una temporada abierta
+fn execute_fund_season(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: RaceContract,
+    season_id: u64,
+) -> StdResult<Response> {
+    let mut season = contract.seasons.load(deps.storage, season_id)?;
+    if season.closed {
+        return Err(StdError::generic_err("La temporada ya se cerró"));
+    }
+    let amount = paid_amount(&info);
+    if amount.is_zero() {
+        return Err(StdError::generic_err("No se encontró el pago en uxion"));
+    }
+    season.reward_pool += amount;
+    contract.seasons.save(deps.storage, season_id, &season)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "fund_season")
+        .add_attribute("season_id", season_id.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("reward_pool", season.reward_pool.to_string()))
+}
+
+// Cerrar una temporada terminada: guardar el top N de cada pista y repartirle el premio.
+// El premio se divide en partes iguales entre las pistas con carreras; el redondeo, o todo
+// el premio si nadie corrió, queda para la casa.
+fn execute_close_season(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: RaceContract,
+    season_id: u64,
+) -> StdResult<Response> {
+    ensure_admin(deps.as_ref(), &contract, &info)?;
+
+    let mut season = contract.seasons.load(deps.storage, season_id)?;
+    if season.closed {
+        return Err(StdError::generic_err("La temporada ya se cerró"));
+    }
+    if !season.period.has_ended(&env.block) {
+        return Err(StdError::generic_err("La temporada todavía no terminó"));
+    }
+
+    let mut track_ids: Vec<u64> = contract.standings
+        .sub_prefix(season_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|key| key.map(|(track_id, _)| track_id))
+        .collect::<StdResult<_>>()?;
+    track_ids.dedup();
+
+    let track_pool = match track_ids.len() {
+        0 => Uint128::zero(),
+        tracks => season.reward_pool.multiply_ratio(1u128, tracks as u128),
+    };
+    let mut paid = Uint128::zero();
+    let mut messages = vec![];
+    for track_id in track_ids {
+        let ranking = track_ranking(deps.storage, &contract, season_id, track_id)?;
+        let rewards = split_amount(track_pool, &season.reward_splits, ranking.len());
+        let entries: Vec<SeasonReward> = ranking
+            .into_iter()
+            .zip(rewards)
+            .enumerate()
+            .map(|(index, (standing, reward))| SeasonReward { rank: index as u32 + 1, standing, reward })
+            .collect();
+        for entry in entries.iter().filter(|entry| !entry.reward.is_zero()) {
+            paid += entry.reward;
+            messages.push(BankMsg::Send {
+                to_address: entry.standing.owner.to_string(),
+                amount: coins(entry.reward.u128(), DENOM),
+            });
+        }
+        season.snapshot.push(TrackSnapshot { track_id, entries });
+    }
+
+    let remainder = season.reward_pool - paid;
+    contract.house_balance.update(deps.storage, |balance| -> StdResult<_> { Ok(balance + remainder) })?;
+    season.closed = true;
+    contract.seasons.save(deps.storage, season_id, &season)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "close_season")
+        .add_attribute("season_id", season_id.to_string())
+        .add_attribute("paid", paid.to_string()))
+}
+
+// Sumar el resultado de una carrera a la temporada vigente. Puntúan los `classified` primeros
+// según la tabla de la temporada; los descalificados solo suman la carrera.
+fn record_season_results(
+    storage: &mut dyn Storage,
+    env: &Env,
+    contract: &RaceContract,
+    race: &Race,
+    classified: usize,
+) -> StdResult<()> {
+    let season_id = match contract.current_season.may_load(storage)? {
+        Some(season_id) => season_id,
+        None => return Ok(()),
+    };
+    let season = contract.seasons.load(storage, season_id)?;
+    if season.closed || !season.period.is_active(&env.block) {
+        return Ok(());
+    }
+
+    for (position, &car_id) in race.finishing_order.iter().enumerate() {
+        let owner = race.entrants.iter()
+            .find(|entrant| entrant.car_id == car_id)
+            .map(|entrant| entrant.owner.clone())
+            .expect("orden de llegada validado");
+        let key = (season_id, race.track_id, car_id);
+        let mut standing = contract.standings.may_load(storage, key)?.unwrap_or(Standing {
+            car_id,
+            owner: owner.clone(),
+            points: 0,
+            races: 0,
+            wins: 0,
+            best_time: None,
+        });
+        standing.owner = owner;
+        standing.races += 1;
+        if position < classified {
+            standing.points += season.points.get(position).copied().unwrap_or_default();
+            if position == 0 {
+                standing.wins += 1;
+            }
+            if let Some(result) = race.results.iter().find(|result| result.car_id == car_id) {
+                standing.best_time = Some(standing.best_time.map_or(result.finish_time, |best| best.min(result.finish_time)));
+            }
+        }
+        contract.standings.save(storage, key, &standing)?;
+    }
+    Ok(())
+}
+
+// Clasificación de una pista: más puntos, más victorias, mejor tiempo y por último car_id
+fn track_ranking(storage: &dyn Storage, contract: &RaceContract, season_id: u64, track_id: u64) -> StdResult<Vec<Standing>> {
+    let mut ranking: Vec<Standing> = contract.standings
+        .prefix((season_id, track_id))
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, standing)| standing))
+        .collect::<StdResult<_>>()?;
+    ranking.sort_by_key(|standing| {
+        (
+            std::cmp::Reverse(standing.points),
+            std::cmp::Reverse(standing.wins),
+            standing.best_time.unwrap_or(u64::MAX),
+            standing.car_id,
+        )
+    });
+    Ok(ranking)
+}
+
+fn paid_amount(info: &MessageInfo) -> Uint128 {
+    info.funds.iter()
+        .filter(|coin| coin.denom == DENOM)
+        .map(|coin| coin.amount)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+    use car_types::randomness;
+    use cosmwasm_std::{from_json, CosmosMsg, MemoryStorage, OwnedDeps, SystemResult, ContractResult, WasmQuery};
+
+    // Simula el contrato de carros: el carro N pertenece a "ownerN" y su condición es N * 10.
+    // El carro 10 está alquilado a "renter10" y el carro 8 se quemó después de inscribirse.
+    fn mock_car_contract(deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier>) {
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { msg, .. } => {
+                let res = match from_json::<CarQueryMsg>(msg).unwrap() {
+                    CarQueryMsg::GetFullCarMetadata { car_id: 8 } => {
+                        return SystemResult::Ok(ContractResult::Err("El carro no existe".to_string()));
+                    },
+                    CarQueryMsg::GetOwnerCars { owner } => {
+                        let car_id: u64 = owner.trim_start_matches("owner").parse().unwrap();
+                        to_json_binary(&OwnerCarsResponse { car_ids: vec![car_id] })
+                    },
+                    CarQueryMsg::GetCompactCarStats { car_id } => to_json_binary(&car_stats(car_id)),
+                    CarQueryMsg::GetFullCarMetadata { car_id } => to_json_binary(&FullCarMetadata {
+                        car_id,
+                        total_stats: car_stats(car_id),
+                    }),
+                    CarQueryMsg::UserOf { car_id } => to_json_binary(
+                        &(car_id == 10).then(|| CarUser { user: Addr::unchecked("renter10") })
+                    ),
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            },
+            _ => panic!("Consulta inesperada"),
+        });
+    }
+
+    fn car_stats(car_id: u64) -> CompactCarStats {
+        CompactCarStats {
+            image_uri: "ipfs://car".to_string(),
+            speed: 5,
+            acceleration: 5,
+            handling: 5,
+            drift_factor: 5,
+            turn_factor: 5,
+            max_speed: 10,
+            condition: (car_id * 10) as u8,
+        }
+    }
+
+    fn setup() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        mock_car_contract(&mut deps);
+        let msg = InstantiateMsg {
+            car_contract: "car_contract".to_string(),
+            game_server: "game_server".to_string(),
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn create_race(deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier>, race_id: u64) {
+        let msg = ExecuteMsg::CreateRace {
+            race_id,
+            entry_fee: Uint128::new(1000),
+            min_condition: 30,
+            max_entrants: 3,
+            mode: RaceMode::Reported,
+            track_id: 1,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn join(deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier>, race_id: u64, car_id: u64) -> StdResult<Response> {
+        let info = mock_info(&format!("owner{}", car_id), &coins(1000, DENOM));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::JoinRace { race_id, car_id, commitment: None })
+    }
+
+    #[test]
+    fn test_join_race() {
+        let mut deps = setup();
+
+        let msg = ExecuteMsg::CreateRace { race_id: 1, entry_fee: Uint128::new(1000), min_condition: 30, max_entrants: 3, mode: RaceMode::Reported, track_id: 1 };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("other", &[]), msg).is_err());
+        create_race(&mut deps, 1);
+
+        // Pago incorrecto, carro ajeno o con condición por debajo del mínimo
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner5", &coins(999, DENOM)), ExecuteMsg::JoinRace { race_id: 1, car_id: 5, commitment: None }).unwrap_err();
+        assert!(err.to_string().contains("exactamente 1000uxion"));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner4", &coins(1000, DENOM)), ExecuteMsg::JoinRace { race_id: 1, car_id: 5, commitment: None }).unwrap_err();
+        assert!(err.to_string().contains("No eres el dueño"));
+        let err = join(&mut deps, 1, 2).unwrap_err();
+        assert!(err.to_string().contains("La condición del carro (20)"));
+
+        let _res = join(&mut deps, 1, 5).unwrap();
+        assert!(join(&mut deps, 1, 5).is_err());
+        let _res = join(&mut deps, 1, 6).unwrap();
+        let _res = join(&mut deps, 1, 7).unwrap();
+        let err = join(&mut deps, 1, 8).unwrap_err();
+        assert!(err.to_string().contains("La carrera está llena"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetRace { race_id: 1 }).unwrap();
+        let race: Race = from_json(&res).unwrap();
+        assert_eq!(vec![5, 6, 7], race.entrants.iter().map(|e| e.car_id).collect::<Vec<_>>());
+        assert_eq!(Addr::unchecked("owner6"), race.entrants[1].owner);
+
+        // Un carro alquilado lo corre su inquilino, no su dueño
+        create_race(&mut deps, 2);
+        let err = join(&mut deps, 2, 10).unwrap_err();
+        assert!(err.to_string().contains("alquilado a otro jugador"));
+        let info = mock_info("renter10", &coins(1000, DENOM));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::JoinRace { race_id: 2, car_id: 10, commitment: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetRace { race_id: 2 }).unwrap();
+        let race: Race = from_json(&res).unwrap();
+        assert_eq!(Addr::unchecked("renter10"), race.entrants[0].owner);
+    }
+
+    #[test]
+    fn test_submit_results() {
+        let mut deps = setup();
+        create_race(&mut deps, 1);
+        for car_id in [4, 5, 6] {
+            let _res = join(&mut deps, 1, car_id).unwrap();
+        }
+
+        // Solo el servidor de juego, y con todos los inscritos una sola vez
+        let submit = |order: Vec<u64>| ExecuteMsg::SubmitResults { race_id: 1, finishing_order: order };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("owner4", &[]), submit(vec![4, 5, 6])).is_err());
+        let server = mock_info("game_server", &[]);
+        assert!(execute(deps.as_mut(), mock_env(), server.clone(), submit(vec![4, 5])).is_err());
+        assert!(execute(deps.as_mut(), mock_env(), server.clone(), submit(vec![4, 4, 6])).is_err());
+
+        // Pozo 3000, casa 5% = 150; 2850 repartidos 60/30/10
+        let res = execute(deps.as_mut(), mock_env(), server.clone(), submit(vec![6, 4, 5])).unwrap();
+        let sends: Vec<(String, u128)> = res.messages.iter().map(|msg| match &msg.msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => (to_address.clone(), amount[0].amount.u128()),
+            other => panic!("Mensaje inesperado: {:?}", other),
+        }).collect();
+        assert_eq!(
+            vec![("owner6".to_string(), 1710), ("owner4".to_string(), 855), ("owner5".to_string(), 285)],
+            sends
+        );
+        assert!(execute(deps.as_mut(), mock_env(), server, submit(vec![6, 4, 5])).is_err());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetRace { race_id: 1 }).unwrap();
+        let race: Race = from_json(&res).unwrap();
+        assert_eq!(RaceStatus::Settled, race.status);
+        assert_eq!(Uint128::new(150), race.house_cut);
+        assert!(join(&mut deps, 1, 7).is_err());
+
+        // El admin retira solo las comisiones
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("other", &[]), ExecuteMsg::WithdrawFunds {}).is_err());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::WithdrawFunds {}).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send { to_address: "creator".to_string(), amount: coins(150, DENOM) }),
+            res.messages[0].msg
+        );
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::WithdrawFunds {}).is_err());
+    }
+
+    #[test]
+    fn test_payout_config() {
+        let mut deps = setup();
+        let set_config = |splits: Vec<u8>, house_fee| ExecuteMsg::SetPayoutConfig { splits, house_fee };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("other", &[]), set_config(vec![100], 0)).is_err());
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_config(vec![50, 40], 0)).is_err());
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_config(vec![100], 101)).is_err());
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_config(vec![50, 30, 20], 10)).unwrap();
+
+        // Con dos carros y tres puestos premiados, 50/30 se reescala sobre 80
+        create_race(&mut deps, 1);
+        let _res = join(&mut deps, 1, 3).unwrap();
+        let _res = join(&mut deps, 1, 4).unwrap();
+        let msg = ExecuteMsg::SubmitResults { race_id: 1, finishing_order: vec![3, 4] };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("game_server", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetRace { race_id: 1 }).unwrap();
+        let race: Race = from_json(&res).unwrap();
+        let amounts: Vec<u128> = race.payouts.iter().map(|p| p.amount.u128()).collect();
+        assert_eq!(vec![1125, 675], amounts);
+        assert_eq!(Uint128::new(200), race.house_cut);
+    }
+
+    #[test]
+    fn test_profile_results() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::SetProfileContract { address: "profile_contract".to_string() };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("other", &[]), msg.clone()).is_err());
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        create_race(&mut deps, 1);
+        for car_id in [4, 5] {
+            let _res = join(&mut deps, 1, car_id).unwrap();
+        }
+        let msg = ExecuteMsg::SubmitResults { race_id: 1, finishing_order: vec![5, 4] };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game_server", &[]), msg).unwrap();
+
+        // Después de los premios, un solo mensaje al contrato de perfiles con cada jugador
+        let expected = ProfileExecuteMsg::RecordResults {
+            track_id: 1,
+            results: vec![
+                PlayerResult { player: "owner5".to_string(), won: true, finish_time: None },
+                PlayerResult { player: "owner4".to_string(), won: false, finish_time: None },
+            ],
+        };
+        assert_eq!(3, res.messages.len());
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "profile_contract".to_string(),
+                msg: to_json_binary(&expected).unwrap(),
+                funds: vec![],
+            }),
+            res.messages[2].msg
+        );
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    #[test]
+    fn test_simulated_race() {
+        let mut deps = setup();
+        let height = mock_env().block.height;
+        let track = TrackProfile { length: 1000, straight_weight: 70, corner_weight: 30, wear: 5 };
+        let create = |race_id, track: TrackProfile, entry_deadline| ExecuteMsg::CreateRace {
+            race_id,
+            entry_fee: Uint128::new(1000),
+            min_condition: 0,
+            max_entrants: 4,
+            mode: RaceMode::Simulated { track, entry_deadline, reveal_window: 10 },
+            track_id: 2,
+        };
+        let admin = mock_info("creator", &[]);
+        let bad_track = TrackProfile { straight_weight: 80, ..track.clone() };
+        assert!(execute(deps.as_mut(), mock_env(), admin.clone(), create(1, bad_track, height + 5)).is_err());
+        assert!(execute(deps.as_mut(), mock_env(), admin.clone(), create(1, track.clone(), height)).is_err());
+        let _res = execute(deps.as_mut(), mock_env(), admin, create(1, track.clone(), height + 5)).unwrap();
+
+        // Cada inscrito compromete un secreto; sin commit no hay inscripción
+        assert!(join(&mut deps, 1, 2).is_err());
+        let join_with_commit = |deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier>, car_id: u64, env: Env| {
+            let info = mock_info(&format!("owner{}", car_id), &coins(1000, DENOM));
+            let commitment = Some(randomness::commitment_of(&format!("secreto{}", car_id)));
+            execute(deps.as_mut(), env, info, ExecuteMsg::JoinRace { race_id: 1, car_id, commitment })
+        };
+        for car_id in [2, 9, 5, 7] {
+            let _res = join_with_commit(&mut deps, car_id, env_at(height + 5)).unwrap();
+        }
+        let reveal = |car_id: u64, secret: &str| ExecuteMsg::RevealSeed { race_id: 1, car_id, secret: secret.to_string() };
+        let owner = |car_id: u64| mock_info(&format!("owner{}", car_id), &[]);
+
+        // Los secretos se revelan después del cierre y antes del plazo, cada uno por su dueño
+        let err = execute(deps.as_mut(), env_at(height + 5), owner(2), reveal(2, "secreto2")).unwrap_err();
+        assert!(err.to_string().contains("después del plazo de commits"));
+        assert!(execute(deps.as_mut(), env_at(height + 6), owner(9), reveal(2, "secreto2")).is_err());
+        let err = execute(deps.as_mut(), env_at(height + 6), owner(2), reveal(2, "otro")).unwrap_err();
+        assert!(err.to_string().contains("El secreto no corresponde al commit"));
+        for car_id in [2, 9, 5] {
+            let _res = execute(deps.as_mut(), env_at(height + 6), owner(car_id), reveal(car_id, &format!("secreto{}", car_id))).unwrap();
+        }
+        assert!(execute(deps.as_mut(), env_at(height + 16), owner(7), reveal(7, "secreto7")).is_err());
+
+        // El servidor no reporta carreras simuladas y la carrera espera al plazo mientras falte alguien
+        let msg = ExecuteMsg::SubmitResults { race_id: 1, finishing_order: vec![2, 9, 5, 7] };
+        assert!(execute(deps.as_mut(), env_at(height + 16), mock_info("game_server", &[]), msg).is_err());
+        let run = ExecuteMsg::RunRace { race_id: 1 };
+        let err = execute(deps.as_mut(), env_at(height + 15), mock_info("anyone", &[]), run.clone()).unwrap_err();
+        assert!(err.to_string().contains("La ronda sigue abierta a revelaciones"));
+        let env = env_at(height + 16);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), run.clone()).unwrap();
+
+        // Cualquiera puede recalcular el resultado: la semilla sale solo de los secretos revelados y
+        // los plazos de la ronda, así que no cambia con el bloque en que se ejecuta RunRace
+        let race: Race = from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetRace { race_id: 1 }).unwrap()).unwrap();
+        let round = race.randomness.as_ref().unwrap();
+        let seed = round.finalize(env.block.height).unwrap().seed;
+        assert_eq!(seed, round.finalize(env.block.height + 100).unwrap().seed);
+        let runners: Vec<(u64, CompactCarStats)> = [2, 9, 5].iter().map(|&car_id| (car_id, car_stats(car_id))).collect();
+        assert_eq!(simulate_race(&track, &seed, &runners), race.results);
+        assert!(res.attributes.iter().any(|a| a.key == "seed" && a.value == to_hex(&seed)));
+        assert_eq!(RaceStatus::Settled, race.status);
+        assert!(race.results.windows(2).all(|pair| pair[0].finish_time <= pair[1].finish_time));
+
+        // La condición pesa más que la variación; el carro que no reveló llega último y sin premio
+        assert_eq!(vec![9, 5, 2, 7], race.finishing_order);
+        assert_eq!(vec![7], race.disqualified);
+        assert_eq!(vec![9, 5, 2], race.payouts.iter().map(|p| p.car_id).collect::<Vec<_>>());
+
+        // Desgaste solo para los carros que corrieron
+        let degrades: Vec<u64> = res.messages.iter().filter_map(|msg| match &msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => {
+                assert_eq!("car_contract", contract_addr);
+                match from_json::<CarExecuteMsg>(msg).unwrap() {
+                    CarExecuteMsg::DegradeCar { car_id, wear } => {
+                        assert_eq!(5, wear);
+                        Some(car_id)
+                    },
+                }
+            },
+            _ => None,
+        }).collect();
+        assert_eq!(vec![9, 5, 2], degrades);
+        assert!(execute(deps.as_mut(), env, mock_info("anyone", &[]), run).is_err());
+    }
+
+    #[test]
+    fn test_cancel_race() {
+        let mut deps = setup();
+        create_race(&mut deps, 1);
+        let _res = join(&mut deps, 1, 3).unwrap();
+        let _res = join(&mut deps, 1, 4).unwrap();
+        let cancel = ExecuteMsg::CancelRace { race_id: 1 };
+
+        // Antes del plazo solo el admin cancela; después, cualquiera
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), cancel.clone()).unwrap_err();
+        assert!(err.to_string().contains("Solo el admin"));
+        let env = env_at(mock_env().block.height + RACE_TIMEOUT_BLOCKS);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), cancel.clone()).unwrap();
+        let refunds: Vec<CosmosMsg> = ["owner3", "owner4"].iter().map(|owner| CosmosMsg::Bank(BankMsg::Send {
+            to_address: owner.to_string(),
+            amount: coins(1000, DENOM),
+        })).collect();
+        assert_eq!(refunds, res.messages.iter().map(|msg| msg.msg.clone()).collect::<Vec<_>>());
+
+        let race: Race = from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetRace { race_id: 1 }).unwrap()).unwrap();
+        assert_eq!(RaceStatus::Cancelled, race.status);
+        assert!(execute(deps.as_mut(), env, mock_info("creator", &[]), cancel).is_err());
+        assert!(join(&mut deps, 1, 5).is_err());
+        let msg = ExecuteMsg::SubmitResults { race_id: 1, finishing_order: vec![3, 4] };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("game_server", &[]), msg).is_err());
+
+        // El admin puede cancelar en cualquier momento, aunque nadie se haya inscrito
+        create_race(&mut deps, 2);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::CancelRace { race_id: 2 }).unwrap();
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn test_burned_car_is_disqualified() {
+        let mut deps = setup();
+        let height = mock_env().block.height;
+        let track = TrackProfile { length: 1000, straight_weight: 50, corner_weight: 50, wear: 5 };
+        let msg = ExecuteMsg::CreateRace {
+            race_id: 1,
+            entry_fee: Uint128::new(1000),
+            min_condition: 0,
+            max_entrants: 2,
+            mode: RaceMode::Simulated { track, entry_deadline: height + 5, reveal_window: 10 },
+            track_id: 0,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for car_id in [3, 8] {
+            let info = mock_info(&format!("owner{}", car_id), &coins(1000, DENOM));
+            let commitment = Some(randomness::commitment_of(&format!("secreto{}", car_id)));
+            let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::JoinRace { race_id: 1, car_id, commitment }).unwrap();
+            let reveal = ExecuteMsg::RevealSeed { race_id: 1, car_id, secret: format!("secreto{}", car_id) };
+            let _res = execute(deps.as_mut(), env_at(height + 6), mock_info(&format!("owner{}", car_id), &[]), reveal).unwrap();
+        }
+
+        // El carro 8 ya no existe: la carrera corre sin él, no lo degrada y no cobra premio
+        let res = execute(deps.as_mut(), env_at(height + 6), mock_info("anyone", &[]), ExecuteMsg::RunRace { race_id: 1 }).unwrap();
+        let race: Race = from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetRace { race_id: 1 }).unwrap()).unwrap();
+        assert_eq!(vec![8], race.disqualified);
+        assert_eq!(vec![3, 8], race.finishing_order);
+        assert_eq!(vec![3], race.payouts.iter().map(|p| p.car_id).collect::<Vec<_>>());
+        let degraded = res.messages.iter().filter(|msg| matches!(&msg.msg, CosmosMsg::Wasm(_))).count();
+        assert_eq!(1, degraded);
+    }
+
+    #[test]
+    fn test_seasons() {
+        let mut deps = setup();
+        let admin = mock_info("creator", &coins(1000, DENOM));
+        let create = ExecuteMsg::CreateSeason {
+            period: SeasonPeriod::Blocks { start: 12000, end: 12400 },
+            points: vec![10, 6, 3],
+            reward_splits: vec![70, 30],
+        };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("other", &coins(1000, DENOM)), create.clone()).is_err());
+        let bad_splits = ExecuteMsg::CreateSeason {
+            period: SeasonPeriod::Blocks { start: 12000, end: 12400 },
+            points: vec![10],
+            reward_splits: vec![70, 20],
+        };
+        assert!(execute(deps.as_mut(), mock_env(), admin.clone(), bad_splits).is_err());
+        let _res = execute(deps.as_mut(), mock_env(), admin.clone(), create.clone()).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), admin, create).unwrap_err();
+        assert!(err.to_string().contains("todavía no se cerró"));
+        let fund = ExecuteMsg::FundSeason { season_id: 1 };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("sponsor", &coins(1, DENOM)), fund).unwrap();
+
+        // Dos carreras en la pista 1 y una en la pista 3
+        let server = mock_info("game_server", &[]);
+        for (race_id, track_id, order) in [(1, 1, vec![6, 4, 5]), (2, 1, vec![4, 6, 5]), (3, 3, vec![7, 5, 6])] {
+            let msg = ExecuteMsg::CreateRace { race_id, entry_fee: Uint128::new(1000), min_condition: 30, max_entrants: 3, mode: RaceMode::Reported, track_id };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            for &car_id in &order {
+                let _res = join(&mut deps, race_id, car_id).unwrap();
+            }
+            let msg = ExecuteMsg::SubmitResults { race_id, finishing_order: order };
+            let _res = execute(deps.as_mut(), mock_env(), server.clone(), msg).unwrap();
+        }
+
+        // Empate en puntos y victorias: desempata el car_id
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetTrackRanking { season_id: 1, track_id: 1, limit: None }).unwrap();
+        let ranking: Vec<Standing> = from_json(&res).unwrap();
+        assert_eq!(
+            vec![(4, 16, 1), (6, 16, 1), (5, 6, 0)],
+            ranking.iter().map(|s| (s.car_id, s.points, s.wins)).collect::<Vec<_>>()
+        );
+        assert!(ranking.iter().all(|s| s.races == 2));
+
+        // Solo después del último bloque de la temporada
+        let close = ExecuteMsg::CloseSeason { season_id: 1 };
+        let err = execute(deps.as_mut(), env_at(12400), mock_info("creator", &[]), close.clone()).unwrap_err();
+        assert!(err.to_string().contains("todavía no terminó"));
+        assert!(execute(deps.as_mut(), env_at(12401), mock_info("other", &[]), close.clone()).is_err());
+
+        // Premio 1001 entre dos pistas: 500 cada una, 70/30 para el top 2; el sobrante va a la casa
+        let res = execute(deps.as_mut(), env_at(12401), mock_info("creator", &[]), close.clone()).unwrap();
+        let sends: Vec<(String, u128)> = res.messages.iter().map(|msg| match &msg.msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => (to_address.clone(), amount[0].amount.u128()),
+            other => panic!("Mensaje inesperado: {:?}", other),
+        }).collect();
+        assert_eq!(
+            vec![("owner4".to_string(), 350), ("owner6".to_string(), 150), ("owner7".to_string(), 350), ("owner5".to_string(), 150)],
+            sends
+        );
+        assert!(execute(deps.as_mut(), env_at(12401), mock_info("creator", &[]), close).is_err());
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetHouseBalance {}).unwrap();
+        assert_eq!(Uint128::new(3 * 150 + 1), from_json::<Uint128>(&res).unwrap());
+
+        // La temporada cerrada sigue consultable con su foto del top N
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetSeason { season_id: 1 }).unwrap();
+        let season: Season = from_json(&res).unwrap();
+        assert!(season.closed);
+        assert_eq!(vec![1, 3], season.snapshot.iter().map(|t| t.track_id).collect::<Vec<_>>());
+        assert_eq!(2, season.snapshot[0].entries.len());
+        assert_eq!((1, 4), (season.snapshot[0].entries[0].rank, season.snapshot[0].entries[0].standing.car_id));
+
+        // Las carreras fuera de la temporada no puntúan
+        let create = ExecuteMsg::CreateSeason {
+            period: SeasonPeriod::Blocks { start: 13000, end: 14000 },
+            points: vec![10],
+            reward_splits: vec![100],
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), create).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetCurrentSeason {}).unwrap();
+        assert_eq!(Some(2), from_json::<Option<u64>>(&res).unwrap());
+        create_race(&mut deps, 4);
+        for car_id in [4, 5, 6] {
+            let _res = join(&mut deps, 4, car_id).unwrap();
+        }
+        let msg = ExecuteMsg::SubmitResults { race_id: 4, finishing_order: vec![4, 5, 6] };
+        let _res = execute(deps.as_mut(), mock_env(), server, msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetTrackRanking { season_id: 2, track_id: 1, limit: Some(5) }).unwrap();
+        assert!(from_json::<Vec<Standing>>(&res).unwrap().is_empty());
+    }
+}
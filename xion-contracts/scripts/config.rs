@@ -0,0 +1,251 @@
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Directorio con un perfil TOML por red (networks/<nombre>.toml)
+pub const NETWORKS_DIR: &str = "networks";
+
+// Variables de entorno con secretos. Nunca se guardan en el perfil ni se pasan como argumentos.
+pub const MNEMONIC_VAR: &str = "XION_MNEMONIC";
+pub const KEYRING_PASSWORD_VAR: &str = "XION_KEYRING_PASSWORD";
+
+// Forma de compilar los contratos a wasm
+#[derive(Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildMode {
+    // rust-optimizer en un contenedor sin sudo (docker rootless o podman)
+    #[default]
+    Optimizer,
+    // `cargo build --target wasm32-unknown-unknown` local, sin contenedores
+    Cargo,
+}
+
+// Perfil de despliegue de una red
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct NetworkProfile {
+    #[serde(default)]
+    pub name: String,
+    pub chain_id: String,
+    pub node_url: String,
+    pub gas_prices: String,
+    #[serde(default = "default_gas_adjustment")]
+    pub gas_adjustment: String,
+    #[serde(default = "default_keyring_backend")]
+    pub keyring_backend: String,
+    #[serde(default)]
+    pub keyring_home: Option<String>,
+    #[serde(default = "default_wallet_name")]
+    pub wallet_name: String,
+    #[serde(default = "default_mint_price")]
+    pub mint_price: String,
+    #[serde(default)]
+    pub workshop_contract: Option<String>,
+    // Servidor de juego que reporta resultados al contrato de carreras; por defecto el deployer
+    #[serde(default)]
+    pub game_server: Option<String>,
+    #[serde(default)]
+    pub build: BuildMode,
+    #[serde(default = "default_optimizer_engine")]
+    pub optimizer_engine: String,
+    #[serde(default = "default_optimizer_image")]
+    pub optimizer_image: String,
+    #[serde(default = "default_env_file")]
+    pub env_file: String,
+}
+
+fn default_gas_adjustment() -> String {
+    "1.3".to_string()
+}
+
+fn default_keyring_backend() -> String {
+    "test".to_string()
+}
+
+fn default_wallet_name() -> String {
+    "wallet".to_string()
+}
+
+fn default_mint_price() -> String {
+    "100".to_string()
+}
+
+fn default_optimizer_engine() -> String {
+    "docker".to_string()
+}
+
+fn default_optimizer_image() -> String {
+    "cosmwasm/rust-optimizer:0.16.0".to_string()
+}
+
+fn default_env_file() -> String {
+    "../xion/.env".to_string()
+}
+
+impl NetworkProfile {
+    // Cargar el perfil de una red desde networks/<nombre>.toml y aplicar las
+    // variables de entorno DEPLOY_*. Sin archivo TOML el perfil sale solo del entorno.
+    pub fn load(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = Path::new(NETWORKS_DIR).join(format!("{}.toml", name));
+        let mut profile = if path.exists() {
+            Self::from_toml(&fs::read_to_string(&path)?)?
+        } else {
+            load_env_file(&default_env_file());
+            Self::from_env_only()?
+        };
+        if profile.name.is_empty() {
+            profile.name = name.to_string();
+        }
+
+        load_env_file(&profile.env_file);
+        profile.apply_env_overrides();
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    fn from_env_only() -> Result<Self, Box<dyn std::error::Error>> {
+        let required = |key: &str| {
+            env::var(key).map_err(|_| format!("No existe el perfil de red y falta la variable {}", key))
+        };
+        Ok(Self {
+            name: String::new(),
+            chain_id: required("DEPLOY_CHAIN_ID")?,
+            node_url: required("DEPLOY_NODE_URL")?,
+            gas_prices: required("DEPLOY_GAS_PRICES")?,
+            gas_adjustment: default_gas_adjustment(),
+            keyring_backend: default_keyring_backend(),
+            keyring_home: None,
+            wallet_name: default_wallet_name(),
+            mint_price: default_mint_price(),
+            workshop_contract: None,
+            game_server: None,
+            build: BuildMode::default(),
+            optimizer_engine: default_optimizer_engine(),
+            optimizer_image: default_optimizer_image(),
+            env_file: default_env_file(),
+        })
+    }
+
+    // Las variables DEPLOY_* tienen prioridad sobre el archivo TOML
+    fn apply_env_overrides(&mut self) {
+        let overrides: [(&str, &mut String); 9] = [
+            ("DEPLOY_CHAIN_ID", &mut self.chain_id),
+            ("DEPLOY_NODE_URL", &mut self.node_url),
+            ("DEPLOY_GAS_PRICES", &mut self.gas_prices),
+            ("DEPLOY_GAS_ADJUSTMENT", &mut self.gas_adjustment),
+            ("DEPLOY_KEYRING_BACKEND", &mut self.keyring_backend),
+            ("DEPLOY_WALLET_NAME", &mut self.wallet_name),
+            ("DEPLOY_MINT_PRICE", &mut self.mint_price),
+            ("DEPLOY_OPTIMIZER_ENGINE", &mut self.optimizer_engine),
+            ("DEPLOY_OPTIMIZER_IMAGE", &mut self.optimizer_image),
+        ];
+        for (key, field) in overrides {
+            if let Some(value) = optional_env(key) {
+                *field = value;
+            }
+        }
+
+        if let Some(value) = optional_env("DEPLOY_KEYRING_HOME") {
+            self.keyring_home = Some(value);
+        }
+        if let Some(value) = optional_env("DEPLOY_WORKSHOP_CONTRACT") {
+            self.workshop_contract = Some(value);
+        }
+        if let Some(value) = optional_env("DEPLOY_GAME_SERVER") {
+            self.game_server = Some(value);
+        }
+        match optional_env("DEPLOY_BUILD").as_deref() {
+            Some("cargo") => self.build = BuildMode::Cargo,
+            Some("optimizer") => self.build = BuildMode::Optimizer,
+            _ => {}
+        }
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        match self.keyring_backend.as_str() {
+            "test" | "file" | "os" => Ok(()),
+            other => Err(format!("Keyring backend no soportado: {}", other).into()),
+        }
+    }
+
+    // Argumentos comunes de keyring para cualquier comando de xiond
+    pub fn keyring_args(&self) -> Vec<String> {
+        let mut args = vec!["--keyring-backend".to_string(), self.keyring_backend.clone()];
+        if let Some(home) = &self.keyring_home {
+            args.push("--home".to_string());
+            args.push(home.clone());
+        }
+        args
+    }
+
+    // Líneas que xiond lee por stdin para desbloquear el keyring `file`.
+    // `confirm` repite la contraseña cuando el keyring aún no existe.
+    pub fn keyring_stdin(&self, confirm: bool) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        if self.keyring_backend != "file" {
+            return Ok(vec![]);
+        }
+        let password = env::var(KEYRING_PASSWORD_VAR)
+            .map_err(|_| format!("El keyring `file` requiere la variable {}", KEYRING_PASSWORD_VAR))?;
+        let mut lines = vec![password.clone()];
+        if confirm && !self.file_keyring_exists() {
+            lines.push(password);
+        }
+        Ok(lines)
+    }
+
+    // xiond solo pide confirmar la contraseña si todavía no existe el keyhash
+    fn file_keyring_exists(&self) -> bool {
+        let home = self.keyring_home.clone().map(PathBuf::from).or_else(|| {
+            env::var("HOME").ok().map(|home| Path::new(&home).join(".xiond"))
+        });
+        home.map(|home| home.join("keyring-file").join("keyhash").exists())
+            .unwrap_or(false)
+    }
+}
+
+// Leer una variable de entorno opcional, ignorando valores vacíos
+pub fn optional_env(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|value| !value.trim().is_empty())
+}
+
+// Cargar un archivo .env sin sobrescribir variables ya definidas en el entorno
+pub fn load_env_file(path: &str) {
+    if let Ok(contents) = fs::read_to_string(path) {
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                let key = key.trim();
+                if env::var_os(key).is_none() {
+                    env::set_var(key, value.trim().trim_matches('"'));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_from_toml_uses_defaults() {
+        let profile = NetworkProfile::from_toml(
+            "chain_id = \"xion-testnet-1\"\nnode_url = \"http://localhost:26657\"\ngas_prices = \"0.1uxion\"\nbuild = \"cargo\"\n",
+        ).unwrap();
+
+        assert_eq!("xion-testnet-1", profile.chain_id);
+        assert_eq!("test", profile.keyring_backend);
+        assert_eq!("1.3", profile.gas_adjustment);
+        assert_eq!(BuildMode::Cargo, profile.build);
+        assert_eq!(vec!["--keyring-backend".to_string(), "test".to_string()], profile.keyring_args());
+        // El keyring `test` no necesita secretos por stdin
+        assert!(profile.keyring_stdin(true).unwrap().is_empty());
+    }
+}
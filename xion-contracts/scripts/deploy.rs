@@ -1,186 +1,593 @@
-use std::process::Command;
+mod config;
+mod manifest;
+mod runner;
+mod verify;
+
+use config::{BuildMode, NetworkProfile, MNEMONIC_VAR};
+use cosmwasm_std::Binary;
+use manifest::{ContractRecord, DeploymentConfig, DeploymentManifest};
+use runner::{CommandOutput, CommandRunner, CommandSpec, DryRunRunner, FakeXiond, SystemRunner};
+use serde_json::{json, Value};
 use std::env;
 use std::fs;
-use serde_json::Value;
-
-// Leer las variables de entorno del archivo .env
-fn load_env() {
-    let env_path = "../xion/.env";
-    if let Ok(contents) = fs::read_to_string(env_path) {
-        for line in contents.lines() {
-            if line.starts_with('#') || line.is_empty() {
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const CAR_PART_CONTRACT: &str = "car_part_contract";
+const CAR_NFT_CONTRACT: &str = "car_nft_contract";
+const RACE_CONTRACT: &str = "race_contract";
+const PROFILE_CONTRACT: &str = "profile_contract";
+const MARKETPLACE_CONTRACT: &str = "marketplace_contract";
+
+// Contratos en orden de despliegue; cada uno vive en ../<nombre>
+const CONTRACTS: [&str; 5] = [
+    CAR_PART_CONTRACT,
+    CAR_NFT_CONTRACT,
+    RACE_CONTRACT,
+    PROFILE_CONTRACT,
+    MARKETPLACE_CONTRACT,
+];
+
+fn to_args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+// Buscar un atributo de un evento en la respuesta de `xiond query tx`.
+// Soporta tanto el formato con `logs[].events` como el formato con `events` en la raíz.
+fn find_event_attribute(tx: &Value, event_type: &str, key: &str) -> Option<String> {
+    let log_events = tx["logs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|log| log["events"].as_array())
+        .flatten();
+    let root_events = tx["events"].as_array().into_iter().flatten();
+
+    log_events
+        .chain(root_events)
+        .filter(|event| event["type"] == event_type)
+        .filter_map(|event| event["attributes"].as_array())
+        .flatten()
+        .find(|attr| attr["key"] == key)
+        .and_then(|attr| attr["value"].as_str())
+        .map(|value| value.to_string())
+}
+
+// Cliente mínimo de xiond con los parámetros del perfil de red.
+// Todos los comandos pasan por `runner`: real, simulado (--dry-run) o un xiond falso en los tests.
+struct Xiond<'a> {
+    profile: &'a NetworkProfile,
+    runner: &'a dyn CommandRunner,
+    poll_interval: Duration,
+}
+
+impl<'a> Xiond<'a> {
+    fn run(&self, cmd: CommandSpec) -> Result<CommandOutput, Box<dyn std::error::Error>> {
+        Ok(self.runner.run(&cmd)?)
+    }
+
+    // Verificar la configuración de la billetera
+    fn verify_wallet(&self) -> Result<(), Box<dyn std::error::Error>> {
+        println!("🔍 Verificando la configuración de la billetera...");
+        let profile = self.profile;
+
+        // Verificar si la billetera existe
+        let mut show_args = to_args(&["keys", "show", &profile.wallet_name]);
+        show_args.extend(profile.keyring_args());
+        let wallet_check = self.run(CommandSpec::new("xiond", show_args).stdin(profile.keyring_stdin(false)?))?;
+
+        if wallet_check.success {
+            println!("✅ Billetera verificada correctamente");
+            return Ok(());
+        }
+
+        // Si la billetera no existe, intentar importarla usando el mnemónico del entorno
+        println!("⚠️  Billetera no encontrada, intentando importar desde {}...", MNEMONIC_VAR);
+        let mnemonic = env::var(MNEMONIC_VAR)
+            .map_err(|_| format!("No se encontró la variable {} en el entorno", MNEMONIC_VAR))?;
+
+        let mut add_args = to_args(&["keys", "add", &profile.wallet_name, "--recover"]);
+        add_args.extend(profile.keyring_args());
+        let mut stdin_lines = profile.keyring_stdin(true)?;
+        stdin_lines.push(mnemonic.trim().to_string());
+
+        let import_cmd = self.run(CommandSpec::new("xiond", add_args).stdin(stdin_lines))?;
+        if !import_cmd.success {
+            return Err(format!("Error al importar la billetera: {}", import_cmd.stderr).into());
+        }
+        println!("✅ Billetera importada exitosamente");
+
+        Ok(())
+    }
+
+    // Obtener la dirección de la billetera que firma el despliegue
+    fn wallet_address(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let profile = self.profile;
+        let mut args = to_args(&["keys", "show", &profile.wallet_name, "-a"]);
+        args.extend(profile.keyring_args());
+        let output = self.run(CommandSpec::new("xiond", args).stdin(profile.keyring_stdin(false)?))?;
+
+        if !output.success {
+            return Err(format!("No se pudo obtener la dirección de la billetera: {}", output.stderr).into());
+        }
+
+        Ok(output.stdout.trim().to_string())
+    }
+
+    // Compilar un contrato a wasm según el modo del perfil y devolver la ruta del wasm
+    fn build_contract(&self, contract_dir: &str, wasm_name: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let profile = self.profile;
+        let contract_path = fs::canonicalize(contract_dir)?.display().to_string();
+        println!("📦 Compilando {} ({:?})...", wasm_name, profile.build);
+        println!("   Directorio: {}", contract_path);
+
+        let (cmd, wasm_path) = match profile.build {
+            BuildMode::Optimizer => {
+                // El motor (docker rootless o podman) se ejecuta con el usuario actual, sin sudo.
+                // Se monta la raíz del repositorio para que el optimizador vea `car_types`, que los
+                // contratos usan por ruta; el wasm queda en <raíz>/artifacts.
+                let contract = Path::new(&contract_path);
+                let root = contract.parent().ok_or("El contrato no tiene directorio padre")?.display().to_string();
+                let dir_name = contract.file_name().ok_or("Directorio de contrato inválido")?.to_string_lossy().to_string();
+                let args = to_args(&[
+                    "run", "--rm",
+                    "-v", &format!("{}:/code", root),
+                    "--mount", &format!("type=volume,source={}_target_cache,target=/code/{}/target", wasm_name, dir_name),
+                    "--mount", "type=volume,source=registry_cache,target=/usr/local/cargo/registry",
+                    &profile.optimizer_image,
+                    &format!("./{}", dir_name),
+                ]);
+                (
+                    CommandSpec::new(&profile.optimizer_engine, args),
+                    format!("{}/artifacts/{}.wasm", root, wasm_name),
+                )
+            },
+            BuildMode::Cargo => {
+                let args = to_args(&["build", "--release", "--lib", "--target", "wasm32-unknown-unknown"]);
+                (
+                    CommandSpec::new("cargo", args)
+                        .env("RUSTFLAGS", "-C link-arg=-s")
+                        .current_dir(contract_dir),
+                    format!("{}/target/wasm32-unknown-unknown/release/{}.wasm", contract_dir, wasm_name),
+                )
+            },
+        };
+
+        let output = self.run(cmd)?;
+        if !output.success {
+            println!("❌ Error al compilar el contrato:");
+            println!("   stdout: {}", output.stdout);
+            println!("   stderr: {}", output.stderr);
+            return Err(format!("Error al compilar {}", wasm_name).into());
+        }
+
+        // Verificar que el archivo wasm existe (en una simulación no se genera)
+        if !self.runner.is_simulated() && fs::metadata(&wasm_path).is_err() {
+            return Err(format!("No se encontró el archivo WASM: {}", wasm_path).into());
+        }
+        println!("✅ {} compilado: {}", wasm_name, wasm_path);
+
+        Ok(wasm_path)
+    }
+
+    // Enviar una transacción y devolver su hash
+    fn broadcast(&self, tx_args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+        let profile = self.profile;
+        let mut args = vec!["tx".to_string()];
+        args.extend(to_args(tx_args));
+        args.extend(to_args(&[
+            "--from", &profile.wallet_name,
+            "--chain-id", &profile.chain_id,
+            "--node", &profile.node_url,
+            "--gas-prices", &profile.gas_prices,
+            "--gas", "auto",
+            "--gas-adjustment", &profile.gas_adjustment,
+            "-y",
+            "--output", "json",
+            "--broadcast-mode", "sync",
+        ]));
+        args.extend(profile.keyring_args());
+
+        let output = self.run(CommandSpec::new("xiond", args).stdin(profile.keyring_stdin(false)?))?;
+        if !output.stderr.is_empty() {
+            println!("   Información adicional: {}", output.stderr);
+        }
+
+        let response: Value = serde_json::from_str(&output.stdout)
+            .map_err(|e| format!("Error al parsear JSON de la transacción: {} - Response: {}", e, output.stdout))?;
+
+        if response["code"].as_u64().unwrap_or(0) != 0 {
+            return Err(format!("La transacción fue rechazada: {}", response["raw_log"]).into());
+        }
+
+        let txhash = response["txhash"]
+            .as_str()
+            .ok_or("No se encontró el txhash en la respuesta")?;
+        println!("📝 TX Hash: {}", txhash);
+
+        Ok(txhash.to_string())
+    }
+
+    // Esperar a que la transacción se incluya en un bloque y devolver su resultado
+    fn wait_for_tx(&self, txhash: &str) -> Result<Value, Box<dyn std::error::Error>> {
+        println!("⏳ Esperando que la transacción se procese...");
+        let args = to_args(&["query", "tx", txhash, "--node", &self.profile.node_url, "--output", "json"]);
+        for i in 1..=10 {
+            println!("   Intento {} de 10...", i);
+            std::thread::sleep(self.poll_interval);
+
+            let output = self.run(CommandSpec::new("xiond", args.clone()))?;
+            if !output.success {
                 continue;
             }
-            if let Some((key, value)) = line.split_once('=') {
-                env::set_var(key, value.trim_matches('"'));
+
+            let tx: Value = serde_json::from_str(&output.stdout)?;
+            if tx["code"].as_u64().unwrap_or(0) != 0 {
+                return Err(format!("La transacción {} falló: {}", txhash, tx["raw_log"]).into());
             }
+            return Ok(tx);
+        }
+
+        Err(format!("La transacción {} no se procesó después de varios intentos", txhash).into())
+    }
+
+    // Subir un wasm a la cadena y devolver (code_id, txhash)
+    fn store_code(&self, wasm_path: &str) -> Result<(u64, String), Box<dyn std::error::Error>> {
+        println!("📤 Subiendo {} a la cadena...", wasm_path);
+        let txhash = self.broadcast(&["wasm", "store", wasm_path])?;
+        let tx = self.wait_for_tx(&txhash)?;
+
+        let code_id = find_event_attribute(&tx, "store_code", "code_id")
+            .ok_or("No se encontró el CODE_ID en la transacción")?
+            .parse::<u64>()?;
+        println!("📋 CODE_ID: {}", code_id);
+
+        Ok((code_id, txhash))
+    }
+
+    // Instanciar un contrato y devolver (dirección, txhash)
+    fn instantiate(
+        &self,
+        code_id: u64,
+        msg: &Value,
+        label: &str,
+        admin: &str,
+    ) -> Result<(String, String), Box<dyn std::error::Error>> {
+        println!("🔧 Instanciando {} (CODE_ID {})...", label, code_id);
+        let code_id = code_id.to_string();
+        let msg = msg.to_string();
+        let txhash = self.broadcast(&[
+            "wasm", "instantiate", &code_id, &msg,
+            "--label", label,
+            "--admin", admin,
+        ])?;
+        let tx = self.wait_for_tx(&txhash)?;
+
+        let address = find_event_attribute(&tx, "instantiate", "_contract_address")
+            .ok_or("No se encontró la dirección del contrato en la transacción")?;
+        println!("🏠 Dirección: {}", address);
+
+        Ok((address, txhash))
+    }
+
+    // Ejecutar una consulta de solo lectura y devolver el JSON de la respuesta
+    fn query(&self, query_args: &[&str]) -> Result<Value, Box<dyn std::error::Error>> {
+        let mut args = vec!["query".to_string()];
+        args.extend(to_args(query_args));
+        args.extend(to_args(&["--node", &self.profile.node_url, "--output", "json"]));
+
+        let output = self.run(CommandSpec::new("xiond", args))?;
+        if !output.success {
+            return Err(format!("La consulta falló: {}", output.stderr.trim()).into());
+        }
+        Ok(serde_json::from_str(&output.stdout)?)
+    }
+
+    // CODE_ID con el que está instanciado un contrato
+    fn contract_code_id(&self, contract: &str) -> Result<u64, Box<dyn std::error::Error>> {
+        let info = self.query(&["wasm", "contract", contract])?;
+        // Según la versión de xiond el code_id llega como número o como string
+        let code_id = &info["contract_info"]["code_id"];
+        code_id
+            .as_u64()
+            .or_else(|| code_id.as_str().and_then(|id| id.parse().ok()))
+            .ok_or_else(|| format!("No se encontró el CODE_ID de {}", contract).into())
+    }
+
+    // Consulta `smart` a un contrato; devuelve el campo `data` de la respuesta
+    fn smart_query(&self, contract: &str, msg: &Value) -> Result<Value, Box<dyn std::error::Error>> {
+        let msg = msg.to_string();
+        let response = self.query(&["wasm", "contract-state", "smart", contract, &msg])?;
+        Ok(response["data"].clone())
+    }
+
+    // Leer un Item de cw-storage-plus directamente del estado del contrato.
+    // Sirve para datos que los contratos no exponen con una consulta `smart`.
+    fn raw_state(&self, contract: &str, key: &str) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+        let response = self.query(&["wasm", "contract-state", "raw", contract, key, "--ascii"])?;
+        match response["data"].as_str() {
+            None | Some("") => Ok(None),
+            Some(data) => Ok(Some(serde_json::from_slice(&Binary::from_base64(data)?)?)),
         }
     }
+
+    // Ejecutar un mensaje sobre un contrato y esperar a que se procese
+    fn execute(&self, contract: &str, msg: &Value) -> Result<String, Box<dyn std::error::Error>> {
+        println!("⚙️  Ejecutando {} en {}...", msg, contract);
+        let msg = msg.to_string();
+        let txhash = self.broadcast(&["wasm", "execute", contract, &msg])?;
+        self.wait_for_tx(&txhash)?;
+        Ok(txhash)
+    }
 }
 
-// Verificar la configuración de la billetera
-fn verify_wallet(wallet_name: &str) -> Result<(), Box<dyn std::error::Error>> {
-    println!("🔍 Verificando la configuración de la billetera...");
-    
-    // Verificar si la billetera existe
-    let wallet_check = Command::new("xiond")
-        .args(&["keys", "show", wallet_name, "--keyring-backend", "test"])
-        .output()?;
-
-    if !wallet_check.status.success() {
-        // Si la billetera no existe, intentar importarla usando el mnemónico del .env
-        println!("⚠️  Billetera no encontrada, intentando importar desde .env...");
-        
-        if let Ok(contents) = fs::read_to_string("../xion/.env") {
-            if let Some(mnemonic_line) = contents.lines().find(|line| line.contains("XION_MNEMONIC")) {
-                if let Some((_, mnemonic)) = mnemonic_line.split_once('=') {
-                    let mnemonic = mnemonic.trim().trim_matches('"');
-                    
-                    let import_cmd = Command::new("sh")
-                        .arg("-c")
-                        .arg(format!(
-                            "echo '{}' | xiond keys add {} --recover --keyring-backend test",
-                            mnemonic, wallet_name
-                        ))
-                        .output()?;
-
-                    if !import_cmd.status.success() {
-                        return Err(format!(
-                            "Error al importar la billetera: {}", 
-                            String::from_utf8_lossy(&import_cmd.stderr)
-                        ).into());
-                    }
-                    println!("✅ Billetera importada exitosamente");
-                } else {
-                    return Err("No se encontró el mnemónico en el archivo .env".into());
-                }
-            } else {
-                return Err("No se encontró la variable XION_MNEMONIC en el archivo .env".into());
-            }
-        } else {
-            return Err("No se pudo leer el archivo .env".into());
+// Compilar, subir, instanciar y enlazar todos los contratos. Devuelve el manifiesto sin guardarlo.
+fn deploy(xiond: &Xiond, deployed_at: u64) -> Result<DeploymentManifest, Box<dyn std::error::Error>> {
+    let profile = xiond.profile;
+
+    // Verificar la billetera antes de continuar
+    xiond.verify_wallet()?;
+    let deployer = xiond.wallet_address()?;
+    println!("👛 Desplegando desde: {}", deployer);
+
+    let mut manifest = DeploymentManifest::new(&profile.chain_id, &profile.node_url, &deployer, deployed_at);
+
+    // 1. Compilar y subir todos los contratos
+    let mut stored = vec![];
+    for name in CONTRACTS {
+        let wasm_path = xiond.build_contract(&format!("../{}", name), name)?;
+        let (code_id, store_tx) = xiond.store_code(&wasm_path)?;
+        stored.push((name, wasm_path, code_id, store_tx));
+    }
+
+    // 2. Instanciar en orden de dependencias. El contrato de carros aún no existe al instanciar
+    // el de partes, así que se usa la dirección del deployer hasta ejecutar SetCarContract.
+    let game_server = profile.game_server.clone().unwrap_or_else(|| deployer.clone());
+    let mut addresses: Vec<String> = vec![];
+    for (name, wasm_path, code_id, store_tx) in stored {
+        let msg = match name {
+            CAR_PART_CONTRACT => json!({ "car_contract": deployer }),
+            CAR_NFT_CONTRACT => json!({ "car_part_contract": addresses[0], "mint_price": profile.mint_price }),
+            RACE_CONTRACT => json!({ "car_contract": addresses[1], "game_server": game_server }),
+            PROFILE_CONTRACT => json!({ "car_contract": addresses[1], "leaderboard_contract": addresses[2] }),
+            MARKETPLACE_CONTRACT => json!({ "car_contract": addresses[1], "part_contract": addresses[0] }),
+            other => return Err(format!("Contrato desconocido: {}", other).into()),
+        };
+        let (address, instantiate_tx) = xiond.instantiate(code_id, &msg, &name.replace('_', " "), &deployer)?;
+        addresses.push(address.clone());
+        manifest.contracts.insert(name.to_string(), ContractRecord {
+            code_id,
+            address,
+            store_tx,
+            instantiate_tx,
+            wasm_path,
+        });
+    }
+    let [part_address, nft_address, race_address, profile_address, _] = &addresses[..] else {
+        return Err("No se instanciaron todos los contratos".into());
+    };
+
+    // 3. Enlazar el contrato de partes con el contrato de carros
+    xiond.execute(part_address, &json!({ "set_car_contract": { "address": nft_address } }))?;
+
+    // 4. El contrato de carreras es el leaderboard del contrato de carros y reporta
+    // resultados a los perfiles; el taller es opcional
+    if let Some(address) = &profile.workshop_contract {
+        xiond.execute(nft_address, &json!({ "set_workshop_contract": { "address": address } }))?;
+    }
+    xiond.execute(nft_address, &json!({ "set_leaderboard_contract": { "address": race_address } }))?;
+    xiond.execute(race_address, &json!({ "set_profile_contract": { "address": profile_address } }))?;
+
+    // 5. El servidor de juego publica su semilla en cada commit de minteo y cada compra de pack
+    xiond.execute(nft_address, &json!({ "set_game_server": { "address": game_server } }))?;
+    xiond.execute(part_address, &json!({ "set_game_server": { "address": game_server } }))?;
+
+    manifest.config = DeploymentConfig {
+        mint_price: profile.mint_price.clone(),
+        workshop_contract: profile.workshop_contract.clone(),
+        leaderboard_contract: Some(race_address.clone()),
+        game_server: Some(game_server),
+    };
+
+    Ok(manifest)
+}
+
+// Acción a realizar sobre la red
+#[derive(Debug, PartialEq)]
+enum Subcommand {
+    // Desplegar y enlazar los contratos, y escribir el manifiesto
+    Deploy,
+    // Comparar el manifiesto guardado con el estado de la cadena
+    Verify,
+}
+
+// Argumentos de línea de comandos: `deploy [verify] [--network <nombre>] [--dry-run]`
+#[derive(Debug, PartialEq)]
+struct CliArgs {
+    subcommand: Subcommand,
+    network: String,
+    dry_run: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<CliArgs, Box<dyn std::error::Error>> {
+    let mut cli = CliArgs {
+        subcommand: Subcommand::Deploy,
+        network: "testnet".to_string(),
+        dry_run: false,
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--network" => {
+                cli.network = iter.next().ok_or("Falta el valor de --network")?.clone();
+            },
+            "--dry-run" => cli.dry_run = true,
+            "verify" => cli.subcommand = Subcommand::Verify,
+            other => return Err(format!("Argumento desconocido: {}", other).into()),
         }
+    }
+    Ok(cli)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("🚀 Iniciando el proceso de despliegue...");
+
+    // Cargar el perfil de la red (networks/<red>.toml + variables DEPLOY_*)
+    let args: Vec<String> = env::args().skip(1).collect();
+    let cli = parse_args(&args)?;
+    let profile = NetworkProfile::load(&cli.network)?;
+    println!("🌐 Red: {} ({})", profile.name, profile.chain_id);
+
+    let manifest_path = DeploymentManifest::path_for(&profile.chain_id);
+    if cli.subcommand == Subcommand::Verify {
+        return run_verify(&profile, &manifest_path, cli.dry_run);
+    }
+
+    // En modo --dry-run solo se imprimen los comandos y xiond se simula
+    let dry_runner = DryRunRunner {
+        fake: FakeXiond::new("xion1dryrundeployer", 1),
+    };
+    let xiond = if cli.dry_run {
+        println!("🧪 Modo dry-run: no se ejecutará ningún comando");
+        Xiond { profile: &profile, runner: &dry_runner, poll_interval: Duration::ZERO }
     } else {
-        println!("✅ Billetera verificada correctamente");
+        Xiond { profile: &profile, runner: &SystemRunner, poll_interval: Duration::from_secs(2) }
+    };
+
+    let deployed_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let manifest = deploy(&xiond, deployed_at)?;
+
+    if cli.dry_run {
+        println!("\n📄 Manifiesto simulado (no se guardó):");
+        println!("{}", serde_json::to_string_pretty(&manifest)?);
+        return Ok(());
     }
 
+    // 6. Guardar el manifiesto del despliegue
+    manifest.save(&manifest_path)?;
+
+    println!("\n✅ Despliegue completado exitosamente!");
+    for (name, record) in &manifest.contracts {
+        println!("   {}: {} (CODE_ID {})", name, record.address, record.code_id);
+    }
+    println!("📄 Manifiesto guardado en {}", manifest_path.display());
+    println!("   Compruébalo con: cargo run --bin deploy -- verify --network {}", cli.network);
+
     Ok(())
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    println!("🚀 Iniciando el proceso de despliegue...");
-    
-    // Cargar variables de entorno
-    load_env();
+// Subcomando `verify`: consultar cada contrato del manifiesto y reportar diferencias
+fn run_verify(profile: &NetworkProfile, manifest_path: &Path, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = DeploymentManifest::load(manifest_path)?;
+    println!("🔎 Verificando {} contra {}", manifest_path.display(), profile.node_url);
 
-    let wallet_name = "wallet";
-    let chain_id = "xion-testnet-1";
-    let node_url = "https://rpc.xion-testnet-1.burnt.com:443";
-    let gas_prices = "0.1uxion";
+    let dry_runner = DryRunRunner {
+        fake: FakeXiond::with_manifest(&manifest),
+    };
+    let runner: &dyn CommandRunner = if dry_run { &dry_runner } else { &SystemRunner };
+    let xiond = Xiond { profile, runner, poll_interval: Duration::ZERO };
 
-    // Verificar la billetera antes de continuar
-    verify_wallet(wallet_name)?;
-
-    println!("📦 Optimizando el contrato...");
-    
-    // Cambiar al directorio del contrato
-    let contract_path = env::current_dir()?.display().to_string();
-    println!("   Directorio actual: {}", contract_path);
-    println!("⚠️  Se solicitará la contraseña de sudo para ejecutar Docker...");
-    
-    let docker_cmd = Command::new("sudo")
-        .args(&[
-            "docker", "run", "--rm",
-            "-v", format!("{}:/code", contract_path).as_str(),
-            "--mount", "type=volume,source=target_cache,target=/code/target",
-            "--mount", "type=volume,source=registry_cache,target=/usr/local/cargo/registry",
-            "cosmwasm/rust-optimizer:0.16.0"
-        ])
-        .output()?;
-
-    if !docker_cmd.status.success() {
-        println!("❌ Error al ejecutar Docker:");
-        println!("   stdout: {}", String::from_utf8_lossy(&docker_cmd.stdout));
-        println!("   stderr: {}", String::from_utf8_lossy(&docker_cmd.stderr));
-        return Err("Error al optimizar el contrato".into());
-    }
-
-    println!("✅ Contrato optimizado correctamente");
-
-    // Verificar que el archivo wasm existe
-    let wasm_path = "artifacts/xion_contracts.wasm";
-    if !fs::metadata(wasm_path).is_ok() {
-        return Err("No se encontró el archivo WASM optimizado".into());
-    }
-    println!("✅ Archivo WASM optimizado encontrado");
-
-    // Subir el contrato a la cadena
-    println!("📤 Subiendo el contrato a la cadena...");
-    let store_cmd = Command::new("xiond")
-        .args(&[
-            "tx", "wasm", "store", wasm_path,
-            "--from", wallet_name,
-            "--chain-id", chain_id,
-            "--node", node_url,
-            "--gas-prices", gas_prices,
-            "--gas", "auto",
-            "--gas-adjustment", "1.3",
-            "--keyring-backend", "test",
-            "-y",
-            "--output", "json",
-            "--broadcast-mode", "sync"
-        ])
-        .output()?;
-
-    println!("   Respuesta del comando store:");
-    let store_response = String::from_utf8_lossy(&store_cmd.stdout);
-    let store_stderr = String::from_utf8_lossy(&store_cmd.stderr);
-    
-    if !store_stderr.is_empty() {
-        println!("   Información adicional: {}", store_stderr);
-    }
-
-    let store_json: Value = serde_json::from_str(&store_response)
-        .map_err(|e| format!("Error al parsear JSON de store: {} - Response: {}", e, store_response))?;
-    
-    let txhash = store_json["txhash"]
-        .as_str()
-        .ok_or("No se encontró el txhash en la respuesta")?;
-    println!("📝 TX Hash: {}", txhash);
-
-    // Esperar que se procese la transacción
-    println!("⏳ Esperando que la transacción se procese...");
-    for i in 1..=10 {
-        println!("   Intento {} de 10...", i);
-        std::thread::sleep(std::time::Duration::from_secs(2));
-
-        let query_output = Command::new("xiond")
-            .args(&[
-                "query", "tx", txhash,
-                "--node", node_url,
-                "--output", "json"
-            ])
-            .output()?;
-
-        let query_response = String::from_utf8_lossy(&query_output.stdout);
-        
-        if !query_response.contains("not found") {
-            println!("\n🔍 Analizando respuesta de la transacción:");
-            println!("{}", query_response);
-            
-            // Buscar el CODE_ID en la salida completa
-            if let Some(code_id_pos) = query_response.find("code_id") {
-                let substr = &query_response[code_id_pos..];
-                if let Some(value_start) = substr.find(char::is_numeric) {
-                    let value_end = substr[value_start..].find(|c: char| !c.is_numeric())
-                        .map_or(substr.len() - value_start, |i| value_start + i);
-                    let code_id = &substr[value_start..value_end];
-                    println!("📋 CODE_ID encontrado: {}", code_id);
-                    return Ok(());
-                }
-            }
-        }
+    let drifts = verify::verify(&xiond, &manifest)?;
+    if drifts.is_empty() {
+        println!("✅ El despliegue coincide con el manifiesto");
+        return Ok(());
     }
 
-    Err("No se pudo obtener el CODE_ID después de varios intentos".into())
-} 
\ No newline at end of file
+    println!("❌ Se encontraron {} diferencia(s):", drifts.len());
+    for drift in &drifts {
+        println!("   {}", drift);
+    }
+    Err(format!("El despliegue de {} no coincide con el manifiesto", manifest.chain_id).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_profile() -> NetworkProfile {
+        let mut profile = NetworkProfile::from_toml(
+            "chain_id = \"xion-local-1\"\nnode_url = \"http://localhost:26657\"\ngas_prices = \"0.1uxion\"\nbuild = \"cargo\"\n",
+        ).unwrap();
+        profile.workshop_contract = Some("xion1workshop".to_string());
+        profile
+    }
+
+    #[test]
+    fn finds_code_id_in_both_tx_formats() {
+        let root = json!({ "events": [{ "type": "store_code", "attributes": [{ "key": "code_id", "value": "1902" }] }] });
+        let logs = json!({ "logs": [{ "events": [{ "type": "store_code", "attributes": [{ "key": "code_id", "value": "1904" }] }] }] });
+        // Un code_id de otro evento no debe confundirse con el de store_code
+        let other = json!({ "events": [{ "type": "instantiate", "attributes": [{ "key": "code_id", "value": "7" }] }] });
+
+        assert_eq!(Some("1902".to_string()), find_event_attribute(&root, "store_code", "code_id"));
+        assert_eq!(Some("1904".to_string()), find_event_attribute(&logs, "store_code", "code_id"));
+        assert_eq!(None, find_event_attribute(&other, "store_code", "code_id"));
+    }
+
+    #[test]
+    fn deploy_with_fake_xiond_writes_manifest() {
+        let profile = test_profile();
+        let fake = FakeXiond::new("xion1deployer", 100);
+        let xiond = Xiond { profile: &profile, runner: &fake, poll_interval: Duration::ZERO };
+
+        let manifest = deploy(&xiond, 42).unwrap();
+
+        let part = &manifest.contracts[CAR_PART_CONTRACT];
+        let nft = &manifest.contracts[CAR_NFT_CONTRACT];
+        assert_eq!(100, part.code_id);
+        assert_eq!(101, nft.code_id);
+        assert_eq!(FakeXiond::contract_address(100), part.address);
+        assert_eq!(FakeXiond::contract_address(101), nft.address);
+        assert_eq!("xion1deployer", manifest.deployer);
+        assert_eq!(Some("xion1workshop".to_string()), manifest.config.workshop_contract);
+
+        // El contrato de carros se instancia con la dirección real del contrato de partes
+        // y después se ejecuta SetCarContract sobre el contrato de partes
+        let commands: Vec<String> = fake.commands().iter().map(|cmd| cmd.display()).collect();
+        assert!(commands.iter().any(|cmd| cmd.contains(&format!("\"car_part_contract\":\"{}\"", part.address))));
+        assert!(commands.iter().any(|cmd| {
+            cmd.contains(&format!("wasm execute {}", part.address)) && cmd.contains("set_car_contract")
+        }));
+        assert!(commands.iter().any(|cmd| cmd.contains("set_workshop_contract")));
+
+        // Carreras, perfiles y marketplace se despliegan y se enlazan entre sí
+        let race = &manifest.contracts[RACE_CONTRACT].address;
+        let profile = &manifest.contracts[PROFILE_CONTRACT].address;
+        let marketplace = &manifest.contracts[MARKETPLACE_CONTRACT];
+        assert_eq!(104, marketplace.code_id);
+        assert_eq!(Some(race.clone()), manifest.config.leaderboard_contract);
+        assert_eq!(Some("xion1deployer".to_string()), manifest.config.game_server);
+        assert!(commands.iter().any(|cmd| {
+            cmd.contains(&format!("wasm execute {}", nft.address)) && cmd.contains(&format!("set_leaderboard_contract\":{{\"address\":\"{}\"", race))
+        }));
+        assert!(commands.iter().any(|cmd| {
+            cmd.contains(&format!("wasm execute {}", race)) && cmd.contains(&format!("\"address\":\"{}\"", profile))
+        }));
+        assert!(commands.iter().any(|cmd| cmd.contains(&format!("\"leaderboard_contract\":\"{}\"", race))));
+        assert!(commands.iter().any(|cmd| {
+            cmd.contains(&format!("wasm execute {}", nft.address)) && cmd.contains("set_game_server\":{\"address\":\"xion1deployer\"")
+        }));
+        assert!(commands.iter().any(|cmd| {
+            cmd.contains(&format!("wasm execute {}", part.address)) && cmd.contains("set_game_server\":{\"address\":\"xion1deployer\"")
+        }));
+        assert!(commands.iter().any(|cmd| cmd.contains(&format!("\"part_contract\":\"{}\"", part.address))));
+
+        // El manifiesto se guarda y se puede volver a leer
+        let path = env::temp_dir().join("speed-rush-deploy-test").join("xion-local-1.json");
+        manifest.save(&path).unwrap();
+        let saved: DeploymentManifest = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(manifest, saved);
+    }
+
+    #[test]
+    fn parses_cli_args() {
+        let args = to_args(&["--network", "local", "--dry-run"]);
+        assert_eq!(
+            CliArgs { subcommand: Subcommand::Deploy, network: "local".to_string(), dry_run: true },
+            parse_args(&args).unwrap()
+        );
+        let args = to_args(&["verify", "--network", "testnet"]);
+        assert_eq!(Subcommand::Verify, parse_args(&args).unwrap().subcommand);
+        assert!(parse_args(&to_args(&["--network"])).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Directorio donde se guardan los manifiestos de despliegue (uno por red)
+pub const DEPLOYMENTS_DIR: &str = "deployments";
+
+// Información de un contrato desplegado
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ContractRecord {
+    pub code_id: u64,
+    pub address: String,
+    pub store_tx: String,
+    pub instantiate_tx: String,
+    pub wasm_path: String,
+}
+
+// Configuración aplicada a los contratos después de instanciarlos
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct DeploymentConfig {
+    pub mint_price: String,
+    pub workshop_contract: Option<String>,
+    pub leaderboard_contract: Option<String>, // el contrato de carreras
+    #[serde(default)]
+    pub game_server: Option<String>,
+}
+
+// Manifiesto legible por máquina con el resultado de un despliegue
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DeploymentManifest {
+    pub chain_id: String,
+    pub node_url: String,
+    pub deployer: String,
+    pub deployed_at: u64,
+    pub contracts: BTreeMap<String, ContractRecord>,
+    pub config: DeploymentConfig,
+}
+
+impl DeploymentManifest {
+    pub fn new(chain_id: &str, node_url: &str, deployer: &str, deployed_at: u64) -> Self {
+        Self {
+            chain_id: chain_id.to_string(),
+            node_url: node_url.to_string(),
+            deployer: deployer.to_string(),
+            deployed_at,
+            contracts: BTreeMap::new(),
+            config: DeploymentConfig::default(),
+        }
+    }
+
+    // Ruta del manifiesto para una red: deployments/<chain_id>.json
+    pub fn path_for(chain_id: &str) -> PathBuf {
+        Path::new(DEPLOYMENTS_DIR).join(format!("{}.json", chain_id))
+    }
+
+    // Leer un manifiesto guardado previamente
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("No se pudo leer el manifiesto {}: {}", path.display(), e))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    // Dirección registrada de un contrato del manifiesto
+    pub fn address_of(&self, contract: &str) -> Result<&str, Box<dyn std::error::Error>> {
+        self.contracts
+            .get(contract)
+            .map(|record| record.address.as_str())
+            .ok_or_else(|| format!("El manifiesto no contiene el contrato {}", contract).into())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,360 @@
+use crate::manifest::DeploymentManifest;
+use cosmwasm_std::Binary;
+use serde_json::{json, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+// Comando a ejecutar. Las líneas de `stdin` pueden contener secretos y nunca se imprimen.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct CommandSpec {
+    pub program: String,
+    pub args: Vec<String>,
+    pub stdin: Vec<String>,
+    pub current_dir: Option<String>,
+    pub env: Vec<(String, String)>,
+}
+
+impl CommandSpec {
+    pub fn new(program: &str, args: Vec<String>) -> Self {
+        Self {
+            program: program.to_string(),
+            args,
+            ..Self::default()
+        }
+    }
+
+    pub fn stdin(mut self, lines: Vec<String>) -> Self {
+        self.stdin = lines;
+        self
+    }
+
+    pub fn current_dir(mut self, dir: &str) -> Self {
+        self.current_dir = Some(dir.to_string());
+        self
+    }
+
+    pub fn env(mut self, key: &str, value: &str) -> Self {
+        self.env.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    // Representación legible del comando, sin el contenido de stdin
+    pub fn display(&self) -> String {
+        let mut parts: Vec<String> = self.env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        parts.push(self.program.clone());
+        parts.extend(self.args.iter().map(|arg| {
+            if arg.contains(' ') || arg.contains('{') {
+                format!("'{}'", arg)
+            } else {
+                arg.clone()
+            }
+        }));
+        let mut line = parts.join(" ");
+        if let Some(dir) = &self.current_dir {
+            line = format!("(cd {} && {})", dir, line);
+        }
+        if !self.stdin.is_empty() {
+            line.push_str(&format!(" <<< [{} línea(s) secretas por stdin]", self.stdin.len()));
+        }
+        line
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    pub fn ok(stdout: String) -> Self {
+        Self { success: true, stdout, stderr: String::new() }
+    }
+}
+
+// Abstracción sobre la ejecución de comandos externos (xiond, docker, cargo)
+pub trait CommandRunner {
+    fn run(&self, cmd: &CommandSpec) -> io::Result<CommandOutput>;
+
+    // Los runners simulados no generan archivos wasm reales
+    fn is_simulated(&self) -> bool {
+        false
+    }
+}
+
+// Ejecuta los comandos de verdad, sin pasar por un shell
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, cmd: &CommandSpec) -> io::Result<CommandOutput> {
+        let mut command = Command::new(&cmd.program);
+        command
+            .args(&cmd.args)
+            .envs(cmd.env.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(dir) = &cmd.current_dir {
+            command.current_dir(dir);
+        }
+
+        let mut child = command.spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            for line in &cmd.stdin {
+                writeln!(stdin, "{}", line)?;
+            }
+        }
+
+        let output = child.wait_with_output()?;
+        Ok(CommandOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+// Contrato instanciado en el xiond simulado. `state` imita los Item de cw-storage-plus
+// guardados con el mismo nombre que el campo del mensaje que los configura.
+#[derive(Clone, Debug, Default)]
+struct FakeContract {
+    code_id: u64,
+    state: BTreeMap<String, Value>,
+}
+
+// Sustituto de xiond que responde con JSON predefinido para store, instantiate, execute
+// y las consultas `query tx` / `query wasm`. Guarda cada comando recibido para los tests.
+pub struct FakeXiond {
+    pub deployer: String,
+    next_code_id: Cell<u64>,
+    next_execute: Cell<u64>,
+    commands: RefCell<Vec<CommandSpec>>,
+    contracts: RefCell<BTreeMap<String, FakeContract>>,
+}
+
+impl FakeXiond {
+    pub fn new(deployer: &str, first_code_id: u64) -> Self {
+        Self {
+            deployer: deployer.to_string(),
+            next_code_id: Cell::new(first_code_id),
+            next_execute: Cell::new(1),
+            commands: RefCell::new(vec![]),
+            contracts: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    // xiond simulado con los contratos de un manifiesto ya desplegados
+    pub fn with_manifest(manifest: &DeploymentManifest) -> Self {
+        let fake = Self::new(&manifest.deployer, 1);
+        let part = manifest.contracts.get("car_part_contract");
+        let nft = manifest.contracts.get("car_nft_contract");
+        if let (Some(part), Some(nft)) = (part, nft) {
+            let config = &manifest.config;
+            let mut nft_state = BTreeMap::from([
+                ("car_part_contract".to_string(), json!(part.address)),
+                ("mint_price".to_string(), json!(config.mint_price)),
+            ]);
+            if let Some(address) = &config.workshop_contract {
+                nft_state.insert("workshop_contract".to_string(), json!(address));
+            }
+            if let Some(address) = &config.leaderboard_contract {
+                nft_state.insert("leaderboard_contract".to_string(), json!(address));
+            }
+
+            let mut contracts = fake.contracts.borrow_mut();
+            contracts.insert(part.address.clone(), FakeContract {
+                code_id: part.code_id,
+                state: BTreeMap::from([("car_contract".to_string(), json!(nft.address))]),
+            });
+            contracts.insert(nft.address.clone(), FakeContract { code_id: nft.code_id, state: nft_state });
+
+            // Carreras, perfiles y marketplace, si el manifiesto los incluye
+            let race = manifest.contracts.get("race_contract");
+            let profile = manifest.contracts.get("profile_contract");
+            if let Some(race) = race {
+                let mut state = BTreeMap::from([("car_contract".to_string(), json!(nft.address))]);
+                if let Some(game_server) = &config.game_server {
+                    state.insert("game_server".to_string(), json!(game_server));
+                }
+                if let Some(profile) = profile {
+                    state.insert("profile_contract".to_string(), json!(profile.address));
+                }
+                contracts.insert(race.address.clone(), FakeContract { code_id: race.code_id, state });
+            }
+            if let Some(profile) = profile {
+                let mut state = BTreeMap::from([("car_contract".to_string(), json!(nft.address))]);
+                if let Some(race) = race {
+                    state.insert("leaderboard_contract".to_string(), json!(race.address));
+                }
+                contracts.insert(profile.address.clone(), FakeContract { code_id: profile.code_id, state });
+            }
+            if let Some(marketplace) = manifest.contracts.get("marketplace_contract") {
+                contracts.insert(marketplace.address.clone(), FakeContract {
+                    code_id: marketplace.code_id,
+                    state: BTreeMap::from([
+                        ("car_contract".to_string(), json!(nft.address)),
+                        ("part_contract".to_string(), json!(part.address)),
+                    ]),
+                });
+            }
+        }
+        fake
+    }
+
+    #[cfg(test)]
+    pub fn commands(&self) -> Vec<CommandSpec> {
+        self.commands.borrow().clone()
+    }
+
+    // Dirección determinista del contrato instanciado a partir de un code_id
+    pub fn contract_address(code_id: u64) -> String {
+        format!("xion1contract{}", code_id)
+    }
+
+    fn respond(&self, args: &[&str]) -> CommandOutput {
+        match args {
+            ["keys", "show", _, "-a", ..] => CommandOutput::ok(format!("{}\n", self.deployer)),
+            ["keys", ..] => CommandOutput::ok(String::new()),
+            ["tx", "wasm", "store", ..] => {
+                let code_id = self.next_code_id.get();
+                self.next_code_id.set(code_id + 1);
+                CommandOutput::ok(json!({ "code": 0, "txhash": format!("STORE_TX_{}", code_id) }).to_string())
+            },
+            ["tx", "wasm", "instantiate", code_id, msg, ..] => {
+                let code_id: u64 = code_id.parse().unwrap_or(0);
+                let state = match serde_json::from_str(msg) {
+                    Ok(Value::Object(fields)) => fields.into_iter().collect(),
+                    _ => BTreeMap::new(),
+                };
+                self.contracts
+                    .borrow_mut()
+                    .insert(Self::contract_address(code_id), FakeContract { code_id, state });
+                CommandOutput::ok(json!({ "code": 0, "txhash": format!("INSTANTIATE_TX_{}", code_id) }).to_string())
+            },
+            ["tx", "wasm", "execute", contract, msg, ..] => {
+                self.apply_execute(contract, msg);
+                let n = self.next_execute.get();
+                self.next_execute.set(n + 1);
+                CommandOutput::ok(json!({ "code": 0, "txhash": format!("EXECUTE_TX_{}", n) }).to_string())
+            },
+            ["query", "tx", txhash, ..] => CommandOutput::ok(Self::tx_result(txhash).to_string()),
+            ["query", "wasm", "contract", address, ..] => match self.contracts.borrow().get(*address) {
+                Some(contract) => CommandOutput::ok(json!({
+                    "address": address,
+                    "contract_info": { "code_id": contract.code_id.to_string() }
+                }).to_string()),
+                None => Self::not_found(address),
+            },
+            ["query", "wasm", "contract-state", "raw", address, key, ..] => match self.contracts.borrow().get(*address) {
+                Some(contract) => {
+                    let data = contract.state.get(*key)
+                        .map(|value| Binary::from(value.to_string().as_bytes()).to_base64());
+                    CommandOutput::ok(json!({ "data": data }).to_string())
+                },
+                None => Self::not_found(address),
+            },
+            ["query", "wasm", "contract-state", "smart", address, msg, ..] => match self.contracts.borrow().get(*address) {
+                Some(contract) => {
+                    let msg: Value = serde_json::from_str(msg).unwrap_or_default();
+                    let data = if msg.get("get_mint_price").is_some() {
+                        contract.state.get("mint_price").cloned()
+                    } else {
+                        None
+                    };
+                    CommandOutput::ok(json!({ "data": data }).to_string())
+                },
+                None => Self::not_found(address),
+            },
+            _ => CommandOutput::ok(String::new()),
+        }
+    }
+
+    // `{"set_<item>": {"<campo>": valor}}` guarda `valor` en el Item `<item>` del contrato
+    fn apply_execute(&self, contract: &str, msg: &str) {
+        let Ok(Value::Object(msg)) = serde_json::from_str::<Value>(msg) else {
+            return;
+        };
+        let mut contracts = self.contracts.borrow_mut();
+        let Some(contract) = contracts.get_mut(contract) else {
+            return;
+        };
+        for (name, fields) in msg {
+            let value = fields.as_object().and_then(|fields| fields.values().next().cloned());
+            if let (Some(item), Some(value)) = (name.strip_prefix("set_"), value) {
+                contract.state.insert(item.to_string(), value);
+            }
+        }
+    }
+
+    fn not_found(address: &str) -> CommandOutput {
+        CommandOutput {
+            success: false,
+            stdout: String::new(),
+            stderr: format!("Error: no such contract: {}", address),
+        }
+    }
+
+    fn tx_result(txhash: &str) -> serde_json::Value {
+        if let Some(code_id) = txhash.strip_prefix("STORE_TX_") {
+            json!({
+                "code": 0,
+                "txhash": txhash,
+                "events": [{
+                    "type": "store_code",
+                    "attributes": [{ "key": "code_id", "value": code_id }]
+                }]
+            })
+        } else if let Some(code_id) = txhash.strip_prefix("INSTANTIATE_TX_") {
+            let address = Self::contract_address(code_id.parse().unwrap_or(0));
+            json!({
+                "code": 0,
+                "txhash": txhash,
+                "logs": [{
+                    "events": [{
+                        "type": "instantiate",
+                        "attributes": [
+                            { "key": "_contract_address", "value": address },
+                            { "key": "code_id", "value": code_id }
+                        ]
+                    }]
+                }]
+            })
+        } else {
+            json!({ "code": 0, "txhash": txhash, "events": [] })
+        }
+    }
+}
+
+impl CommandRunner for FakeXiond {
+    fn run(&self, cmd: &CommandSpec) -> io::Result<CommandOutput> {
+        self.commands.borrow_mut().push(cmd.clone());
+        if cmd.program != "xiond" {
+            return Ok(CommandOutput::ok(String::new()));
+        }
+        let args: Vec<&str> = cmd.args.iter().map(|arg| arg.as_str()).collect();
+        Ok(self.respond(&args))
+    }
+
+    fn is_simulated(&self) -> bool {
+        true
+    }
+}
+
+// Modo --dry-run: imprime cada comando y responde con el xiond simulado
+pub struct DryRunRunner {
+    pub fake: FakeXiond,
+}
+
+impl CommandRunner for DryRunRunner {
+    fn run(&self, cmd: &CommandSpec) -> io::Result<CommandOutput> {
+        println!("🧪 [dry-run] {}", cmd.display());
+        self.fake.run(cmd)
+    }
+
+    fn is_simulated(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,196 @@
+use crate::manifest::DeploymentManifest;
+use crate::{Xiond, CAR_NFT_CONTRACT, CAR_PART_CONTRACT, MARKETPLACE_CONTRACT, PROFILE_CONTRACT, RACE_CONTRACT};
+use serde_json::{json, Value};
+use std::fmt;
+
+// Diferencia entre lo registrado en el manifiesto y lo que hay en la cadena
+#[derive(Clone, Debug, PartialEq)]
+pub struct Drift {
+    pub contract: String,
+    pub check: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} / {}: esperado `{}`, encontrado `{}`",
+            self.contract, self.check, self.expected, self.actual
+        )
+    }
+}
+
+fn value_to_string(value: Value) -> String {
+    match value {
+        Value::String(value) => value,
+        other => other.to_string(),
+    }
+}
+
+// Comparar un valor esperado con el resultado de una consulta. Un error de consulta también cuenta como diferencia.
+fn compare(
+    drifts: &mut Vec<Drift>,
+    contract: &str,
+    check: &str,
+    expected: &str,
+    actual: Result<Option<String>, Box<dyn std::error::Error>>,
+) {
+    let actual = match actual {
+        Ok(Some(actual)) if actual == expected => return,
+        Ok(Some(actual)) => actual,
+        Ok(None) => "<sin valor>".to_string(),
+        Err(e) => format!("error: {}", e),
+    };
+    drifts.push(Drift {
+        contract: contract.to_string(),
+        check: check.to_string(),
+        expected: expected.to_string(),
+        actual,
+    });
+}
+
+fn raw_string(xiond: &Xiond, contract: &str, key: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    Ok(xiond.raw_state(contract, key)?.map(value_to_string))
+}
+
+// Consultar cada contrato del manifiesto y devolver las diferencias encontradas:
+// CODE_ID, enlaces entre contratos, precio de minteo y direcciones opcionales
+pub fn verify(xiond: &Xiond, manifest: &DeploymentManifest) -> Result<Vec<Drift>, Box<dyn std::error::Error>> {
+    let part = manifest.address_of(CAR_PART_CONTRACT)?;
+    let nft = manifest.address_of(CAR_NFT_CONTRACT)?;
+    let mut drifts = vec![];
+
+    // 1. El contrato sigue instanciado con el CODE_ID registrado
+    for (name, record) in &manifest.contracts {
+        let code_id = xiond.contract_code_id(&record.address).map(|id| Some(id.to_string()));
+        compare(&mut drifts, name, "code_id", &record.code_id.to_string(), code_id);
+    }
+
+    // 2. Enlaces cruzados entre ambos contratos
+    compare(&mut drifts, CAR_PART_CONTRACT, "car_contract", nft, raw_string(xiond, part, "car_contract"));
+    compare(&mut drifts, CAR_NFT_CONTRACT, "car_part_contract", part, raw_string(xiond, nft, "car_part_contract"));
+
+    // 3. Precio de minteo
+    let mint_price = xiond
+        .smart_query(nft, &json!({ "get_mint_price": {} }))
+        .map(|price| Some(value_to_string(price)));
+    compare(&mut drifts, CAR_NFT_CONTRACT, "mint_price", &manifest.config.mint_price, mint_price);
+
+    // 4. Taller y leaderboard, solo si se configuraron en el despliegue
+    let optional_links = [
+        ("workshop_contract", &manifest.config.workshop_contract),
+        ("leaderboard_contract", &manifest.config.leaderboard_contract),
+    ];
+    for (key, expected) in optional_links {
+        if let Some(expected) = expected {
+            compare(&mut drifts, CAR_NFT_CONTRACT, key, expected, raw_string(xiond, nft, key));
+        }
+    }
+
+    // 5. Carreras, perfiles y marketplace. Los manifiestos anteriores no los incluyen.
+    let address = |name: &str| manifest.contracts.get(name).map(|record| record.address.as_str());
+    let mut links = vec![];
+    if let Some(race) = address(RACE_CONTRACT) {
+        links.push((RACE_CONTRACT, race, "car_contract", nft));
+        if let Some(game_server) = &manifest.config.game_server {
+            links.push((RACE_CONTRACT, race, "game_server", game_server.as_str()));
+        }
+        if let Some(profile) = address(PROFILE_CONTRACT) {
+            links.push((RACE_CONTRACT, race, "profile_contract", profile));
+            links.push((PROFILE_CONTRACT, profile, "leaderboard_contract", race));
+        }
+    }
+    if let Some(profile) = address(PROFILE_CONTRACT) {
+        links.push((PROFILE_CONTRACT, profile, "car_contract", nft));
+    }
+    if let Some(marketplace) = address(MARKETPLACE_CONTRACT) {
+        links.push((MARKETPLACE_CONTRACT, marketplace, "car_contract", nft));
+        links.push((MARKETPLACE_CONTRACT, marketplace, "part_contract", part));
+    }
+    for (name, contract, key, expected) in links {
+        compare(&mut drifts, name, key, expected, raw_string(xiond, contract, key));
+    }
+
+    Ok(drifts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NetworkProfile;
+    use crate::runner::{CommandRunner, CommandSpec, FakeXiond};
+    use std::time::Duration;
+
+    fn test_profile() -> NetworkProfile {
+        NetworkProfile::from_toml(
+            "chain_id = \"xion-local-1\"\nnode_url = \"http://localhost:26657\"\ngas_prices = \"0.1uxion\"\nbuild = \"cargo\"\n",
+        ).unwrap()
+    }
+
+    #[test]
+    fn verify_reports_drift_after_relink() {
+        let profile = test_profile();
+        let fake = FakeXiond::new("xion1deployer", 7);
+        let xiond = Xiond { profile: &profile, runner: &fake, poll_interval: Duration::ZERO };
+
+        let manifest = crate::deploy(&xiond, 0).unwrap();
+        assert_eq!(Vec::<Drift>::new(), verify(&xiond, &manifest).unwrap());
+
+        // Alguien vuelve a enlazar el contrato de partes con otro contrato de carros
+        let part = manifest.address_of(CAR_PART_CONTRACT).unwrap();
+        let relink = CommandSpec::new("xiond", vec![
+            "tx".to_string(),
+            "wasm".to_string(),
+            "execute".to_string(),
+            part.to_string(),
+            json!({ "set_car_contract": { "address": "xion1otro" } }).to_string(),
+        ]);
+        fake.run(&relink).unwrap();
+
+        let drifts = verify(&xiond, &manifest).unwrap();
+        assert_eq!(1, drifts.len());
+        assert_eq!("car_contract", drifts[0].check);
+        assert_eq!("xion1otro", drifts[0].actual);
+
+        // También se detecta un contrato de carreras que reporta a otros perfiles
+        let race = manifest.address_of(RACE_CONTRACT).unwrap();
+        let relink = CommandSpec::new("xiond", vec![
+            "tx".to_string(),
+            "wasm".to_string(),
+            "execute".to_string(),
+            race.to_string(),
+            json!({ "set_profile_contract": { "address": "xion1otro" } }).to_string(),
+        ]);
+        fake.run(&relink).unwrap();
+        let drifts = verify(&xiond, &manifest).unwrap();
+        assert!(drifts.iter().any(|d| d.contract == RACE_CONTRACT && d.check == "profile_contract"));
+    }
+
+    #[test]
+    fn verify_reports_missing_contract() {
+        let profile = test_profile();
+        let fake = FakeXiond::new("xion1deployer", 7);
+        let xiond = Xiond { profile: &profile, runner: &fake, poll_interval: Duration::ZERO };
+        let mut manifest = crate::deploy(&xiond, 0).unwrap();
+
+        // Un manifiesto que apunta a un CODE_ID distinto y a un contrato inexistente
+        manifest.contracts.get_mut(CAR_PART_CONTRACT).unwrap().code_id = 99;
+        manifest.contracts.get_mut(CAR_NFT_CONTRACT).unwrap().address = "xion1inexistente".to_string();
+        manifest.config.mint_price = "250".to_string();
+
+        let drifts = verify(&xiond, &manifest).unwrap();
+        let checks: Vec<(&str, &str)> = drifts.iter().map(|d| (d.contract.as_str(), d.check.as_str())).collect();
+        assert!(checks.contains(&(CAR_PART_CONTRACT, "code_id")));
+        assert!(checks.contains(&(CAR_NFT_CONTRACT, "code_id")));
+        assert!(checks.contains(&(CAR_PART_CONTRACT, "car_contract")));
+        assert!(checks.contains(&(CAR_NFT_CONTRACT, "mint_price")));
+        assert!(drifts.iter().any(|d| d.actual.starts_with("error:")));
+
+        // Con el xiond simulado a partir del manifiesto no hay diferencias
+        let seeded = FakeXiond::with_manifest(&manifest);
+        let xiond = Xiond { profile: &profile, runner: &seeded, poll_interval: Duration::ZERO };
+        assert!(verify(&xiond, &manifest).unwrap().is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use super::car_part::{ExecuteMsg as CarPartExecuteMsg, PartType};
 use cosmwasm_std::{
     entry_point, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
-    SubMsg, Uint128, WasmMsg, BankMsg, Coin, Reply,
+    SubMsg, Uint128, WasmMsg, BankMsg, Reply,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -249,7 +249,7 @@ fn execute_mint_car(
     let mut has_wheels = false;
 
     // Preparar los arrays para la composición del carro
-    let mut part_ids = vec![0u64; 3];
+    let part_ids = vec![0u64; 3];
     let mut slot_occupied = vec![false; 3];
 
     // Obtener la dirección del contrato de partes
@@ -328,7 +328,7 @@ fn execute_mint_car(
 fn execute_unequip_part(
     deps: DepsMut,
     _env: Env,
-    info: MessageInfo,
+    _info: MessageInfo,
     contract: CarNftContract,
     car_id: u64,
     part_id: u64,
@@ -383,7 +383,7 @@ fn execute_unequip_part(
 fn execute_set_mint_price(
     deps: DepsMut,
     _env: Env,
-    info: MessageInfo,
+    _info: MessageInfo,
     contract: CarNftContract,
     price: Uint128,
 ) -> StdResult<Response> {
@@ -401,7 +401,7 @@ fn execute_set_mint_price(
 fn execute_equip_part(
     deps: DepsMut,
     _env: Env,
-    info: MessageInfo,
+    _info: MessageInfo,
     contract: CarNftContract,
     car_id: u64,
     part_id: u64,
@@ -447,9 +447,9 @@ fn execute_equip_part(
 }
 
 fn execute_replace_part(
-    mut deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
     contract: CarNftContract,
     car_id: u64,
     old_part_id: u64,
@@ -651,6 +651,6 @@ mod tests {
         };
 
         let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(2, res.attributes.len());
+        assert_eq!(4, res.attributes.len());
     }
 } 
\ No newline at end of file
@@ -119,7 +119,7 @@ pub enum QueryMsg {
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    info: MessageInfo,
+    _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
     let contract = CarPartContract::default();
@@ -196,7 +196,7 @@ pub fn query(
 // Funciones de ejecución
 fn execute_set_car_contract(
     deps: DepsMut,
-    info: MessageInfo,
+    _info: MessageInfo,
     contract: CarPartContract,
     address: String,
 ) -> StdResult<Response> {
@@ -209,6 +209,7 @@ fn execute_set_car_contract(
         .add_attribute("car_contract", address))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_mint(
     deps: DepsMut,
     _env: Env,
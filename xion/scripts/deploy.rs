@@ -4,7 +4,6 @@ use std::fs;
 use serde_json::Value;
 
 const WALLET_NAME: &str = "wallet";
-const WALLET_PASSWORD: &str = "saritu12";
 const CHAIN_ID: &str = "xion-testnet-1";
 const NODE_URL: &str = "https://rpc.xion-testnet-1.burnt.com:443";
 const GAS_PRICES: &str = "0.1uxion";
@@ -16,9 +15,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📦 Optimizando el contrato...");
     println!("   Directorio actual: {:?}", env::current_dir()?);
     
-    let docker_cmd = Command::new("sudo")
+    // Docker rootless: no se necesita sudo. Los secretos del keyring se leen
+    // del entorno (ver xion-contracts/scripts/config.rs), nunca del código.
+    let docker_cmd = Command::new("docker")
         .args(&[
-            "docker", "run", "--rm",
+            "run", "--rm",
             "-v", format!("{}:/code", env::current_dir()?.display()).as_str(),
             "--mount", "type=volume,source=target_cache,target=/code/target",
             "--mount", "type=volume,source=registry_cache,target=/usr/local/cargo/registry",